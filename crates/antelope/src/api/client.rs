@@ -1,11 +1,15 @@
 use std::fmt::{Debug, Display, Formatter};
 
 pub use crate::api::default_provider::DefaultProvider;
-use crate::api::util::transact;
+use crate::api::signer::Signer;
+use crate::api::transaction_builder::TransactionBuilder;
+use crate::api::transaction_watcher::TransactionWatcher;
+use crate::api::util::{transact, transact_context_free};
 use crate::api::v1::chain::ChainAPI;
 use crate::api::v1::structs::{ClientError, SendTransactionResponse, SendTransactionResponseError};
 use crate::chain::action::Action;
-use crate::chain::private_key::PrivateKey;
+use crate::chain::checksum::Checksum256;
+use crate::chain::transaction::SignedTransaction;
 
 pub enum HTTPMethod {
     GET,
@@ -53,11 +57,51 @@ impl<P: Provider> APIClient<P> {
         })
     }
 
-    pub async fn transact(
+    /// Starts building a transaction, see `TransactionBuilder`.
+    pub fn transaction_builder(&self) -> TransactionBuilder<'_, P> {
+        TransactionBuilder::new(self)
+    }
+
+    /// Watches the status of the transaction `id`, see `TransactionWatcher`.
+    pub fn watch_transaction(&self, id: Checksum256) -> TransactionWatcher<'_, P> {
+        TransactionWatcher::new(self, id)
+    }
+
+    /// Sends `trx` and returns a watcher for it which resubmits it if it is
+    /// dropped before its expiration.
+    pub async fn send_and_watch(
+        &self,
+        trx: SignedTransaction,
+    ) -> Result<TransactionWatcher<'_, P>, ClientError<SendTransactionResponseError>> {
+        let id = Checksum256::from_bytes(&trx.transaction.id())?;
+        self.v1_chain.send_transaction(trx.clone()).await?;
+        Ok(self.watch_transaction(id).resubmit(trx))
+    }
+
+    /// Signs `actions` with `signer` and sends them. A `PrivateKey` can be
+    /// passed as the signer.
+    pub async fn transact<S: Signer>(
+        &self,
+        actions: Vec<Action>,
+        signer: S,
+    ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
+        transact(self, actions, signer).await
+    }
+
+    pub async fn transact_context_free<S: Signer>(
         &self,
+        context_free_actions: Vec<Action>,
         actions: Vec<Action>,
-        private_key: PrivateKey,
+        context_free_data: Vec<Vec<u8>>,
+        signer: S,
     ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
-        transact(self, actions, private_key).await
+        transact_context_free(
+            self,
+            context_free_actions,
+            actions,
+            context_free_data,
+            signer,
+        )
+        .await
     }
 }
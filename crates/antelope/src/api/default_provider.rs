@@ -12,16 +12,15 @@ pub struct DefaultProvider {
 impl DefaultProvider {
     pub fn new(base_url: String, timeout: Option<u64>) -> Result<Self, String> {
         let mut client_builder = Client::builder();
-        if timeout.is_some() {
-            client_builder =
-                client_builder.timeout(std::time::Duration::from_secs(timeout.unwrap()));
+        if let Some(timeout) = timeout {
+            client_builder = client_builder.timeout(std::time::Duration::from_secs(timeout));
         }
         let client = client_builder.build();
         if client.is_err() {
             let err = client.err();
             let mut err_message = String::from("Error building http client");
-            if err.is_some() {
-                err_message = err.unwrap().to_string();
+            if let Some(err) = err {
+                err_message = err.to_string();
             }
             return Err(err_message);
         }
@@ -63,8 +62,7 @@ impl Provider for DefaultProvider {
 
     async fn post(&self, path: String, body: Option<String>) -> Result<String, String> {
         let mut builder = self.client.post(self.base_url.to_string() + &path);
-        if body.is_some() {
-            let body_str = body.unwrap();
+        if let Some(body_str) = body {
             debug!("POST {} {}", self.base_url.to_string() + &path, body_str);
             builder = builder.body(body_str);
         }
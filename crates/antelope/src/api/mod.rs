@@ -1,5 +1,9 @@
 pub mod client;
 pub mod default_provider;
+pub mod signer;
 pub mod system;
+pub mod token;
+pub mod transaction_builder;
+pub mod transaction_watcher;
 mod util;
 pub mod v1;
@@ -0,0 +1,172 @@
+use serde::Deserialize;
+
+use crate::api::client::Provider;
+use crate::chain::{
+    checksum::Checksum256,
+    private_key::PrivateKey,
+    public_key::PublicKey,
+    signature::Signature,
+    transaction::{SignedTransaction, Transaction},
+};
+
+/// Signs transactions for a chain.
+///
+/// The signatures cover the chain id, the transaction and its context free
+/// data, see `Transaction::signing_data_with_cfd`.
+#[async_trait::async_trait]
+pub trait Signer: Send + Sync {
+    async fn sign(
+        &self,
+        chain_id: &Checksum256,
+        transaction: &Transaction,
+        context_free_data: &[Vec<u8>],
+    ) -> Result<Vec<Signature>, String>;
+}
+
+#[async_trait::async_trait]
+impl<S: Signer + ?Sized> Signer for &S {
+    async fn sign(
+        &self,
+        chain_id: &Checksum256,
+        transaction: &Transaction,
+        context_free_data: &[Vec<u8>],
+    ) -> Result<Vec<Signature>, String> {
+        (**self)
+            .sign(chain_id, transaction, context_free_data)
+            .await
+    }
+}
+
+/// Signs with a single key.
+#[async_trait::async_trait]
+impl Signer for PrivateKey {
+    async fn sign(
+        &self,
+        chain_id: &Checksum256,
+        transaction: &Transaction,
+        context_free_data: &[Vec<u8>],
+    ) -> Result<Vec<Signature>, String> {
+        let data = transaction.signing_data_with_cfd(&chain_id.data, context_free_data);
+        Ok(vec![self.sign_message(&data)])
+    }
+}
+
+/// Signs with the keys of a set that a transaction needs, for transactions
+/// with several authorizers.
+///
+/// nodeos rejects signatures that no authority requires, so the ring asks
+/// the chain at `provider` which of its keys are required through
+/// `/v1/chain/get_required_keys` and signs with only those.
+#[derive(Clone, Default)]
+pub struct KeyRing<P: Provider> {
+    provider: P,
+    keys: Vec<PrivateKey>,
+}
+
+impl<P: Provider> KeyRing<P> {
+    /// Creates a ring of `keys` looking up required keys through `provider`.
+    pub fn new(provider: P, keys: Vec<PrivateKey>) -> Self {
+        Self { provider, keys }
+    }
+
+    /// Adds a key, unless one with the same public key is already held.
+    pub fn add(&mut self, key: PrivateKey) {
+        let public_key = key.to_public();
+        if !self.keys.iter().any(|k| k.to_public() == public_key) {
+            self.keys.push(key);
+        }
+    }
+
+    pub fn public_keys(&self) -> Vec<PublicKey> {
+        self.keys.iter().map(PrivateKey::to_public).collect()
+    }
+
+    /// Returns the keys of the ring that `transaction` needs signatures by.
+    pub async fn required_keys(&self, transaction: &Transaction) -> Result<Vec<PublicKey>, String> {
+        let payload = serde_json::json!({
+            "transaction": transaction,
+            "available_keys": self.public_keys(),
+        });
+        let response = self
+            .provider
+            .post(
+                String::from("/v1/chain/get_required_keys"),
+                Some(payload.to_string()),
+            )
+            .await?;
+        serde_json::from_str::<RequiredKeysResponse>(&response)
+            .map(|r| r.required_keys)
+            .map_err(|e| format!("Failed to parse required keys: {}", e))
+    }
+}
+
+#[async_trait::async_trait]
+impl<P: Provider> Signer for KeyRing<P> {
+    async fn sign(
+        &self,
+        chain_id: &Checksum256,
+        transaction: &Transaction,
+        context_free_data: &[Vec<u8>],
+    ) -> Result<Vec<Signature>, String> {
+        let required = self.required_keys(transaction).await?;
+        let data = transaction.signing_data_with_cfd(&chain_id.data, context_free_data);
+        Ok(self
+            .keys
+            .iter()
+            .filter(|k| required.contains(&k.to_public()))
+            .map(|k| k.sign_message(&data))
+            .collect())
+    }
+}
+
+#[derive(Deserialize)]
+struct RequiredKeysResponse {
+    required_keys: Vec<PublicKey>,
+}
+
+#[derive(Deserialize)]
+struct SignTransactionResponse {
+    signatures: Vec<Signature>,
+}
+
+/// Signs through a separate signing process speaking the keosd wallet API,
+/// `/v1/wallet/sign_transaction`, so the keys never enter this process.
+#[derive(Debug, Default, Clone)]
+pub struct RemoteSigner<P: Provider> {
+    provider: P,
+    public_keys: Vec<PublicKey>,
+}
+
+impl<P: Provider> RemoteSigner<P> {
+    /// Creates a signer asking `provider` for signatures by `public_keys`.
+    pub fn new(provider: P, public_keys: Vec<PublicKey>) -> Self {
+        Self {
+            provider,
+            public_keys,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<P: Provider> Signer for RemoteSigner<P> {
+    async fn sign(
+        &self,
+        chain_id: &Checksum256,
+        transaction: &Transaction,
+        context_free_data: &[Vec<u8>],
+    ) -> Result<Vec<Signature>, String> {
+        let signed = SignedTransaction::new(transaction.clone())
+            .with_context_free_data(context_free_data.to_vec());
+        let payload = serde_json::json!([signed, self.public_keys, chain_id]);
+        let response = self
+            .provider
+            .post(
+                String::from("/v1/wallet/sign_transaction"),
+                Some(payload.to_string()),
+            )
+            .await?;
+        serde_json::from_str::<SignTransactionResponse>(&response)
+            .map(|r| r.signatures)
+            .map_err(|e| format!("Failed to parse signatures: {}", e))
+    }
+}
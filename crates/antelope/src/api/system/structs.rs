@@ -3,8 +3,7 @@ use crate::chain::authority::Authority;
 use crate::chain::binary_extension::BinaryExtension;
 use crate::chain::name::Name;
 use crate::chain::public_key::PublicKey;
-use crate::chain::{Decoder, Encoder, Packer};
-use antelope_client_macros::StructPacker;
+use antelope_client_macros::{AbiType, StructPacker};
 
 pub struct CreateAccountParams {
     pub name: Name,
@@ -34,7 +33,8 @@ impl CreateAccountParams {
     }
 }
 
-#[derive(Debug, Clone, StructPacker)]
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "transfer")]
 pub struct TransferAction {
     pub from: Name,
     pub to: Name,
@@ -42,7 +42,8 @@ pub struct TransferAction {
     pub memo: String,
 }
 
-#[derive(Debug, Clone, StructPacker)]
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "newaccount")]
 pub struct NewAccountAction {
     pub creator: Name,
     pub name: Name,
@@ -50,14 +51,16 @@ pub struct NewAccountAction {
     pub active: Authority,
 }
 
-#[derive(Debug, Clone, StructPacker)]
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "buyrambytes")]
 pub struct BuyRamBytesAction {
     pub payer: Name,
     pub receiver: Name,
     pub bytes: u32,
 }
 
-#[derive(Debug, Clone, StructPacker)]
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "delegatebw")]
 pub struct DelegateBandwidthAction {
     pub from: Name,
     pub receiver: Name,
@@ -66,7 +69,8 @@ pub struct DelegateBandwidthAction {
     pub transfer: bool,
 }
 
-#[derive(Debug, Clone, StructPacker)]
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "setcode")]
 pub struct SetCodeAction {
     pub account: Name,
     pub vmtype: u8,
@@ -75,7 +79,8 @@ pub struct SetCodeAction {
     pub memo: BinaryExtension<String>,
 }
 
-#[derive(Debug, Clone, StructPacker)]
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "setabi")]
 pub struct SetAbiAction {
     pub account: Name,
     pub abi: Vec<u8>,
@@ -0,0 +1,214 @@
+pub mod structs;
+
+use crate::api::client::{APIClient, Provider};
+use crate::api::signer::Signer;
+use crate::api::token::structs::{
+    CloseAction, CreateAction, IssueAction, OpenAction, RetireAction,
+};
+use crate::api::v1::structs::{
+    ClientError, CurrencyStats, SendTransactionResponse, SendTransactionResponseError,
+};
+use crate::chain::action::{Action, PermissionLevel};
+use crate::chain::asset::{Asset, SymbolCode};
+use crate::chain::name::Name;
+use crate::name;
+use crate::serializer::Packer;
+
+/// Manages the tokens of an eosio.token contract. The precision of a token
+/// is looked up from its stats, so amounts are given as decimal strings such
+/// as `1.5`.
+#[derive(Debug, Default, Clone)]
+pub struct TokenAPI<T: Provider> {
+    api_client: APIClient<T>,
+    contract: Name,
+}
+
+impl<T: Provider> TokenAPI<T> {
+    /// Creates a TokenAPI for the eosio.token account.
+    pub fn new(api_client: APIClient<T>) -> Self {
+        Self::with_contract(api_client, name!("eosio.token"))
+    }
+
+    /// Creates a TokenAPI for a token contract deployed on `contract`.
+    pub fn with_contract(api_client: APIClient<T>, contract: Name) -> Self {
+        TokenAPI {
+            api_client,
+            contract,
+        }
+    }
+
+    /// Returns the stats of the token `symbol`.
+    pub async fn stats(
+        &self,
+        symbol: SymbolCode,
+    ) -> Result<CurrencyStats, ClientError<SendTransactionResponseError>> {
+        let stats = self
+            .api_client
+            .v1_chain
+            .get_currency_stats(self.contract, symbol)
+            .await
+            .map_err(|e| ClientError::simple(format!("Failed to get stats of {symbol}: {e:?}")))?;
+        stats.ok_or_else(|| {
+            ClientError::simple(format!("Unknown token {symbol} on {}", self.contract))
+        })
+    }
+
+    /// Returns `amount` as an asset with the precision of the token `symbol`.
+    pub async fn asset(
+        &self,
+        amount: &str,
+        symbol: SymbolCode,
+    ) -> Result<Asset, ClientError<SendTransactionResponseError>> {
+        let stats = self.stats(symbol).await?;
+        Ok(Asset::from_amount(amount, stats.supply.symbol())?)
+    }
+
+    /// Returns the balance of `account` in the token `symbol`, zero if the
+    /// account has no balance.
+    pub async fn balance(
+        &self,
+        account: Name,
+        symbol: SymbolCode,
+    ) -> Result<Asset, ClientError<SendTransactionResponseError>> {
+        let balances = self
+            .api_client
+            .v1_chain
+            .get_currency_balance(self.contract, account, Some(symbol))
+            .await
+            .map_err(|e| {
+                ClientError::simple(format!("Failed to get balance of {account}: {e:?}"))
+            })?;
+        match balances.into_iter().next() {
+            Some(balance) => Ok(balance),
+            None => Ok(Asset::new(0, self.stats(symbol).await?.supply.symbol())),
+        }
+    }
+
+    /// Creates a token, signed by the token contract. The precision of the
+    /// token is the one of `maximum_supply`.
+    pub async fn create<S: Signer>(
+        &self,
+        issuer: Name,
+        maximum_supply: Asset,
+        signer: S,
+    ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
+        self.transact(
+            self.contract,
+            name!("create"),
+            CreateAction {
+                issuer,
+                maximum_supply,
+            },
+            signer,
+        )
+        .await
+    }
+
+    /// Issues `amount` of the token `symbol` to its issuer, signed by the
+    /// issuer.
+    pub async fn issue<S: Signer>(
+        &self,
+        amount: &str,
+        symbol: SymbolCode,
+        memo: String,
+        signer: S,
+    ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
+        let stats = self.stats(symbol).await?;
+        let quantity = Asset::from_amount(amount, stats.supply.symbol())?;
+        self.transact(
+            stats.issuer,
+            name!("issue"),
+            IssueAction {
+                to: stats.issuer,
+                quantity,
+                memo,
+            },
+            signer,
+        )
+        .await
+    }
+
+    /// Takes `amount` of the token `symbol` out of circulation from the
+    /// balance of the issuer, signed by the issuer.
+    pub async fn retire<S: Signer>(
+        &self,
+        amount: &str,
+        symbol: SymbolCode,
+        memo: String,
+        signer: S,
+    ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
+        let stats = self.stats(symbol).await?;
+        let quantity = Asset::from_amount(amount, stats.supply.symbol())?;
+        self.transact(
+            stats.issuer,
+            name!("retire"),
+            RetireAction { quantity, memo },
+            signer,
+        )
+        .await
+    }
+
+    /// Opens a zero balance of the token `symbol` for `owner`, with its RAM
+    /// paid and signed for by `ram_payer`.
+    pub async fn open<S: Signer>(
+        &self,
+        owner: Name,
+        symbol: SymbolCode,
+        ram_payer: Name,
+        signer: S,
+    ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
+        let stats = self.stats(symbol).await?;
+        self.transact(
+            ram_payer,
+            name!("open"),
+            OpenAction {
+                owner,
+                symbol: stats.supply.symbol(),
+                ram_payer,
+            },
+            signer,
+        )
+        .await
+    }
+
+    /// Closes the zero balance of the token `symbol` of `owner`, signed by
+    /// the owner.
+    pub async fn close<S: Signer>(
+        &self,
+        owner: Name,
+        symbol: SymbolCode,
+        signer: S,
+    ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
+        let stats = self.stats(symbol).await?;
+        self.transact(
+            owner,
+            name!("close"),
+            CloseAction {
+                owner,
+                symbol: stats.supply.symbol(),
+            },
+            signer,
+        )
+        .await
+    }
+
+    async fn transact<D: Packer, S: Signer>(
+        &self,
+        actor: Name,
+        action: Name,
+        data: D,
+        signer: S,
+    ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
+        self.api_client
+            .transact(
+                vec![Action::new(
+                    self.contract,
+                    action,
+                    PermissionLevel::new(actor, name!("active")),
+                    data,
+                )],
+                signer,
+            )
+            .await
+    }
+}
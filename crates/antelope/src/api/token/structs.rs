@@ -0,0 +1,40 @@
+use crate::chain::asset::{Asset, Symbol};
+use crate::chain::name::Name;
+use antelope_client_macros::{AbiType, StructPacker};
+
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "create")]
+pub struct CreateAction {
+    pub issuer: Name,
+    pub maximum_supply: Asset,
+}
+
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "issue")]
+pub struct IssueAction {
+    pub to: Name,
+    pub quantity: Asset,
+    pub memo: String,
+}
+
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "retire")]
+pub struct RetireAction {
+    pub quantity: Asset,
+    pub memo: String,
+}
+
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "open")]
+pub struct OpenAction {
+    pub owner: Name,
+    pub symbol: Symbol,
+    pub ram_payer: Name,
+}
+
+#[derive(Debug, Clone, StructPacker, AbiType)]
+#[abi(name = "close")]
+pub struct CloseAction {
+    pub owner: Name,
+    pub symbol: Symbol,
+}
@@ -0,0 +1,161 @@
+use crate::api::client::{APIClient, Provider};
+use crate::api::signer::Signer;
+use crate::api::v1::structs::{ClientError, GetInfoResponse};
+use crate::chain::{
+    action::Action,
+    block_id::BlockId,
+    transaction::{SignedTransaction, Transaction, TransactionHeader},
+    varint::VarUint32,
+};
+
+/// The block a transaction references for TAPOS.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum ReferenceBlock {
+    /// The head block at the time the transaction is built.
+    Head,
+    /// The last irreversible block at the time the transaction is built.
+    #[default]
+    Irreversible,
+    /// A given block.
+    Block(BlockId),
+}
+
+/// Builds a transaction against the chain of an `APIClient`, fetching the
+/// chain info for TAPOS and the expiration when it is built.
+///
+/// The defaults match `APIClient::transact`: TAPOS from the last irreversible
+/// block, an expiration 90 seconds after the head block time and no resource
+/// limits or delay.
+#[derive(Debug)]
+pub struct TransactionBuilder<'a, P: Provider> {
+    client: &'a APIClient<P>,
+    reference_block: ReferenceBlock,
+    expire_seconds: u32,
+    max_net_usage_words: u32,
+    max_cpu_usage_ms: u8,
+    delay_sec: u32,
+    context_free_actions: Vec<Action>,
+    actions: Vec<Action>,
+    context_free_data: Vec<Vec<u8>>,
+}
+
+impl<'a, P: Provider> TransactionBuilder<'a, P> {
+    pub fn new(client: &'a APIClient<P>) -> Self {
+        Self {
+            client,
+            reference_block: ReferenceBlock::default(),
+            expire_seconds: 90,
+            max_net_usage_words: 0,
+            max_cpu_usage_ms: 0,
+            delay_sec: 0,
+            context_free_actions: vec![],
+            actions: vec![],
+            context_free_data: vec![],
+        }
+    }
+
+    /// Sets the block referenced for TAPOS.
+    pub fn reference_block(mut self, reference_block: ReferenceBlock) -> Self {
+        self.reference_block = reference_block;
+        self
+    }
+
+    /// Sets the expiration, in seconds after the head block time.
+    pub fn expire_seconds(mut self, seconds: u32) -> Self {
+        self.expire_seconds = seconds;
+        self
+    }
+
+    /// Sets the NET limit of the transaction in 8 byte words, 0 for none.
+    pub fn max_net_usage_words(mut self, words: u32) -> Self {
+        self.max_net_usage_words = words;
+        self
+    }
+
+    /// Sets the CPU limit of the transaction in milliseconds, 0 for none.
+    pub fn max_cpu_usage_ms(mut self, ms: u8) -> Self {
+        self.max_cpu_usage_ms = ms;
+        self
+    }
+
+    /// Sets the number of seconds to delay the transaction by.
+    pub fn delay_sec(mut self, seconds: u32) -> Self {
+        self.delay_sec = seconds;
+        self
+    }
+
+    /// Adds an action. Actions may have different authorizers, each of which
+    /// must sign the transaction.
+    pub fn action(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Adds several actions.
+    pub fn actions(mut self, actions: impl IntoIterator<Item = Action>) -> Self {
+        self.actions.extend(actions);
+        self
+    }
+
+    /// Adds a context free action.
+    pub fn context_free_action(mut self, action: Action) -> Self {
+        self.context_free_actions.push(action);
+        self
+    }
+
+    /// Adds several context free actions.
+    pub fn context_free_actions(mut self, actions: impl IntoIterator<Item = Action>) -> Self {
+        self.context_free_actions.extend(actions);
+        self
+    }
+
+    /// Sets the context free data, used when the transaction is signed.
+    pub fn context_free_data(mut self, context_free_data: Vec<Vec<u8>>) -> Self {
+        self.context_free_data = context_free_data;
+        self
+    }
+
+    /// Builds the unsigned transaction.
+    pub async fn build(&self) -> Result<Transaction, ClientError<()>> {
+        let info = self.client.v1_chain.get_info().await?;
+        self.transaction(&info)
+    }
+
+    /// Builds the transaction with its context free data and signs it with
+    /// `signer`.
+    pub async fn build_signed<S: Signer + ?Sized>(
+        &self,
+        signer: &S,
+    ) -> Result<SignedTransaction, ClientError<()>> {
+        let info = self.client.v1_chain.get_info().await?;
+        let transaction = self.transaction(&info)?;
+        let signatures = signer
+            .sign(&info.chain_id, &transaction, &self.context_free_data)
+            .await?;
+        let mut signed = SignedTransaction::new(transaction)
+            .with_context_free_data(self.context_free_data.clone());
+        signed.signatures = signatures;
+        Ok(signed)
+    }
+
+    fn transaction(&self, info: &GetInfoResponse) -> Result<Transaction, ClientError<()>> {
+        let block_id = match &self.reference_block {
+            ReferenceBlock::Head => &info.head_block_id,
+            ReferenceBlock::Irreversible => &info.last_irreversible_block_id,
+            ReferenceBlock::Block(block_id) => block_id,
+        };
+        Ok(Transaction {
+            header: TransactionHeader {
+                expiration: info.expiration(self.expire_seconds)?,
+                ref_block_num: block_id.ref_block_num()?,
+                ref_block_prefix: block_id.ref_block_prefix()?,
+                max_net_usage_words: VarUint32::new(self.max_net_usage_words),
+                max_cpu_usage_ms: self.max_cpu_usage_ms,
+                delay_sec: VarUint32::new(self.delay_sec),
+            },
+            context_free_actions: self.context_free_actions.clone(),
+            actions: self.actions.clone(),
+            extension: vec![],
+        })
+    }
+}
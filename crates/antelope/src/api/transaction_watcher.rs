@@ -0,0 +1,282 @@
+use std::time::Duration;
+
+use futures_util::{stream, Stream, StreamExt};
+use tokio::time::Instant;
+
+use crate::api::client::{APIClient, Provider};
+use crate::api::v1::structs::{
+    ClientError, ErrorResponse, GetTransactionStatusResponse, SendTransactionResponseError,
+    TransactionState,
+};
+use crate::chain::{checksum::Checksum256, transaction::SignedTransaction};
+
+/// An event seen while watching a transaction.
+#[derive(Debug)]
+pub enum TransactionEvent {
+    /// The transaction moved to a new state.
+    State(GetTransactionStatusResponse),
+    /// The transaction was dropped or forked out before its expiration and
+    /// was sent again.
+    Resubmitted,
+}
+
+#[derive(Debug)]
+pub enum WatchError {
+    /// The transaction did not finish in time, with the last state seen.
+    Timeout(Option<TransactionState>),
+    /// The transaction expired without being included in a block.
+    Expired,
+    /// The transaction failed.
+    Failed(GetTransactionStatusResponse),
+    /// Polling `get_transaction_status` failed.
+    Status(ClientError<ErrorResponse>),
+    /// Sending the transaction again failed.
+    Resubmit(ClientError<SendTransactionResponseError>),
+}
+
+/// Follows a transaction through `get_transaction_status` until it is
+/// irreversible, has failed or expired, or the timeout is reached.
+///
+/// With `resubmit`, a transaction is sent again before its expiration as
+/// soon as it is forked out, or once it has been unknown to the node for the
+/// resubmit delay. It is sent at most once per resubmit delay, and at most
+/// three times by default.
+pub struct TransactionWatcher<'a, P: Provider> {
+    client: &'a APIClient<P>,
+    id: Checksum256,
+    transaction: Option<SignedTransaction>,
+    resubmissions_left: u32,
+    resubmit_after: Duration,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl<'a, P: Provider> TransactionWatcher<'a, P> {
+    pub fn new(client: &'a APIClient<P>, id: Checksum256) -> Self {
+        Self {
+            client,
+            id,
+            transaction: None,
+            resubmissions_left: 3,
+            resubmit_after: Duration::from_secs(3),
+            poll_interval: Duration::from_millis(500),
+            timeout: Duration::from_secs(180),
+        }
+    }
+
+    /// Sends `transaction` again when it is dropped before its expiration.
+    pub fn resubmit(mut self, transaction: SignedTransaction) -> Self {
+        self.transaction = Some(transaction);
+        self
+    }
+
+    /// Sets how many times the transaction may be sent again.
+    pub fn max_resubmissions(mut self, max_resubmissions: u32) -> Self {
+        self.resubmissions_left = max_resubmissions;
+        self
+    }
+
+    /// Sets how long a transaction stays unknown before it is sent again, and
+    /// the least time between two resubmissions, three seconds by default.
+    pub fn resubmit_after(mut self, resubmit_after: Duration) -> Self {
+        self.resubmit_after = resubmit_after;
+        self
+    }
+
+    /// Sets the time between two status requests, half a second by default.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Sets how long to watch for, three minutes by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the state transitions and resubmissions of the transaction.
+    /// The stream ends after the transaction is irreversible or has failed,
+    /// or after an error.
+    pub fn stream(self) -> impl Stream<Item = Result<TransactionEvent, WatchError>> + 'a {
+        let watch = Watch {
+            deadline: Instant::now() + self.timeout,
+            watcher: self,
+            last_state: None,
+            dropped_since: None,
+            polled: false,
+            resubmit_pending: false,
+            done: false,
+        };
+        stream::unfold(watch, |mut watch| async move {
+            let event = watch.next_event().await?;
+            Some((event, watch))
+        })
+    }
+
+    /// Waits until the transaction reaches `state`, or a later one: a
+    /// transaction waited on to be `InBlock` may already be `Irreversible`.
+    pub async fn wait_for(
+        self,
+        state: TransactionState,
+    ) -> Result<GetTransactionStatusResponse, WatchError> {
+        let mut events = Box::pin(self.stream());
+        let mut last_state = None;
+        while let Some(event) = events.next().await {
+            if let TransactionEvent::State(status) = event? {
+                if status.state == TransactionState::Failed {
+                    return Err(WatchError::Failed(status));
+                }
+                if reached(status.state, state) {
+                    return Ok(status);
+                }
+                last_state = Some(status.state);
+            }
+        }
+        Err(WatchError::Timeout(last_state))
+    }
+
+    /// Waits until the transaction is irreversible.
+    pub async fn wait_for_irreversible(self) -> Result<GetTransactionStatusResponse, WatchError> {
+        self.wait_for(TransactionState::Irreversible).await
+    }
+}
+
+fn progress(state: TransactionState) -> Option<u8> {
+    match state {
+        TransactionState::LocallyApplied => Some(0),
+        TransactionState::InBlock => Some(1),
+        TransactionState::Irreversible => Some(2),
+        _ => None,
+    }
+}
+
+fn reached(state: TransactionState, target: TransactionState) -> bool {
+    match (progress(state), progress(target)) {
+        (Some(state), Some(target)) => state >= target,
+        _ => state == target,
+    }
+}
+
+struct Watch<'a, P: Provider> {
+    watcher: TransactionWatcher<'a, P>,
+    deadline: Instant,
+    last_state: Option<TransactionState>,
+    /// When the transaction was last seen dropped, or last sent again.
+    dropped_since: Option<Instant>,
+    polled: bool,
+    resubmit_pending: bool,
+    done: bool,
+}
+
+impl<P: Provider> Watch<'_, P> {
+    async fn next_event(&mut self) -> Option<Result<TransactionEvent, WatchError>> {
+        if self.done {
+            return None;
+        }
+        loop {
+            if self.resubmit_pending {
+                self.resubmit_pending = false;
+                return Some(self.resubmit().await);
+            }
+            if self.polled {
+                tokio::time::sleep(self.watcher.poll_interval).await;
+            }
+            if Instant::now() >= self.deadline {
+                self.done = true;
+                return Some(Err(WatchError::Timeout(self.last_state)));
+            }
+            self.polled = true;
+
+            let status = match self
+                .watcher
+                .client
+                .v1_chain
+                .get_transaction_status(self.watcher.id)
+                .await
+            {
+                Ok(status) => status,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(WatchError::Status(e)));
+                }
+            };
+            let changed = self.last_state != Some(status.state);
+            self.last_state = Some(status.state);
+
+            match status.state {
+                TransactionState::Irreversible | TransactionState::Failed => {
+                    self.done = true;
+                    return Some(Ok(TransactionEvent::State(status)));
+                }
+                TransactionState::Unknown | TransactionState::ForkedOut => {
+                    if self.is_expired(&status) {
+                        self.done = true;
+                        return Some(Err(WatchError::Expired));
+                    }
+                    self.resubmit_pending = self.watcher.transaction.is_some()
+                        && self.watcher.resubmissions_left > 0
+                        && self.should_resubmit(status.state, changed);
+                }
+                _ => self.dropped_since = None,
+            }
+            if changed {
+                return Some(Ok(TransactionEvent::State(status)));
+            }
+        }
+    }
+
+    /// Whether the head block is past the expiration of the transaction,
+    /// taken from the status or else from the transaction being resubmitted.
+    fn is_expired(&self, status: &GetTransactionStatusResponse) -> bool {
+        let expiration = status.expiration.map(|e| e.elapsed).or_else(|| {
+            self.watcher
+                .transaction
+                .as_ref()
+                .map(|t| u64::from(t.transaction.header.expiration.seconds) * 1_000_000)
+        });
+        match expiration {
+            Some(expiration) => status.head_timestamp.elapsed >= expiration,
+            None => false,
+        }
+    }
+
+    /// Whether a dropped transaction should be sent again: right away when it
+    /// was just forked out, otherwise once it has been dropped for the
+    /// resubmit delay since it was last seen dropped or sent.
+    fn should_resubmit(&mut self, state: TransactionState, changed: bool) -> bool {
+        let now = Instant::now();
+        let since = match self.dropped_since {
+            Some(since) if !changed => since,
+            _ => {
+                self.dropped_since = Some(now);
+                return state == TransactionState::ForkedOut;
+            }
+        };
+        now.duration_since(since) >= self.watcher.resubmit_after
+    }
+
+    async fn resubmit(&mut self) -> Result<TransactionEvent, WatchError> {
+        let Some(transaction) = self.watcher.transaction.clone() else {
+            self.done = true;
+            return Err(WatchError::Resubmit(ClientError::simple(String::from(
+                "No transaction to resubmit",
+            ))));
+        };
+        self.watcher.resubmissions_left -= 1;
+        self.dropped_since = Some(Instant::now());
+        match self
+            .watcher
+            .client
+            .v1_chain
+            .send_transaction(transaction)
+            .await
+        {
+            Ok(_) => Ok(TransactionEvent::Resubmitted),
+            Err(e) => {
+                self.done = true;
+                Err(WatchError::Resubmit(e))
+            }
+        }
+    }
+}
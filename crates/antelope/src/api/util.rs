@@ -1,30 +1,33 @@
 use crate::api::client::{APIClient, Provider};
+use crate::api::signer::Signer;
 use crate::api::v1::structs::{ClientError, SendTransactionResponse, SendTransactionResponseError};
 use crate::chain::action::Action;
-use crate::chain::private_key::PrivateKey;
-use crate::chain::transaction::{SignedTransaction, Transaction};
 
-pub async fn transact<T: Provider>(
+pub async fn transact<T: Provider, S: Signer>(
     api_client: &APIClient<T>,
     actions: Vec<Action>,
-    private_key: PrivateKey,
+    signer: S,
 ) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
-    let info = api_client.v1_chain.get_info().await.unwrap();
-    let trx_header = info.get_transaction_header(90);
-    let trx = Transaction {
-        header: trx_header,
-        context_free_actions: vec![],
-        actions,
-        extension: vec![],
-    };
-
-    let sign_data = trx.signing_data(&info.chain_id.data);
+    transact_context_free(api_client, vec![], actions, vec![], signer).await
+}
 
-    let signed = SignedTransaction {
-        transaction: trx,
-        signatures: vec![private_key.sign_message(&sign_data)],
-        context_free_data: vec![],
-    };
+/// Like `transact`, with context free actions and their context free data,
+/// which is covered by the signature.
+pub async fn transact_context_free<T: Provider, S: Signer>(
+    api_client: &APIClient<T>,
+    context_free_actions: Vec<Action>,
+    actions: Vec<Action>,
+    context_free_data: Vec<Vec<u8>>,
+    signer: S,
+) -> Result<SendTransactionResponse, ClientError<SendTransactionResponseError>> {
+    let signed = api_client
+        .transaction_builder()
+        .context_free_actions(context_free_actions)
+        .actions(actions)
+        .context_free_data(context_free_data)
+        .build_signed(&signer)
+        .await
+        .map_err(|e| ClientError::simple(format!("Failed to build transaction: {e:?}")))?;
 
     api_client.v1_chain.send_transaction(signed).await
 }
@@ -1,12 +1,17 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::str::FromStr;
 
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
 use serde_json::{self, Value};
 
 use crate::api::v1::structs::{
-    ABIResponse, EncodingError, GetBlockResponse, GetTransactionStatusResponse,
-    SendTransaction2Request, ServerError,
+    ABIResponse, ComputeTransactionResponse, CurrencyStats, EncodingError, GetBlockResponse,
+    GetRawAbiResponse, GetTableByScopeParams, GetTableByScopeResponse,
+    GetTransactionStatusResponse, SendTransaction2Request, ServerError, TableScope,
 };
-use crate::chain::checksum::{Checksum160, Checksum256};
+use crate::chain::checksum::Checksum256;
 use crate::{
     api::{
         client::Provider,
@@ -14,17 +19,18 @@ use crate::{
             AccountObject, ClientError, ErrorResponse, GetInfoResponse, GetTableRowsParams,
             GetTableRowsResponse, SendTransaction2Options, SendTransaction2Response,
             SendTransactionResponse, SendTransactionResponse2Error, SendTransactionResponseError,
-            TableIndexType,
         },
     },
     chain::{
+        asset::{Asset, SymbolCode},
         name::Name,
         transaction::{CompressionType, PackedTransaction, SignedTransaction},
         Decoder, Packer,
     },
-    name,
-    serializer::formatter::{JSONObject, ValueTo},
-    util::hex_to_bytes,
+    serializer::{
+        abi_serializer::AbiSerializer,
+        formatter::{JSONObject, ValueTo},
+    },
 };
 
 #[derive(Debug, Default, Clone)]
@@ -72,6 +78,77 @@ impl<T: Provider> ChainAPI<T> {
         }
     }
 
+    /// get_currency_balance returns the balances of `account` in the tokens of
+    /// the `code` contract, or only the balance of `symbol` if given.
+    pub async fn get_currency_balance(
+        &self,
+        code: Name,
+        account: Name,
+        symbol: Option<SymbolCode>,
+    ) -> Result<Vec<Asset>, ClientError<ErrorResponse>> {
+        let mut payload = serde_json::json!({
+            "code": code,
+            "account": account,
+        });
+        if let Some(symbol) = symbol {
+            payload["symbol"] = serde_json::json!(symbol);
+        }
+
+        let result = self
+            .provider
+            .post(
+                String::from("/v1/chain/get_currency_balance"),
+                Some(payload.to_string()),
+            )
+            .await
+            .map_err(ClientError::NETWORK)?;
+
+        match serde_json::from_str::<Vec<Asset>>(&result) {
+            Ok(balances) => Ok(balances),
+            Err(e) => match serde_json::from_str::<ErrorResponse>(&result) {
+                Ok(error_response) => Err(ClientError::server(error_response)),
+                Err(_) => Err(ClientError::encoding(format!(
+                    "Failed to parse response: {}",
+                    e
+                ))),
+            },
+        }
+    }
+
+    /// get_currency_stats returns the supply, maximum supply and issuer of
+    /// the `symbol` token of the `code` contract, or `None` if it does not
+    /// exist.
+    pub async fn get_currency_stats(
+        &self,
+        code: Name,
+        symbol: SymbolCode,
+    ) -> Result<Option<CurrencyStats>, ClientError<ErrorResponse>> {
+        let payload = serde_json::json!({
+            "code": code,
+            "symbol": symbol,
+        });
+
+        let result = self
+            .provider
+            .post(
+                String::from("/v1/chain/get_currency_stats"),
+                Some(payload.to_string()),
+            )
+            .await
+            .map_err(ClientError::NETWORK)?;
+
+        match serde_json::from_str::<HashMap<String, CurrencyStats>>(&result) {
+            Ok(mut stats) => Ok(stats.remove(&symbol.to_string())),
+            Err(e) => match serde_json::from_str::<ErrorResponse>(&result) {
+                Ok(error_response) => Err(ClientError::server(error_response)),
+                Err(_) => Err(ClientError::encoding(format!(
+                    "Failed to parse response: {}",
+                    e
+                ))),
+            },
+        }
+    }
+
     pub async fn get_abi(
         &self,
         account_name: String,
@@ -106,6 +183,38 @@ impl<T: Provider> ChainAPI<T> {
         }
     }
 
+    pub async fn get_raw_abi(
+        &self,
+        account_name: Name,
+    ) -> Result<GetRawAbiResponse, ClientError<ErrorResponse>> {
+        let payload = serde_json::json!({
+            "account_name": account_name.as_string(),
+        });
+
+        let result = self
+            .provider
+            .post(
+                String::from("/v1/chain/get_raw_abi"),
+                Some(payload.to_string()),
+            )
+            .await;
+
+        match result {
+            Ok(response) => match serde_json::from_str::<GetRawAbiResponse>(&response) {
+                Ok(raw_abi) => Ok(raw_abi),
+                Err(_) => match serde_json::from_str::<ErrorResponse>(&response) {
+                    Ok(error_response) => Err(ClientError::SERVER(ServerError {
+                        error: error_response,
+                    })),
+                    Err(_) => Err(ClientError::ENCODING(EncodingError {
+                        message: "Failed to parse JSON".into(),
+                    })),
+                },
+            },
+            Err(msg) => Err(ClientError::NETWORK(msg)),
+        }
+    }
+
     pub async fn get_block(
         &self,
         block_num_or_id: String,
@@ -252,6 +361,59 @@ impl<T: Provider> ChainAPI<T> {
         }
     }
 
+    /// compute_transaction executes a transaction using /v1/chain/compute_transaction
+    /// without including it in a block, to estimate the resources it uses.
+    /// Signatures are not checked, so the transaction may be unsigned.
+    pub async fn compute_transaction(
+        &self,
+        trx: SignedTransaction,
+    ) -> Result<ComputeTransactionResponse, ClientError<SendTransactionResponseError>> {
+        self.execute_transaction("/v1/chain/compute_transaction", trx)
+            .await
+    }
+
+    /// send_read_only_transaction executes a transaction of read-only actions
+    /// using /v1/chain/send_read_only_transaction, to query contracts.
+    pub async fn send_read_only_transaction(
+        &self,
+        trx: SignedTransaction,
+    ) -> Result<ComputeTransactionResponse, ClientError<SendTransactionResponseError>> {
+        self.execute_transaction("/v1/chain/send_read_only_transaction", trx)
+            .await
+    }
+
+    async fn execute_transaction(
+        &self,
+        path: &str,
+        trx: SignedTransaction,
+    ) -> Result<ComputeTransactionResponse, ClientError<SendTransactionResponseError>> {
+        let packed = PackedTransaction::from_signed(trx, CompressionType::ZLIB)
+            .map_err(|_| ClientError::encoding("Failed to pack transaction".into()))?;
+        let payload = serde_json::json!({
+            "transaction": packed.to_json(),
+        });
+
+        let result = self
+            .provider
+            .post(String::from(path), Some(payload.to_string()))
+            .await
+            .map_err(ClientError::NETWORK)?;
+
+        match serde_json::from_str::<ComputeTransactionResponse>(&result) {
+            Ok(response) => match response.processed.except {
+                Some(error) => Err(ClientError::server(error)),
+                None => Ok(response),
+            },
+            Err(e) => match serde_json::from_str::<ErrorResponse>(&result) {
+                Ok(error_response) => Err(ClientError::server(error_response.error)),
+                Err(_) => Err(ClientError::encoding(format!(
+                    "Failed to parse response: {} Raw response was: {}",
+                    e, result
+                ))),
+            },
+        }
+    }
+
     pub async fn get_transaction_status(
         &self,
         trx_id: Checksum256,
@@ -292,10 +454,70 @@ impl<T: Provider> ChainAPI<T> {
     pub async fn get_table_rows<P: Packer + Default>(
         &self,
         params: GetTableRowsParams,
-    ) -> Result<GetTableRowsResponse<P>, ClientError<()>> {
+    ) -> Result<GetTableRowsResponse<P>, ClientError<ErrorResponse>> {
+        let page = self.table_rows_page(&params, false).await?;
+        let mut rows: Vec<P> = Vec::with_capacity(page.rows.len());
+        for encoded_row in &page.rows {
+            let row_bytes = table_row_bytes(encoded_row)?;
+            let mut decoder = Decoder::new(&row_bytes);
+            let mut row = P::default();
+            decoder.unpack(&mut row)?;
+            rows.push(row);
+        }
+        Ok(page.with_rows(rows))
+    }
+
+    /// Returns table rows decoded by nodeos with the contract ABI, as
+    /// `json: true` does, and deserialized into `R`.
+    pub async fn get_table_rows_json<R: DeserializeOwned>(
+        &self,
+        params: GetTableRowsParams,
+    ) -> Result<GetTableRowsResponse<R>, ClientError<ErrorResponse>> {
+        let page = self.table_rows_page(&params, true).await?;
+        let mut rows: Vec<R> = Vec::with_capacity(page.rows.len());
+        for row in &page.rows {
+            rows.push(
+                serde_json::from_value(row.clone())
+                    .map_err(|e| ClientError::encoding(format!("Failed to parse row: {}", e)))?,
+            );
+        }
+        Ok(page.with_rows(rows))
+    }
+
+    /// Returns table rows decoded locally with `abi`, the ABI of the
+    /// contract, for tables whose row type is not known at compile time.
+    pub async fn get_table_rows_abi(
+        &self,
+        params: GetTableRowsParams,
+        abi: &AbiSerializer,
+    ) -> Result<GetTableRowsResponse<Value>, ClientError<ErrorResponse>> {
+        let page = self.table_rows_page(&params, false).await?;
+        let mut rows: Vec<Value> = Vec::with_capacity(page.rows.len());
+        for row in &page.rows {
+            let row_bytes = table_row_bytes(row)?;
+            rows.push(
+                abi.bin_to_table_row(params.table, &row_bytes)
+                    .map_err(|e| ClientError::encoding(format!("Failed to decode row: {}", e)))?,
+            );
+        }
+        Ok(page.with_rows(rows))
+    }
+
+    /// Requests a page of table rows, leaving the rows undecoded. With
+    /// `show_payer` each row comes with its RAM payer, which is split off
+    /// into `ram_payers`.
+    async fn table_rows_page(
+        &self,
+        params: &GetTableRowsParams,
+        json: bool,
+    ) -> Result<GetTableRowsResponse<Value>, ClientError<ErrorResponse>> {
         let result = self.provider.post(
             String::from("/v1/chain/get_table_rows"),
-            Some(params.to_json()),
+            Some(
+                params
+                    .to_json_with_rows(json)
+                    .map_err(ClientError::with_server_type)?,
+            ),
         );
 
         let response = match result.await {
@@ -307,56 +529,186 @@ impl<T: Provider> ChainAPI<T> {
                 )))
             }
         };
-        let json: Value = serde_json::from_str(response.as_str()).unwrap();
+        let json: Value = serde_json::from_str(response.as_str())
+            .map_err(|e| ClientError::encoding(format!("Failed to parse JSON: {}", e)))?;
+        if json.get("rows").is_none() {
+            if let Ok(error_response) = serde_json::from_value::<ErrorResponse>(json.clone()) {
+                return Err(ClientError::server(error_response));
+            }
+        }
         let response_obj = JSONObject::new(json);
         let more = response_obj.get_bool("more")?;
         let next_key_str = response_obj.get_string("next_key")?;
         let rows_value = response_obj.get_vec("rows")?;
-        let mut rows: Vec<P> = Vec::with_capacity(rows_value.len());
-        for encoded_row in rows_value {
-            let row_bytes_hex = &ValueTo::string(Some(encoded_row))?;
-            let row_bytes = hex_to_bytes(row_bytes_hex);
-            let mut decoder = Decoder::new(&row_bytes);
-            let mut row = P::default();
-            decoder.unpack(&mut row);
-            rows.push(row);
-        }
 
-        let mut next_key = None;
+        let (rows, ram_payers) = if params.show_payer == Some(true) {
+            let mut rows = Vec::with_capacity(rows_value.len());
+            let mut ram_payers = Vec::with_capacity(rows_value.len());
+            for row in rows_value {
+                let row_obj = JSONObject::new(row.clone());
+                let payer = row_obj.get_string("payer")?;
+                ram_payers.push(Name::from_str(&payer).map_err(ClientError::encoding)?);
+                rows.push(row_obj.get_value("data")?);
+            }
+            (rows, Some(ram_payers))
+        } else {
+            (rows_value.clone(), None)
+        };
 
-        if !next_key_str.is_empty() {
-            match params.lower_bound {
-                Some(TableIndexType::NAME(_)) => {
-                    next_key = Some(TableIndexType::NAME(name!(next_key_str.as_str())));
-                }
-                Some(TableIndexType::UINT64(_)) => {
-                    next_key = Some(TableIndexType::UINT64(next_key_str.parse().unwrap()));
-                }
-                Some(TableIndexType::UINT128(_)) => {
-                    next_key = Some(TableIndexType::UINT128(next_key_str.parse().unwrap()));
-                }
-                Some(TableIndexType::CHECKSUM160(_)) => {
-                    next_key = Some(TableIndexType::CHECKSUM160(
-                        Checksum160::from_bytes(hex_to_bytes(&next_key_str).as_slice()).unwrap(),
-                    ));
-                }
-                Some(TableIndexType::CHECKSUM256(_)) => {
-                    next_key = Some(TableIndexType::CHECKSUM256(
-                        Checksum256::from_bytes(hex_to_bytes(&next_key_str).as_slice()).unwrap(),
-                    ));
-                }
-                Some(TableIndexType::FLOAT64(_)) => {
-                    next_key = Some(TableIndexType::FLOAT64(next_key_str.parse().unwrap()));
-                }
-                None => {}
-            };
-        }
+        let next_key = if next_key_str.is_empty() {
+            None
+        } else {
+            Some(
+                params
+                    .key_type()
+                    .and_then(|key_type| key_type.parse(&next_key_str))
+                    .map_err(ClientError::encoding)?,
+            )
+        };
 
         Ok(GetTableRowsResponse {
             rows,
             more,
-            ram_payers: None,
+            ram_payers,
             next_key,
         })
     }
+
+    pub async fn get_table_by_scope(
+        &self,
+        params: GetTableByScopeParams,
+    ) -> Result<GetTableByScopeResponse, ClientError<ErrorResponse>> {
+        let result = self
+            .provider
+            .post(
+                String::from("/v1/chain/get_table_by_scope"),
+                Some(params.to_json()),
+            )
+            .await
+            .map_err(ClientError::NETWORK)?;
+
+        match serde_json::from_str::<GetTableByScopeResponse>(&result) {
+            Ok(response) => Ok(response),
+            Err(e) => match serde_json::from_str::<ErrorResponse>(&result) {
+                Ok(error_response) => Err(ClientError::server(error_response)),
+                Err(_) => Err(ClientError::encoding(format!(
+                    "Failed to parse response: {}",
+                    e
+                ))),
+            },
+        }
+    }
+
+    /// Returns every row of a table, requesting the following pages with
+    /// `next_key` as the lower bound, or as the upper bound when `reverse` is
+    /// set. `limit` is the size of each page and must exceed the number of
+    /// rows sharing a key, or the stream ends with an error.
+    pub fn get_table_rows_stream<'a, P: Packer + Default + 'a>(
+        &'a self,
+        params: GetTableRowsParams,
+    ) -> impl Stream<Item = Result<P, ClientError<ErrorResponse>>> + 'a {
+        stream::unfold(Some(params), move |params| async move {
+            let mut params = params?;
+            let page = match self.get_table_rows::<P>(params.clone()).await {
+                Ok(page) => page,
+                Err(e) => return Some((vec![Err(e)], None)),
+            };
+            let mut rows: Vec<_> = page.rows.into_iter().map(Ok).collect();
+            let next = match (page.more, page.next_key) {
+                (false, _) => None,
+                (true, Some(next_key)) => {
+                    let bound = if params.reverse == Some(true) {
+                        &mut params.upper_bound
+                    } else {
+                        &mut params.lower_bound
+                    };
+                    if bound.as_ref() == Some(&next_key) {
+                        // More than `limit` rows share the key, paging would
+                        // request the same page forever
+                        rows.push(Err(ClientError::simple(format!(
+                            "Table rows do not advance past key {}, raise the limit",
+                            next_key.to_json()
+                        ))));
+                        None
+                    } else {
+                        *bound = Some(next_key);
+                        Some(params)
+                    }
+                }
+                (true, None) => {
+                    rows.push(Err(ClientError::simple(
+                        "More table rows but no next_key to request them".into(),
+                    )));
+                    None
+                }
+            };
+            Some((rows, next))
+        })
+        .flat_map(stream::iter)
+    }
+
+    /// Returns every scope of a contract, or of one of its tables, requesting
+    /// the following pages from the `more` scope.
+    pub fn get_table_by_scope_stream(
+        &self,
+        params: GetTableByScopeParams,
+    ) -> impl Stream<Item = Result<TableScope, ClientError<ErrorResponse>>> + '_ {
+        stream::unfold(Some(params), move |params| async move {
+            let mut params = params?;
+            let page = match self.get_table_by_scope(params.clone()).await {
+                Ok(page) => page,
+                Err(e) => return Some((vec![Err(e)], None)),
+            };
+            let mut scopes: Vec<_> = page.rows.into_iter().map(Ok).collect();
+            let next = if page.more.is_empty() {
+                None
+            } else {
+                match Name::from_str(&page.more) {
+                    Ok(more) => {
+                        if params.reverse == Some(true) {
+                            params.upper_bound = Some(more);
+                        } else {
+                            params.lower_bound = Some(more);
+                        }
+                        Some(params)
+                    }
+                    Err(e) => {
+                        scopes.push(Err(ClientError::encoding(e)));
+                        None
+                    }
+                }
+            };
+            Some((scopes, next))
+        })
+        .flat_map(stream::iter)
+    }
+
+    /// Returns every row of every scope of a table with its scope. The scope
+    /// of `params` is ignored, the other fields apply to each scope.
+    pub fn get_all_table_rows_stream<'a, P: Packer + Default + 'a>(
+        &'a self,
+        params: GetTableRowsParams,
+    ) -> impl Stream<Item = Result<(Name, P), ClientError<ErrorResponse>>> + 'a {
+        let scopes = self.get_table_by_scope_stream(GetTableByScopeParams {
+            code: params.code,
+            table: Some(params.table),
+            ..Default::default()
+        });
+        scopes.flat_map(move |scope| match scope {
+            Ok(scope) => {
+                let rows = self.get_table_rows_stream::<P>(GetTableRowsParams {
+                    scope: Some(scope.scope),
+                    ..params.clone()
+                });
+                rows.map_ok(move |row| (scope.scope, row)).left_stream()
+            }
+            Err(e) => stream::iter([Err(e)]).right_stream(),
+        })
+    }
+}
+
+/// Decodes a hex encoded table row.
+fn table_row_bytes(row: &Value) -> Result<Vec<u8>, EncodingError> {
+    let row_bytes_hex = ValueTo::str(Some(row))?;
+    hex::decode(row_bytes_hex).map_err(|e| EncodingError::new(format!("Invalid row hex: {}", e)))
 }
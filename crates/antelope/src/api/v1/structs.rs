@@ -4,7 +4,7 @@ use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{json, Value};
 use std::fmt;
-use std::mem::discriminant;
+use std::str::FromStr;
 
 use crate::chain::abi::ABI;
 use crate::chain::public_key::PublicKey;
@@ -14,6 +14,7 @@ use crate::chain::{
     action::{Action, PermissionLevel},
     asset::{deserialize_asset, deserialize_optional_asset, Asset},
     authority::Authority,
+    blob::Blob,
     block_id::{deserialize_block_id, deserialize_optional_block_id, BlockId},
     checksum::{deserialize_checksum256, Checksum160, Checksum256},
     name::{deserialize_name, deserialize_optional_name, deserialize_vec_name, Name},
@@ -22,6 +23,7 @@ use crate::chain::{
     transaction::TransactionHeader,
     varint::VarUint32,
 };
+use crate::serializer::{decode_all, AbiError, AbiSerializer, DecodeError, Packer};
 use tracing::info;
 
 #[derive(Debug)]
@@ -53,12 +55,32 @@ impl<T> From<EncodingError> for ClientError<T> {
     }
 }
 
+impl<T> From<DecodeError> for ClientError<T> {
+    fn from(value: DecodeError) -> Self {
+        ClientError::encoding(value.to_string())
+    }
+}
+
 impl<T> From<String> for ClientError<T> {
     fn from(value: String) -> Self {
         ClientError::simple(value)
     }
 }
 
+impl ClientError<()> {
+    /// Converts a request building error, which never carries a server
+    /// response, for an endpoint with typed server errors.
+    pub fn with_server_type<T>(self) -> ClientError<T> {
+        match self {
+            ClientError::SIMPLE(e) => ClientError::SIMPLE(e),
+            ClientError::SERVER(_) => ClientError::simple(String::from("Server error")),
+            ClientError::HTTP(e) => ClientError::HTTP(e),
+            ClientError::ENCODING(e) => ClientError::ENCODING(e),
+            ClientError::NETWORK(e) => ClientError::NETWORK(e),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SimpleError {
     pub message: String,
@@ -133,22 +155,28 @@ pub struct GetInfoResponse {
 }
 
 impl GetInfoResponse {
-    pub fn get_transaction_header(&self, seconds_ahead: u32) -> TransactionHeader {
-        let expiration = TimePointSec {
-            // head_block_time.elapsed is microseconds, convert to seconds
-            seconds: (self.head_block_time.elapsed / 1000 / 1000) as u32 + seconds_ahead,
-        };
-        let id = self.last_irreversible_block_id.bytes.to_vec();
-        let prefix_array = &id[8..12];
-        let prefix = u32::from_ne_bytes(prefix_array.try_into().unwrap());
-        TransactionHeader {
+    pub fn get_transaction_header(&self, seconds_ahead: u32) -> Result<TransactionHeader, String> {
+        Ok(TransactionHeader {
             max_net_usage_words: VarUint32::default(),
             max_cpu_usage_ms: 0,
             delay_sec: VarUint32::default(),
-            expiration,
+            expiration: self.expiration(seconds_ahead)?,
             ref_block_num: (self.last_irreversible_block_num & 0xffff) as u16,
-            ref_block_prefix: prefix,
-        }
+            ref_block_prefix: self.last_irreversible_block_id.ref_block_prefix()?,
+        })
+    }
+
+    /// The expiration `seconds_ahead` seconds after the head block time,
+    /// failing if it does not fit a `TimePointSec`.
+    pub fn expiration(&self, seconds_ahead: u32) -> Result<TimePointSec, String> {
+        // head_block_time.elapsed is microseconds, convert to seconds
+        u32::try_from(self.head_block_time.elapsed / 1000 / 1000)
+            .ok()
+            .and_then(|seconds| seconds.checked_add(seconds_ahead))
+            .map(|seconds| TimePointSec { seconds })
+            .ok_or_else(|| {
+                format!("Expiration {seconds_ahead} seconds after the head block overflows")
+            })
     }
 }
 
@@ -347,7 +375,7 @@ pub struct SendTransaction2Response {
     pub processed: ProcessedTransaction2,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionState {
     LocallyApplied,
@@ -383,6 +411,51 @@ pub struct GetTransactionStatusResponse {
     pub earliest_tracked_block_number: u32,
 }
 
+/// The trace of a transaction that was executed without being included in
+/// a block, by `compute_transaction` or `send_read_only_transaction`.
+/// Read-only transactions are not billed and have no receipt.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionTrace {
+    pub id: String,
+    pub block_num: u64,
+    pub block_time: String,
+    pub producer_block_id: Option<String>,
+    pub receipt: Option<ProcessedTransactionReceipt>,
+    pub elapsed: u64,
+    pub net_usage: u32,
+    pub scheduled: bool,
+    pub action_traces: Vec<ActionTrace>,
+    pub account_ram_delta: Option<AccountRamDelta>,
+    pub except: Option<SendTransactionResponseError>,
+    pub error_code: Option<String>,
+}
+
+impl TransactionTrace {
+    /// The CPU billed in microseconds, or the execution time when there is
+    /// no receipt.
+    pub fn cpu_usage_us(&self) -> u64 {
+        match &self.receipt {
+            Some(receipt) => receipt.cpu_usage_us as u64,
+            None => self.elapsed,
+        }
+    }
+
+    /// The NET billed in 8 byte words, or the NET used when there is no
+    /// receipt.
+    pub fn net_usage_words(&self) -> u32 {
+        match &self.receipt {
+            Some(receipt) => receipt.net_usage_words,
+            None => self.net_usage.div_ceil(8),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComputeTransactionResponse {
+    pub transaction_id: String,
+    pub processed: TransactionTrace,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ActionTrace {
     pub action_ordinal: u32,
@@ -407,6 +480,33 @@ pub struct ActionTrace {
     pub return_value_hex_data: String,
 }
 
+impl ActionTrace {
+    /// Decodes the return value of the action with the `action_results` of
+    /// the contract ABI. Returns `None` if the action returned nothing.
+    pub fn return_value(&self, abi: &AbiSerializer) -> Result<Option<Value>, AbiError> {
+        if self.return_value_hex_data.is_empty() {
+            return Ok(None);
+        }
+        let type_name = abi.action_result_type(self.act.name).ok_or_else(|| {
+            AbiError::new(format!(
+                "No action result type for action: {}",
+                self.act.name
+            ))
+        })?;
+        let data = hex::decode(&self.return_value_hex_data)
+            .map_err(|e| AbiError::new(e.to_string()).in_field("return_value_hex_data"))?;
+        abi.bin_to_json(type_name, &data).map(Some)
+    }
+
+    /// Unpacks the return value of the action as `T`, which must use all of
+    /// it.
+    pub fn decode_return_value<T: Packer + Default>(&self) -> Result<T, DecodeError> {
+        let data = hex::decode(&self.return_value_hex_data)
+            .map_err(|e| DecodeError::invalid("ActionTrace", e.to_string()))?;
+        decode_all(&data)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ActionReceipt {
     #[serde(deserialize_with = "deserialize_name")]
@@ -437,7 +537,7 @@ pub struct AccountRamDelta {
     pub delta: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum IndexPosition {
     PRIMARY,
     SECONDARY,
@@ -468,7 +568,7 @@ impl IndexPosition {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TableIndexType {
     NAME(Name),
     UINT64(u64),
@@ -491,18 +591,82 @@ impl TableIndexType {
     }
 
     pub fn get_key_type(&self) -> Value {
+        Value::String(self.key_type().as_str().to_string())
+    }
+
+    pub fn key_type(&self) -> TableKeyType {
         match self {
-            TableIndexType::NAME(_) => Value::String("name".to_string()),
-            TableIndexType::UINT64(_) => Value::String("i64".to_string()),
-            TableIndexType::UINT128(_) => Value::String("i128".to_string()),
-            TableIndexType::FLOAT64(_) => Value::String("float64".to_string()),
-            TableIndexType::CHECKSUM256(_) => Value::String("sha256".to_string()),
-            TableIndexType::CHECKSUM160(_) => Value::String("ripemd160".to_string()),
+            TableIndexType::NAME(_) => TableKeyType::NAME,
+            TableIndexType::UINT64(_) => TableKeyType::UINT64,
+            TableIndexType::UINT128(_) => TableKeyType::UINT128,
+            TableIndexType::FLOAT64(_) => TableKeyType::FLOAT64,
+            TableIndexType::CHECKSUM256(_) => TableKeyType::CHECKSUM256,
+            TableIndexType::CHECKSUM160(_) => TableKeyType::CHECKSUM160,
         }
     }
+
+    /// Parses `key` as a key of the same type, as nodeos writes it in
+    /// `next_key`.
+    pub fn parse_like(&self, key: &str) -> Result<TableIndexType, String> {
+        self.key_type().parse(key)
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The type of the keys of a table index, without a key value.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TableKeyType {
+    NAME,
+    #[default]
+    UINT64,
+    UINT128,
+    FLOAT64,
+    CHECKSUM256,
+    CHECKSUM160,
+}
+
+impl TableKeyType {
+    /// The `key_type` nodeos expects for this type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TableKeyType::NAME => "name",
+            TableKeyType::UINT64 => "i64",
+            TableKeyType::UINT128 => "i128",
+            TableKeyType::FLOAT64 => "float64",
+            TableKeyType::CHECKSUM256 => "sha256",
+            TableKeyType::CHECKSUM160 => "ripemd160",
+        }
+    }
+
+    /// Parses `key` as a key of this type, as nodeos writes it in
+    /// `next_key`.
+    pub fn parse(&self, key: &str) -> Result<TableIndexType, String> {
+        let invalid = |e: String| format!("Invalid {} key {key}: {e}", self.as_str());
+        match self {
+            TableKeyType::NAME => Name::from_str(key).map(TableIndexType::NAME),
+            TableKeyType::UINT64 => key
+                .parse()
+                .map(TableIndexType::UINT64)
+                .map_err(|e: std::num::ParseIntError| e.to_string()),
+            TableKeyType::UINT128 => key
+                .parse()
+                .map(TableIndexType::UINT128)
+                .map_err(|e: std::num::ParseIntError| e.to_string()),
+            TableKeyType::FLOAT64 => key
+                .parse()
+                .map(TableIndexType::FLOAT64)
+                .map_err(|e: std::num::ParseFloatError| e.to_string()),
+            TableKeyType::CHECKSUM256 => {
+                Checksum256::from_hex(key).map(TableIndexType::CHECKSUM256)
+            }
+            TableKeyType::CHECKSUM160 => {
+                Checksum160::from_hex(key).map(TableIndexType::CHECKSUM160)
+            }
+        }
+        .map_err(invalid)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct GetTableRowsParams {
     #[serde(deserialize_with = "deserialize_name")]
     pub code: Name,
@@ -515,11 +679,22 @@ pub struct GetTableRowsParams {
     pub limit: Option<u32>,
     pub reverse: Option<bool>,
     pub index_position: Option<IndexPosition>,
+    /// The type of the index keys. Taken from the bounds when not set, but
+    /// needed to page through a secondary index without bounds.
+    #[serde(default)]
+    pub key_type: Option<TableKeyType>,
     pub show_payer: Option<bool>,
 }
 
 impl GetTableRowsParams {
-    pub fn to_json(&self) -> String {
+    pub fn to_json(&self) -> Result<String, ClientError<()>> {
+        self.to_json_with_rows(false)
+    }
+
+    /// The request body, asking nodeos to decode the rows with the contract
+    /// ABI when `json` is set, or to return them hex encoded.
+    pub fn to_json_with_rows(&self, json: bool) -> Result<String, ClientError<()>> {
+        let key_type = self.key_type().map_err(ClientError::simple)?;
         let mut req: HashMap<&str, Value> = HashMap::new();
         req.insert("code", Value::String(self.code.to_string()));
         req.insert("table", Value::String(self.table.to_string()));
@@ -527,7 +702,11 @@ impl GetTableRowsParams {
         let scope = self.scope.unwrap_or(self.code);
         req.insert("scope", Value::String(scope.to_string()));
 
-        req.insert("json", Value::Bool(false));
+        req.insert("json", Value::Bool(json));
+
+        if let Some(show_payer) = &self.show_payer {
+            req.insert("show_payer", Value::Bool(*show_payer));
+        }
 
         if let Some(limit) = &self.limit {
             req.insert("limit", Value::String(limit.to_string()));
@@ -537,32 +716,46 @@ impl GetTableRowsParams {
             req.insert("reverse", Value::Bool(*reverse));
         }
 
-        if self.lower_bound.is_some() || self.upper_bound.is_some() {
-            if self.upper_bound.is_none() {
-                let lower = self.lower_bound.as_ref().unwrap();
-                req.insert("key_type", lower.get_key_type());
-                req.insert("lower_bound", lower.to_json());
-            } else if self.lower_bound.is_none() {
-                let upper = self.upper_bound.as_ref().unwrap();
-                req.insert("key_type", upper.get_key_type());
-                req.insert("upper_bound", upper.to_json());
-            } else {
-                let lower = self.lower_bound.as_ref().unwrap();
-                let upper = self.upper_bound.as_ref().unwrap();
-                if discriminant(lower) != discriminant(upper) {
-                    panic!("lower_bound and upper_bound must be of the same type");
-                }
-                req.insert("key_type", lower.get_key_type());
-                req.insert("lower_bound", lower.to_json());
-                req.insert("upper_bound", upper.to_json());
-            }
+        if self.key_type.is_some() || self.lower_bound.is_some() || self.upper_bound.is_some() {
+            req.insert("key_type", Value::String(key_type.as_str().to_string()));
+        }
+        if let Some(lower) = &self.lower_bound {
+            req.insert("lower_bound", lower.to_json());
+        }
+        if let Some(upper) = &self.upper_bound {
+            req.insert("upper_bound", upper.to_json());
+        }
 
-            if let Some(index_position) = &self.index_position {
-                req.insert("index_position", index_position.to_json());
-            }
+        if let Some(index_position) = &self.index_position {
+            req.insert("index_position", index_position.to_json());
         }
 
-        json!(req).to_string()
+        Ok(json!(req).to_string())
+    }
+
+    /// The type of the index keys, from `key_type` or else from the bounds,
+    /// which must all agree. Without either nodeos uses `i64` keys.
+    pub fn key_type(&self) -> Result<TableKeyType, String> {
+        let mut key_type = self.key_type;
+        for (field, bound) in [
+            ("lower_bound", &self.lower_bound),
+            ("upper_bound", &self.upper_bound),
+        ] {
+            let Some(bound) = bound else {
+                continue;
+            };
+            match key_type {
+                Some(expected) if expected != bound.key_type() => {
+                    return Err(format!(
+                        "{field} is a {} key but the index has {} keys",
+                        bound.key_type().as_str(),
+                        expected.as_str()
+                    ))
+                }
+                _ => key_type = Some(bound.key_type()),
+            }
+        }
+        Ok(key_type.unwrap_or_default())
     }
 }
 
@@ -574,6 +767,76 @@ pub struct GetTableRowsResponse<T> {
     pub next_key: Option<TableIndexType>,
 }
 
+impl<T> GetTableRowsResponse<T> {
+    /// Replaces the rows, keeping the paging and RAM payer data.
+    pub fn with_rows<R>(self, rows: Vec<R>) -> GetTableRowsResponse<R> {
+        GetTableRowsResponse {
+            rows,
+            more: self.more,
+            ram_payers: self.ram_payers,
+            next_key: self.next_key,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GetTableByScopeParams {
+    pub code: Name,
+    /// Only list the scopes of this table.
+    pub table: Option<Name>,
+    pub lower_bound: Option<Name>,
+    pub upper_bound: Option<Name>,
+    pub limit: Option<u32>,
+    pub reverse: Option<bool>,
+}
+
+impl GetTableByScopeParams {
+    pub fn to_json(&self) -> String {
+        let mut req = json!({ "code": self.code });
+        if let Some(table) = self.table {
+            req["table"] = json!(table);
+        }
+        if let Some(lower_bound) = self.lower_bound {
+            req["lower_bound"] = json!(lower_bound);
+        }
+        if let Some(upper_bound) = self.upper_bound {
+            req["upper_bound"] = json!(upper_bound);
+        }
+        if let Some(limit) = self.limit {
+            req["limit"] = json!(limit);
+        }
+        if let Some(reverse) = self.reverse {
+            req["reverse"] = json!(reverse);
+        }
+        req.to_string()
+    }
+}
+
+/// A scope of a table, from `get_table_by_scope`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TableScope {
+    pub code: Name,
+    pub scope: Name,
+    pub table: Name,
+    pub payer: Name,
+    pub count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetTableByScopeResponse {
+    pub rows: Vec<TableScope>,
+    /// The scope to continue from, empty when there are no more.
+    pub more: String,
+}
+
+/// The stats of a token, from `get_currency_stats`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyStats {
+    pub supply: Asset,
+    pub max_supply: Asset,
+    pub issuer: Name,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AccountObject {
     #[serde(deserialize_with = "deserialize_name")]
@@ -801,6 +1064,31 @@ pub struct ABIResponse {
     pub abi: ABI,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GetRawAbiResponse {
+    pub account_name: String,
+    #[serde(deserialize_with = "deserialize_checksum256")]
+    pub code_hash: Checksum256,
+    #[serde(deserialize_with = "deserialize_checksum256")]
+    pub abi_hash: Checksum256,
+    /// The binary ABI, base64 encoded.
+    #[serde(default)]
+    pub abi: Option<String>,
+}
+
+impl GetRawAbiResponse {
+    /// Decodes the binary ABI, returning `None` if the account has none.
+    pub fn decode_abi(&self) -> Result<Option<ABI>, DecodeError> {
+        match self.abi.as_deref() {
+            None | Some("") => Ok(None),
+            Some(abi) => {
+                let blob = Blob::from_string(abi).map_err(|e| DecodeError::invalid("ABI", e))?;
+                ABI::from_bytes(&blob.array).map(Some)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct GetBlockResponse {
     #[serde(rename = "timestamp")]
@@ -43,8 +43,8 @@ pub fn decode_ripemd160_check(
         return Err("Checksum mismatch".to_string());
     }
 
-    if size.is_some() {
-        let size_value = size.unwrap() + 4;
+    if let Some(size) = size {
+        let size_value = size + 4;
         if data.len() > size_value {
             return Ok(data[0..size_value].to_vec());
         }
@@ -88,12 +88,12 @@ pub fn decode_public_key(value: &str) -> Result<(KeyType, Vec<u8>), String> {
             KeyType::K1 | KeyType::R1 => Some(32),
             KeyType::WA => None, // ... other cases ...
         };
-        let data = decode_ripemd160_check(parts[2], size, Option::from(key_type), false).unwrap();
+        let data = decode_ripemd160_check(parts[2], size, Option::from(key_type), false)?;
         Ok((key_type, data))
     } else if value.len() > 50 {
         let without_prefix = value.chars().skip(value.len() - 50).collect::<String>();
-        let data = base58::decode_ripemd160_check(without_prefix.as_str(), Some(32), None, false);
-        Ok((KeyType::K1, data.unwrap().to_vec()))
+        let data = base58::decode_ripemd160_check(without_prefix.as_str(), Some(32), None, false)?;
+        Ok((KeyType::K1, data.to_vec()))
     } else {
         Err(String::from("Public key format invalid"))
     }
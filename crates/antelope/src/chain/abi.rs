@@ -1,13 +1,33 @@
-use crate::chain::{Decoder, Encoder, Packer};
 use antelope_client_macros::StructPacker;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
 use crate::{
-    chain::name::{deserialize_name, Name},
-    // serializer::{Decoder, Encoder, Packer},
+    chain::{
+        asset::{Asset, ExtendedAsset, Symbol, SymbolCode},
+        binary_extension::BinaryExtension,
+        checksum::{Checksum160, Checksum256, Checksum512},
+        name::{deserialize_name, serialize_name, Name},
+        public_key::PublicKey,
+        signature::Signature,
+        time::{BlockTimestamp, TimePoint, TimePointSec},
+        varint::{VarInt32, VarUint32},
+    },
+    serializer::{decode_all, packer::Float128, DecodeError, Decoder, Encoder, Packer},
 };
 
-#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize, StructPacker)]
+mod diff;
+mod validate;
+
+pub use diff::{AbiChange, AbiChangeKind, AbiDiff, Compatibility};
+pub use validate::AbiValidationError;
+
+/// A contract ABI, as stored on chain by `setabi` and returned by `get_abi`.
+///
+/// Converts losslessly between the nodeos JSON form and the binary form. The
+/// trailing `variants`, `action_results` and `kv_tables` are binary
+/// extensions, older ABIs simply end before them.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ABI {
     pub version: String,
     #[serde(default)]
@@ -21,22 +41,168 @@ pub struct ABI {
     #[serde(default)]
     pub ricardian_clauses: Vec<AbiClause>,
     #[serde(default)]
-    error_messages: Vec<String>,
+    pub error_messages: Vec<AbiErrorMessage>,
     #[serde(default)]
-    abi_extensions: Vec<String>,
+    pub abi_extensions: Vec<AbiExtension>,
     #[serde(default)]
     pub variants: Vec<AbiVariant>,
     #[serde(default)]
     pub action_results: Vec<AbiActionResult>,
-    // kv_tables: {}
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        with = "kv_tables_json"
+    )]
+    pub kv_tables: Vec<AbiKvTable>,
 }
 
 impl ABI {
     pub fn from_string(str: &str) -> Result<Self, String> {
-        let mut abi = serde_json::from_str::<ABI>(str).unwrap();
-        abi.error_messages = vec![];
-        abi.abi_extensions = vec![];
-        Ok(abi)
+        serde_json::from_str::<ABI>(str).map_err(|e| format!("invalid ABI json: {e}"))
+    }
+
+    /// Decodes a binary ABI, e.g. the `abi` field of a `setabi` action or
+    /// the decoded `abi` of a `get_raw_abi` response.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DecodeError> {
+        decode_all(data)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Encoder::pack(self)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// The `(major, minor)` version from a `eosio::abi/<major>.<minor>`
+    /// version string.
+    pub fn version_number(&self) -> Option<(u32, u32)> {
+        let version = self.version.strip_prefix("eosio::abi/")?;
+        let (major, minor) = version.split_once('.')?;
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    }
+
+    /// The versioned ABI features this ABI makes use of.
+    pub fn features(&self) -> Vec<AbiFeature> {
+        let mut features = vec![];
+        if self
+            .structs
+            .iter()
+            .any(|s| s.fields.iter().any(|f| f.r#type.ends_with('$')))
+        {
+            features.push(AbiFeature::BinaryExtensions);
+        }
+        if !self.variants.is_empty() {
+            features.push(AbiFeature::Variants);
+        }
+        if !self.action_results.is_empty() {
+            features.push(AbiFeature::ActionResults);
+        }
+        if !self.kv_tables.is_empty() {
+            features.push(AbiFeature::KvTables);
+        }
+        features
+    }
+
+    /// The lowest ABI version supporting every feature in use.
+    pub fn required_version(&self) -> (u32, u32) {
+        self.features()
+            .iter()
+            .map(AbiFeature::min_version)
+            .max()
+            .unwrap_or((1, 0))
+    }
+}
+
+impl Packer for ABI {
+    fn size(&self) -> usize {
+        let mut size = self.version.size()
+            + self.types.size()
+            + self.structs.size()
+            + self.actions.size()
+            + self.tables.size()
+            + self.ricardian_clauses.size()
+            + self.error_messages.size()
+            + self.abi_extensions.size()
+            + self.variants.size()
+            + self.action_results.size();
+        if !self.kv_tables.is_empty() {
+            size += self.kv_tables.size();
+        }
+        size
+    }
+
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        let pos = enc.get_size();
+        self.version.pack(enc);
+        self.types.pack(enc);
+        self.structs.pack(enc);
+        self.actions.pack(enc);
+        self.tables.pack(enc);
+        self.ricardian_clauses.pack(enc);
+        self.error_messages.pack(enc);
+        self.abi_extensions.pack(enc);
+        // nodeos always writes variants and action_results, kv_tables are
+        // only understood by some versions so leave them out unless used
+        self.variants.pack(enc);
+        self.action_results.pack(enc);
+        if !self.kv_tables.is_empty() {
+            self.kv_tables.pack(enc);
+        }
+        enc.get_size() - pos
+    }
+
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut dec = Decoder::new(data);
+        dec.unpack(&mut self.version)
+            .map_err(|e| e.in_field("version"))?;
+        dec.unpack(&mut self.types)
+            .map_err(|e| e.in_field("types"))?;
+        dec.unpack(&mut self.structs)
+            .map_err(|e| e.in_field("structs"))?;
+        dec.unpack(&mut self.actions)
+            .map_err(|e| e.in_field("actions"))?;
+        dec.unpack(&mut self.tables)
+            .map_err(|e| e.in_field("tables"))?;
+        dec.unpack(&mut self.ricardian_clauses)
+            .map_err(|e| e.in_field("ricardian_clauses"))?;
+        dec.unpack(&mut self.error_messages)
+            .map_err(|e| e.in_field("error_messages"))?;
+        dec.unpack(&mut self.abi_extensions)
+            .map_err(|e| e.in_field("abi_extensions"))?;
+        if dec.remaining() > 0 {
+            dec.unpack(&mut self.variants)
+                .map_err(|e| e.in_field("variants"))?;
+        }
+        if dec.remaining() > 0 {
+            dec.unpack(&mut self.action_results)
+                .map_err(|e| e.in_field("action_results"))?;
+        }
+        if dec.remaining() > 0 {
+            dec.unpack(&mut self.kv_tables)
+                .map_err(|e| e.in_field("kv_tables"))?;
+        }
+        Ok(dec.get_pos())
+    }
+}
+
+/// A feature introduced by a later ABI version.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AbiFeature {
+    /// Fields with a `$` suffix.
+    BinaryExtensions,
+    Variants,
+    ActionResults,
+    KvTables,
+}
+
+impl AbiFeature {
+    pub fn min_version(&self) -> (u32, u32) {
+        match self {
+            AbiFeature::BinaryExtensions | AbiFeature::Variants => (1, 1),
+            AbiFeature::ActionResults | AbiFeature::KvTables => (1, 2),
+        }
     }
 }
 
@@ -68,7 +234,10 @@ pub struct AbiVariant {
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize, StructPacker)]
 pub struct AbiAction {
-    #[serde(deserialize_with = "deserialize_name")]
+    #[serde(
+        serialize_with = "serialize_name",
+        deserialize_with = "deserialize_name"
+    )]
     pub name: Name,
     pub r#type: String,
     pub ricardian_contract: String,
@@ -76,7 +245,10 @@ pub struct AbiAction {
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize, StructPacker)]
 pub struct AbiTable {
-    #[serde(deserialize_with = "deserialize_name")]
+    #[serde(
+        serialize_with = "serialize_name",
+        deserialize_with = "deserialize_name"
+    )]
     pub name: Name,
     #[serde(default)]
     pub index_type: String,
@@ -95,7 +267,393 @@ pub struct AbiClause {
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize, StructPacker)]
 pub struct AbiActionResult {
-    #[serde(deserialize_with = "deserialize_name")]
+    #[serde(
+        serialize_with = "serialize_name",
+        deserialize_with = "deserialize_name"
+    )]
     pub name: Name,
     pub result_type: String,
 }
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize, StructPacker)]
+pub struct AbiErrorMessage {
+    #[serde(deserialize_with = "deserialize_error_code")]
+    pub error_code: u64,
+    pub error_msg: String,
+}
+
+fn deserialize_error_code<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| serde::de::Error::custom("expected an unsigned error code")),
+        Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        _ => Err(serde::de::Error::custom("expected a number or a string")),
+    }
+}
+
+/// An ABI extension, written in JSON as `[type, "hex data"]`.
+#[derive(Debug, Clone, Default, Eq, PartialEq, StructPacker)]
+pub struct AbiExtension {
+    pub r#type: u16,
+    pub data: Vec<u8>,
+}
+
+impl Serialize for AbiExtension {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.r#type, hex::encode(&self.data)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AbiExtension {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (r#type, data) = <(u16, String)>::deserialize(deserializer)?;
+        let data = hex::decode(data).map_err(serde::de::Error::custom)?;
+        Ok(AbiExtension { r#type, data })
+    }
+}
+
+/// A key-value table, only understood by nodeos versions supporting the KV
+/// database. In JSON `kv_tables` is an object keyed by table name.
+#[derive(Debug, Clone, Default, Eq, PartialEq, StructPacker)]
+pub struct AbiKvTable {
+    pub name: Name,
+    pub r#type: String,
+    pub primary_index: AbiKvIndex,
+    pub secondary_indices: Vec<AbiKvSecondaryIndex>,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize, StructPacker)]
+pub struct AbiKvIndex {
+    #[serde(
+        serialize_with = "serialize_name",
+        deserialize_with = "deserialize_name"
+    )]
+    pub name: Name,
+    pub r#type: String,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, StructPacker)]
+pub struct AbiKvSecondaryIndex {
+    pub name: Name,
+    pub r#type: String,
+}
+
+mod kv_tables_json {
+    use std::marker::PhantomData;
+
+    use serde::{
+        de::MapAccess, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    use super::{AbiKvIndex, AbiKvSecondaryIndex, AbiKvTable};
+    use crate::chain::name::Name;
+
+    #[derive(Serialize, Deserialize)]
+    struct TableEntry {
+        r#type: String,
+        primary_index: AbiKvIndex,
+        #[serde(default, with = "secondary_indices_json")]
+        secondary_indices: Vec<AbiKvSecondaryIndex>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct SecondaryEntry {
+        r#type: String,
+    }
+
+    /// Visits a JSON object keeping the order of its keys.
+    struct OrderedMap<V>(PhantomData<V>);
+
+    impl<'de, V: Deserialize<'de>> serde::de::Visitor<'de> for OrderedMap<V> {
+        type Value = Vec<(Name, V)>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("an object keyed by name")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = vec![];
+            while let Some((key, value)) = map.next_entry::<String, V>()? {
+                let name = key.parse::<Name>().map_err(serde::de::Error::custom)?;
+                entries.push((name, value));
+            }
+            Ok(entries)
+        }
+    }
+
+    pub fn serialize<S: Serializer>(
+        tables: &[AbiKvTable],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(tables.len()))?;
+        for table in tables {
+            let entry = TableEntry {
+                r#type: table.r#type.clone(),
+                primary_index: table.primary_index.clone(),
+                secondary_indices: table.secondary_indices.clone(),
+            };
+            map.serialize_entry(&table.name.to_string(), &entry)?;
+        }
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<AbiKvTable>, D::Error> {
+        let entries = deserializer.deserialize_map(OrderedMap::<TableEntry>(PhantomData))?;
+        Ok(entries
+            .into_iter()
+            .map(|(name, entry)| AbiKvTable {
+                name,
+                r#type: entry.r#type,
+                primary_index: entry.primary_index,
+                secondary_indices: entry.secondary_indices,
+            })
+            .collect())
+    }
+
+    mod secondary_indices_json {
+        use std::marker::PhantomData;
+
+        use serde::{ser::SerializeMap, Deserializer, Serializer};
+
+        use super::{OrderedMap, SecondaryEntry};
+        use crate::chain::abi::AbiKvSecondaryIndex;
+
+        pub fn serialize<S: Serializer>(
+            indices: &[AbiKvSecondaryIndex],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(indices.len()))?;
+            for index in indices {
+                let entry = SecondaryEntry {
+                    r#type: index.r#type.clone(),
+                };
+                map.serialize_entry(&index.name.to_string(), &entry)?;
+            }
+            map.end()
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<AbiKvSecondaryIndex>, D::Error> {
+            let entries =
+                deserializer.deserialize_map(OrderedMap::<SecondaryEntry>(PhantomData))?;
+            Ok(entries
+                .into_iter()
+                .map(|(name, entry)| AbiKvSecondaryIndex {
+                    name,
+                    r#type: entry.r#type,
+                })
+                .collect())
+        }
+    }
+}
+
+/// Describes how a Rust type appears in an ABI.
+///
+/// Derive it with `#[derive(AbiType)]` on structs and on `EnumPacker` style
+/// enums. The ABI name defaults to the snake_case type name and can be set
+/// with `#[abi(name = "...")]`, fields can be renamed the same way.
+pub trait AbiType {
+    /// The type as written in an ABI, e.g. `uint64`, `name[]` or `transfer`.
+    fn abi_type() -> String;
+
+    /// The type of an array of this type. Only differs for `u8`, whose
+    /// arrays are `bytes`.
+    fn abi_array_type() -> String {
+        format!("{}[]", Self::abi_type())
+    }
+
+    /// Adds the struct and variant definitions this type depends on.
+    fn add_abi_definitions(_abi: &mut ABI) {}
+}
+
+macro_rules! impl_abi_type {
+    ( $ty:ty, $name:expr ) => {
+        impl AbiType for $ty {
+            fn abi_type() -> String {
+                String::from($name)
+            }
+        }
+    };
+}
+
+impl_abi_type!(bool, "bool");
+impl_abi_type!(i8, "int8");
+impl_abi_type!(i16, "int16");
+impl_abi_type!(u16, "uint16");
+impl_abi_type!(i32, "int32");
+impl_abi_type!(u32, "uint32");
+impl_abi_type!(i64, "int64");
+impl_abi_type!(u64, "uint64");
+impl_abi_type!(i128, "int128");
+impl_abi_type!(u128, "uint128");
+impl_abi_type!(VarInt32, "varint32");
+impl_abi_type!(VarUint32, "varuint32");
+impl_abi_type!(f32, "float32");
+impl_abi_type!(f64, "float64");
+impl_abi_type!(Float128, "float128");
+impl_abi_type!(TimePoint, "time_point");
+impl_abi_type!(TimePointSec, "time_point_sec");
+impl_abi_type!(BlockTimestamp, "block_timestamp_type");
+impl_abi_type!(Name, "name");
+impl_abi_type!(String, "string");
+impl_abi_type!(Checksum160, "checksum160");
+impl_abi_type!(Checksum256, "checksum256");
+impl_abi_type!(Checksum512, "checksum512");
+impl_abi_type!(PublicKey, "public_key");
+impl_abi_type!(Signature, "signature");
+impl_abi_type!(Symbol, "symbol");
+impl_abi_type!(SymbolCode, "symbol_code");
+impl_abi_type!(Asset, "asset");
+impl_abi_type!(ExtendedAsset, "extended_asset");
+
+impl AbiType for u8 {
+    fn abi_type() -> String {
+        String::from("uint8")
+    }
+
+    fn abi_array_type() -> String {
+        String::from("bytes")
+    }
+}
+
+impl<T: AbiType> AbiType for Vec<T> {
+    fn abi_type() -> String {
+        T::abi_array_type()
+    }
+
+    fn add_abi_definitions(abi: &mut ABI) {
+        T::add_abi_definitions(abi)
+    }
+}
+
+impl<T: AbiType> AbiType for Option<T> {
+    fn abi_type() -> String {
+        format!("{}?", T::abi_type())
+    }
+
+    fn add_abi_definitions(abi: &mut ABI) {
+        T::add_abi_definitions(abi)
+    }
+}
+
+impl<T: AbiType + Packer + Default> AbiType for BinaryExtension<T> {
+    fn abi_type() -> String {
+        format!("{}$", T::abi_type())
+    }
+
+    fn add_abi_definitions(abi: &mut ABI) {
+        T::add_abi_definitions(abi)
+    }
+}
+
+impl<T: AbiType> AbiType for Box<T> {
+    fn abi_type() -> String {
+        T::abi_type()
+    }
+
+    fn add_abi_definitions(abi: &mut ABI) {
+        T::add_abi_definitions(abi)
+    }
+}
+
+/// Builds an `ABI` from Rust types implementing `AbiType`.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::chain::abi::AbiBuilder;
+/// use antelope::chain::asset::Asset;
+/// use antelope::chain::name::Name;
+/// use antelope::{name, AbiType, StructPacker};
+///
+/// #[derive(Default, AbiType, StructPacker)]
+/// struct Transfer {
+///     from: Name,
+///     to: Name,
+///     quantity: Asset,
+///     memo: String,
+/// }
+///
+/// let abi = AbiBuilder::new()
+///     .action::<Transfer>(name!("transfer"))
+///     .build();
+/// assert_eq!(abi.actions[0].r#type, "transfer");
+/// assert_eq!(abi.structs[0].fields[2].r#type, "asset");
+/// ```
+#[derive(Debug, Clone)]
+pub struct AbiBuilder {
+    abi: ABI,
+}
+
+impl Default for AbiBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AbiBuilder {
+    pub fn new() -> Self {
+        Self {
+            abi: ABI {
+                version: String::from("eosio::abi/1.2"),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn version(mut self, version: &str) -> Self {
+        self.abi.version = version.to_string();
+        self
+    }
+
+    /// Adds the definitions for `T` without declaring an action or table.
+    pub fn add_type<T: AbiType>(mut self) -> Self {
+        T::add_abi_definitions(&mut self.abi);
+        self
+    }
+
+    /// Declares the action `name` taking `T` as data.
+    pub fn action<T: AbiType>(mut self, name: Name) -> Self {
+        T::add_abi_definitions(&mut self.abi);
+        self.abi.actions.push(AbiAction {
+            name,
+            r#type: T::abi_type(),
+            ricardian_contract: String::new(),
+        });
+        self
+    }
+
+    /// Declares the table `name` with rows of type `T`.
+    pub fn table<T: AbiType>(mut self, name: Name) -> Self {
+        T::add_abi_definitions(&mut self.abi);
+        self.abi.tables.push(AbiTable {
+            name,
+            index_type: String::from("i64"),
+            key_names: vec![],
+            key_types: vec![],
+            r#type: T::abi_type(),
+        });
+        self
+    }
+
+    /// Declares that the action `name` returns a `T`.
+    pub fn action_result<T: AbiType>(mut self, name: Name) -> Self {
+        T::add_abi_definitions(&mut self.abi);
+        self.abi.action_results.push(AbiActionResult {
+            name,
+            result_type: T::abi_type(),
+        });
+        self
+    }
+
+    pub fn build(self) -> ABI {
+        self.abi
+    }
+}
@@ -0,0 +1,281 @@
+use std::fmt::{Display, Formatter};
+
+use crate::chain::abi::{AbiField, ABI};
+
+/// Whether data written with one ABI can still be read with the other.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compatibility {
+    /// Old data and existing clients keep working.
+    Compatible,
+    /// Old data or existing clients break.
+    Breaking,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AbiChangeKind {
+    Added,
+    Removed,
+    Changed { old: String, new: String },
+    Reordered,
+}
+
+/// A single difference between two ABIs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AbiChange {
+    /// The changed item, e.g. `struct transfer` or `field transfer.memo`.
+    pub item: String,
+    pub kind: AbiChangeKind,
+    pub compatibility: Compatibility,
+}
+
+impl AbiChange {
+    fn new(item: String, kind: AbiChangeKind, compatibility: Compatibility) -> Self {
+        Self {
+            item,
+            kind,
+            compatibility,
+        }
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility == Compatibility::Breaking
+    }
+}
+
+impl Display for AbiChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            AbiChangeKind::Added => write!(f, "{} added", self.item)?,
+            AbiChangeKind::Removed => write!(f, "{} removed", self.item)?,
+            AbiChangeKind::Changed { old, new } => {
+                write!(f, "{} changed from {old} to {new}", self.item)?
+            }
+            AbiChangeKind::Reordered => write!(f, "{} reordered", self.item)?,
+        }
+        if self.is_breaking() {
+            write!(f, " (breaking)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The changes between two ABIs, see `ABI::diff`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AbiDiff {
+    pub changes: Vec<AbiChange>,
+}
+
+impl AbiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// True when no change breaks existing data or clients.
+    pub fn is_compatible(&self) -> bool {
+        !self.changes.iter().any(AbiChange::is_breaking)
+    }
+
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &AbiChange> {
+        self.changes.iter().filter(|c| c.is_breaking())
+    }
+
+    fn push(&mut self, item: String, kind: AbiChangeKind, compatibility: Compatibility) {
+        self.changes.push(AbiChange::new(item, kind, compatibility));
+    }
+
+    /// Compares two lists of named items. Added items are compatible,
+    /// removed ones are breaking and items present in both are handed to
+    /// `changed`.
+    fn compare<'a, T, K: PartialEq>(
+        &mut self,
+        label: &str,
+        old: &'a [T],
+        new: &'a [T],
+        key: impl Fn(&T) -> K,
+        display: impl Fn(&T) -> String,
+        mut changed: impl FnMut(&mut Self, String, &'a T, &'a T),
+    ) {
+        for o in old {
+            let item = format!("{label} {}", display(o));
+            match new.iter().find(|n| key(n) == key(o)) {
+                Some(n) => changed(self, item, o, n),
+                None => self.push(item, AbiChangeKind::Removed, Compatibility::Breaking),
+            }
+        }
+        for n in new {
+            if !old.iter().any(|o| key(o) == key(n)) {
+                let item = format!("{label} {}", display(n));
+                self.push(item, AbiChangeKind::Added, Compatibility::Compatible);
+            }
+        }
+    }
+
+    fn compare_value(&mut self, item: String, old: &str, new: &str) {
+        if old != new {
+            let kind = AbiChangeKind::Changed {
+                old: old.to_string(),
+                new: new.to_string(),
+            };
+            self.push(item, kind, Compatibility::Breaking);
+        }
+    }
+
+    fn compare_fields(&mut self, struct_name: &str, old: &[AbiField], new: &[AbiField]) {
+        let item = |f: &AbiField| format!("field {struct_name}.{}", f.name);
+        // the order of the fields kept in both, so that an added or removed
+        // field is not also reported as moving the ones after it
+        let old_kept: Vec<&str> = old
+            .iter()
+            .filter(|o| new.iter().any(|n| n.name == o.name))
+            .map(|o| o.name.as_str())
+            .collect();
+        let new_kept: Vec<&str> = new
+            .iter()
+            .filter(|n| old.iter().any(|o| o.name == n.name))
+            .map(|n| n.name.as_str())
+            .collect();
+        for o in old {
+            match new.iter().find(|n| n.name == o.name) {
+                Some(n) => {
+                    // making a field an extension keeps existing data valid
+                    let compatibility = if n.r#type == format!("{}$", o.r#type) {
+                        Compatibility::Compatible
+                    } else {
+                        Compatibility::Breaking
+                    };
+                    if o.r#type != n.r#type {
+                        let kind = AbiChangeKind::Changed {
+                            old: o.r#type.clone(),
+                            new: n.r#type.clone(),
+                        };
+                        self.push(item(o), kind, compatibility);
+                    }
+                    let position = |kept: &[&str]| kept.iter().position(|k| *k == o.name);
+                    if position(&old_kept) != position(&new_kept) {
+                        self.push(item(o), AbiChangeKind::Reordered, Compatibility::Breaking);
+                    }
+                }
+                None => self.push(item(o), AbiChangeKind::Removed, Compatibility::Breaking),
+            }
+        }
+        for (j, n) in new.iter().enumerate() {
+            if !old.iter().any(|o| o.name == n.name) {
+                // only trailing binary extensions can be missing from old data
+                let compatibility = if j >= old.len() && n.r#type.ends_with('$') {
+                    Compatibility::Compatible
+                } else {
+                    Compatibility::Breaking
+                };
+                self.push(item(n), AbiChangeKind::Added, compatibility);
+            }
+        }
+    }
+}
+
+impl ABI {
+    /// Compares this ABI with a newer one, classifying every change as
+    /// compatible or breaking.
+    ///
+    /// New types, actions, tables and trailing `$` fields are compatible.
+    /// Removing, renaming, reordering or retyping anything existing is
+    /// breaking, as is adding a variant type anywhere but at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use antelope::chain::abi::{AbiField, ABI};
+    ///
+    /// let old = ABI::from_string(r#"{
+    ///     "version": "eosio::abi/1.2",
+    ///     "structs": [{"name": "greet", "base": "", "fields": [
+    ///         {"name": "who", "type": "name"}
+    ///     ]}]
+    /// }"#).unwrap();
+    /// let mut new = old.clone();
+    /// new.structs[0].fields.push(AbiField {
+    ///     name: String::from("memo"),
+    ///     r#type: String::from("string$"),
+    /// });
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.changes.len(), 1);
+    /// assert!(diff.is_compatible());
+    /// assert!(!new.diff(&old).is_compatible());
+    /// ```
+    pub fn diff(&self, new: &ABI) -> AbiDiff {
+        let mut diff = AbiDiff::default();
+        diff.compare(
+            "typedef",
+            &self.types,
+            &new.types,
+            |t| t.new_type_name.clone(),
+            |t| t.new_type_name.clone(),
+            |diff, item, o, n| diff.compare_value(item, &o.r#type, &n.r#type),
+        );
+        diff.compare(
+            "struct",
+            &self.structs,
+            &new.structs,
+            |s| s.name.clone(),
+            |s| s.name.clone(),
+            |diff, item, o, n| {
+                diff.compare_value(format!("base of {item}"), &o.base, &n.base);
+                diff.compare_fields(&o.name, &o.fields, &n.fields);
+            },
+        );
+        diff.compare(
+            "variant",
+            &self.variants,
+            &new.variants,
+            |v| v.name.clone(),
+            |v| v.name.clone(),
+            |diff, item, o, n| {
+                if o.types != n.types {
+                    let compatibility = if n.types.starts_with(&o.types) {
+                        Compatibility::Compatible
+                    } else {
+                        Compatibility::Breaking
+                    };
+                    let kind = AbiChangeKind::Changed {
+                        old: o.types.join(", "),
+                        new: n.types.join(", "),
+                    };
+                    diff.push(item, kind, compatibility);
+                }
+            },
+        );
+        diff.compare(
+            "action",
+            &self.actions,
+            &new.actions,
+            |a| a.name,
+            |a| a.name.to_string(),
+            |diff, item, o, n| diff.compare_value(item, &o.r#type, &n.r#type),
+        );
+        diff.compare(
+            "table",
+            &self.tables,
+            &new.tables,
+            |t| t.name,
+            |t| t.name.to_string(),
+            |diff, item, o, n| {
+                diff.compare_value(item.clone(), &o.r#type, &n.r#type);
+                diff.compare_value(format!("index of {item}"), &o.index_type, &n.index_type);
+                diff.compare_value(
+                    format!("keys of {item}"),
+                    &o.key_types.join(", "),
+                    &n.key_types.join(", "),
+                );
+            },
+        );
+        diff.compare(
+            "action result",
+            &self.action_results,
+            &new.action_results,
+            |r| r.name,
+            |r| r.name.to_string(),
+            |diff, item, o, n| diff.compare_value(item, &o.result_type, &n.result_type),
+        );
+        diff
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+};
+
+use crate::{
+    chain::{abi::ABI, name::Name},
+    serializer::abi_serializer::BUILTIN_TYPES,
+};
+
+/// A consistency problem found by `ABI::validate`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AbiValidationError {
+    /// `type_name`, used by `context`, is not a builtin, typedef, struct or
+    /// variant.
+    UnknownType {
+        context: String,
+        type_name: String,
+    },
+    /// The typedef is part of a cycle.
+    TypedefCycle(String),
+    /// The struct is its own (indirect) base.
+    BaseCycle(String),
+    /// Two typedefs, structs or variants share the name, or one shadows a
+    /// builtin type.
+    DuplicateType(String),
+    DuplicateAction(Name),
+    DuplicateTable(Name),
+    DuplicateActionResult(Name),
+    /// The row type of the table does not exist.
+    MissingTableType {
+        table: Name,
+        type_name: String,
+    },
+}
+
+impl Display for AbiValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbiValidationError::UnknownType { context, type_name } => {
+                write!(f, "unknown type {type_name} used by {context}")
+            }
+            AbiValidationError::TypedefCycle(name) => write!(f, "typedef {name} is cyclic"),
+            AbiValidationError::BaseCycle(name) => {
+                write!(f, "struct {name} is its own base")
+            }
+            AbiValidationError::DuplicateType(name) => write!(f, "type {name} is already defined"),
+            AbiValidationError::DuplicateAction(name) => {
+                write!(f, "action {name} defined twice")
+            }
+            AbiValidationError::DuplicateTable(name) => write!(f, "table {name} defined twice"),
+            AbiValidationError::DuplicateActionResult(name) => {
+                write!(f, "action result {name} defined twice")
+            }
+            AbiValidationError::MissingTableType { table, type_name } => {
+                write!(f, "table {table} uses missing row type {type_name}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AbiValidationError {}
+
+impl ABI {
+    /// Checks the ABI for internal consistency, returning every problem
+    /// found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use antelope::chain::abi::{AbiValidationError, ABI};
+    ///
+    /// let abi = ABI::from_string(r#"{
+    ///     "version": "eosio::abi/1.2",
+    ///     "structs": [{"name": "greet", "base": "", "fields": [
+    ///         {"name": "who", "type": "account_name"}
+    ///     ]}]
+    /// }"#).unwrap();
+    /// assert_eq!(
+    ///     abi.validate().unwrap_err(),
+    ///     vec![AbiValidationError::UnknownType {
+    ///         context: String::from("field greet.who"),
+    ///         type_name: String::from("account_name"),
+    ///     }]
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<AbiValidationError>> {
+        let mut errors = vec![];
+
+        let mut types = HashSet::new();
+        let names = self
+            .types
+            .iter()
+            .map(|t| &t.new_type_name)
+            .chain(self.structs.iter().map(|s| &s.name))
+            .chain(self.variants.iter().map(|v| &v.name));
+        for name in names {
+            if !types.insert(name.as_str()) || BUILTIN_TYPES.contains(&name.as_str()) {
+                errors.push(AbiValidationError::DuplicateType(name.clone()));
+            }
+        }
+        check_duplicates(
+            self.actions.iter().map(|a| a.name),
+            AbiValidationError::DuplicateAction,
+            &mut errors,
+        );
+        check_duplicates(
+            self.tables.iter().map(|t| t.name),
+            AbiValidationError::DuplicateTable,
+            &mut errors,
+        );
+        check_duplicates(
+            self.action_results.iter().map(|r| r.name),
+            AbiValidationError::DuplicateActionResult,
+            &mut errors,
+        );
+
+        let typedefs: HashMap<&str, &str> = self
+            .types
+            .iter()
+            .map(|t| (t.new_type_name.as_str(), t.r#type.as_str()))
+            .collect();
+        for typedef in &self.types {
+            let mut seen = HashSet::new();
+            let mut current = typedef.new_type_name.as_str();
+            while let Some(next) = typedefs.get(current) {
+                if !seen.insert(current) {
+                    errors.push(AbiValidationError::TypedefCycle(
+                        typedef.new_type_name.clone(),
+                    ));
+                    break;
+                }
+                current = next;
+            }
+        }
+
+        let structs: HashMap<&str, &str> = self
+            .structs
+            .iter()
+            .map(|s| (s.name.as_str(), s.base.as_str()))
+            .collect();
+        for s in &self.structs {
+            let mut seen = HashSet::new();
+            let mut current = s.name.as_str();
+            while let Some(base) = structs.get(current).filter(|b| !b.is_empty()) {
+                if !seen.insert(current) {
+                    errors.push(AbiValidationError::BaseCycle(s.name.clone()));
+                    break;
+                }
+                current = base;
+            }
+        }
+
+        let mut check = |context: String, type_name: &str| {
+            if !self.is_known_type(type_name) {
+                errors.push(AbiValidationError::UnknownType {
+                    context,
+                    type_name: type_name.to_string(),
+                });
+            }
+        };
+        for typedef in &self.types {
+            check(
+                format!("typedef {}", typedef.new_type_name),
+                &typedef.r#type,
+            );
+        }
+        for s in &self.structs {
+            if !s.base.is_empty() && !structs.contains_key(s.base.as_str()) {
+                check(format!("base of struct {}", s.name), &s.base);
+            }
+            for field in &s.fields {
+                check(format!("field {}.{}", s.name, field.name), &field.r#type);
+            }
+        }
+        for variant in &self.variants {
+            for t in &variant.types {
+                check(format!("variant {}", variant.name), t);
+            }
+        }
+        for action in &self.actions {
+            check(format!("action {}", action.name), &action.r#type);
+        }
+        for result in &self.action_results {
+            check(
+                format!("action result {}", result.name),
+                &result.result_type,
+            );
+        }
+        for table in &self.kv_tables {
+            check(format!("kv table {}", table.name), &table.r#type);
+        }
+
+        for table in &self.tables {
+            if !self.is_known_type(&table.r#type) {
+                errors.push(AbiValidationError::MissingTableType {
+                    table: table.name,
+                    type_name: table.r#type.clone(),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn is_known_type(&self, type_name: &str) -> bool {
+        let mut name = type_name;
+        while let Some(inner) = name
+            .strip_suffix('$')
+            .or_else(|| name.strip_suffix('?'))
+            .or_else(|| name.strip_suffix("[]"))
+        {
+            name = inner;
+        }
+        BUILTIN_TYPES.contains(&name)
+            || self.types.iter().any(|t| t.new_type_name == name)
+            || self.structs.iter().any(|s| s.name == name)
+            || self.variants.iter().any(|v| v.name == name)
+    }
+}
+
+fn check_duplicates(
+    names: impl Iterator<Item = Name>,
+    error: fn(Name) -> AbiValidationError,
+    errors: &mut Vec<AbiValidationError>,
+) {
+    let mut seen = HashSet::new();
+    for name in names {
+        if !seen.insert(name) {
+            errors.push(error(name));
+        }
+    }
+}
@@ -1,22 +1,20 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use antelope_client_macros::{AbiType, StructPacker};
+use serde::{de, Deserialize, Deserializer, Serialize};
 
 use crate::{
-    chain::{
-        checksum::Checksum256,
-        name::{deserialize_name, Name},
-        varint::VarUint32,
+    chain::{checksum::Checksum256, name::Name, varint::VarUint32},
+    serializer::{
+        decode_all, error::ensure_size, json::hex_bytes, AbiError, AbiSerializer, DecodeError,
+        Decoder, Encoder, Packer,
     },
-    serializer::{Decoder, Encoder, Packer},
 };
 use serde_json::Value;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize, AbiType)]
 pub struct PermissionLevel {
     /// The account holding the permission.
-    #[serde(deserialize_with = "deserialize_name")]
     pub actor: Name,
     /// The permission type.
-    #[serde(deserialize_with = "deserialize_name")]
     pub permission: Name,
 }
 
@@ -44,15 +42,12 @@ impl Packer for PermissionLevel {
     }
 
     /// Unpacks the PermissionLevel structure from the provided data slice.
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(
-            data.len() >= self.size(),
-            "PermissionLevel.unpack: buffer overflow"
-        );
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "PermissionLevel")?;
         let mut dec = Decoder::new(data);
-        dec.unpack(&mut self.actor);
-        dec.unpack(&mut self.permission);
-        16
+        dec.unpack(&mut self.actor)?;
+        dec.unpack(&mut self.permission)?;
+        Ok(16)
     }
 }
 
@@ -71,21 +66,90 @@ impl Ord for PermissionLevel {
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, AbiType)]
 pub struct Action {
     /// The account on which the action is executed.
-    #[serde(deserialize_with = "deserialize_name")]
     pub account: Name,
     /// The name of the action.
-    #[serde(deserialize_with = "deserialize_name")]
     pub name: Name,
     /// A list of permission levels required to execute the action.
     pub authorization: Vec<PermissionLevel>,
-    /// The action's payload data.
-    #[serde(deserialize_with = "deserialize_data")]
+    /// The action's payload data, hex encoded in JSON.
+    #[serde(serialize_with = "hex_bytes::serialize")]
     pub data: Vec<u8>,
 }
 
+/// The binary form of `Action`.
+#[derive(Deserialize)]
+#[serde(rename = "Action")]
+struct ActionFields {
+    account: Name,
+    name: Name,
+    authorization: Vec<PermissionLevel>,
+    data: Vec<u8>,
+}
+
+/// The JSON form of `Action`. nodeos writes `data` as hex, or as an object
+/// decoded with the contract ABI, in which case the packed payload is in
+/// `hex_data`.
+#[derive(Deserialize)]
+struct ActionJson {
+    account: Name,
+    name: Name,
+    authorization: Vec<PermissionLevel>,
+    #[serde(default)]
+    data: Value,
+    #[serde(default)]
+    hex_data: Option<String>,
+}
+
+impl ActionJson {
+    /// Resolves the payload from `hex_data` or hex `data`, packing object
+    /// `data` with `abi` when one is given.
+    fn into_action(self, abi: Option<&AbiSerializer>) -> Result<Action, AbiError> {
+        let data = match (&self.hex_data, &self.data, abi) {
+            (Some(hex), _, _) => {
+                hex::decode(hex).map_err(|e| AbiError::new(e.to_string()).in_field("hex_data"))?
+            }
+            (None, Value::String(hex), _) => {
+                hex::decode(hex).map_err(|e| AbiError::new(e.to_string()).in_field("data"))?
+            }
+            (None, data, Some(abi)) => abi
+                .action_to_bin(self.name, data)
+                .map_err(|e| e.in_field("data"))?,
+            (None, _, None) => {
+                return Err(AbiError::new(format!(
+                    "data of {}::{} is not hex, an ABI is needed to pack it",
+                    self.account, self.name
+                ))
+                .in_field("data"))
+            }
+        };
+        Ok(Action {
+            account: self.account,
+            name: self.name,
+            authorization: self.authorization,
+            data,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return ActionJson::deserialize(deserializer)?
+                .into_action(None)
+                .map_err(de::Error::custom);
+        }
+        ActionFields::deserialize(deserializer).map(|a| Action {
+            account: a.account,
+            name: a.name,
+            authorization: a.authorization,
+            data: a.data,
+        })
+    }
+}
+
 impl Action {
     /// Creates an action by specifying contract account, action name,
     /// authorization and data.
@@ -93,13 +157,11 @@ impl Action {
     where
         T: Packer,
     {
-        let mut enc = Encoder::new(data.size());
-        data.pack(&mut enc);
         Self {
             account,
             name,
             authorization: vec![authorization],
-            data: enc.get_bytes().to_vec(),
+            data: Encoder::pack(&data),
         }
     }
 
@@ -112,15 +174,26 @@ impl Action {
     where
         T: Packer,
     {
-        let mut enc = Encoder::new(data.size());
-        data.pack(&mut enc);
         Self {
             account,
             name,
             authorization: authorizations,
-            data: enc.get_bytes().to_vec(),
+            data: Encoder::pack(&data),
         }
     }
+
+    /// Parses an action from nodeos JSON, packing object `data` with the
+    /// contract ABI. Hex `data` and `hex_data` are used as they are.
+    pub fn from_json(value: &Value, abi: &AbiSerializer) -> Result<Self, AbiError> {
+        ActionJson::deserialize(value)
+            .map_err(|e| AbiError::new(e.to_string()))?
+            .into_action(Some(abi))
+    }
+
+    /// Unpacks the action data as `T`, which must use all of it.
+    pub fn decode_data<T: Packer + Default>(&self) -> Result<T, DecodeError> {
+        decode_all(&self.data)
+    }
 }
 
 /// Implements the Default trait for Action.
@@ -161,28 +234,19 @@ impl Packer for Action {
     }
 
     /// Unpacks the Action structure from the provided data slice.
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(data.len() >= self.size(), "Action.unpack: buffer overflow");
-
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
         let mut dec = Decoder::new(data);
-        dec.unpack(&mut self.account);
-        dec.unpack(&mut self.name);
-        dec.unpack(&mut self.authorization);
-        dec.unpack(&mut self.data);
-        dec.get_pos()
+        dec.unpack(&mut self.account)
+            .map_err(|e| e.in_field("account"))?;
+        dec.unpack(&mut self.name).map_err(|e| e.in_field("name"))?;
+        dec.unpack(&mut self.authorization)
+            .map_err(|e| e.in_field("authorization"))?;
+        dec.unpack(&mut self.data).map_err(|e| e.in_field("data"))?;
+        Ok(dec.get_pos())
     }
 }
 
-fn deserialize_data<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let data: Value = Deserialize::deserialize(deserializer)?;
-    let serialized_str = serde_json::to_string(&data).map_err(serde::de::Error::custom)?;
-    Ok(serialized_str.into_bytes())
-}
-
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize, StructPacker)]
 pub struct GetCodeHashResult {
     struct_version: VarUint32,
     code_sequence: u64,
@@ -190,35 +254,3 @@ pub struct GetCodeHashResult {
     vm_type: u8,
     vm_version: u8,
 }
-
-impl Packer for GetCodeHashResult {
-    fn size(&self) -> usize {
-        self.struct_version.size() + 8 + 32 + 1 + 1
-    }
-
-    /// Packs the Action structure into the provided Encoder.
-    fn pack(&self, enc: &mut Encoder) -> usize {
-        let pos = enc.get_size();
-
-        self.struct_version.pack(enc);
-        self.code_sequence.pack(enc);
-        self.code_hash.pack(enc);
-        self.vm_type.pack(enc);
-        self.vm_version.pack(enc);
-
-        enc.get_size() - pos
-    }
-
-    /// Unpacks the Action structure from the provided data slice.
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(data.len() >= self.size(), "Action.unpack: buffer overflow");
-
-        let mut dec = Decoder::new(data);
-        dec.unpack(&mut self.struct_version);
-        dec.unpack(&mut self.code_sequence);
-        dec.unpack(&mut self.code_hash);
-        dec.unpack(&mut self.vm_type);
-        dec.unpack(&mut self.vm_version);
-        dec.get_pos()
-    }
-}
@@ -2,8 +2,10 @@ use core::ops;
 use serde::{de, Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use crate::chain::{name::Name, Decoder, Encoder, Packer};
+use crate::serializer::{error::ensure_size, json::impl_string_serde, DecodeError};
 
 const MAX_AMOUNT: i64 = (1 << 62) - 1;
 const MAX_PRECISION: u8 = 18;
@@ -39,11 +41,13 @@ pub fn is_valid_symbol_code(sym: u64) -> bool {
     true
 }
 
-#[derive(Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
 pub struct SymbolCode {
     pub value: u64,
 }
 
+impl_string_serde!(SymbolCode, 8, SymbolCode::from_str, "a symbol code");
+
 impl SymbolCode {
     pub fn new(sym: &str) -> Self {
         let raw = sym.as_bytes();
@@ -83,6 +87,17 @@ impl SymbolCode {
     }
 }
 
+impl FromStr for SymbolCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.len() >= 7 || !s.bytes().all(|c| c.is_ascii_uppercase()) {
+            return Err(format!("Invalid symbol code: {s}"));
+        }
+        Ok(SymbolCode::new(s))
+    }
+}
+
 impl Display for SymbolCode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_string())
@@ -98,22 +113,23 @@ impl Packer for SymbolCode {
         self.value.pack(enc)
     }
 
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(
-            data.len() >= self.size(),
-            "SymbolCode.unpack: buffer overflow"
-        );
-        self.value.unpack(data);
-        assert!(self.is_valid(), "SymbolCode.unpack:: bad symbol code");
-        8
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "SymbolCode")?;
+        self.value.unpack(data)?;
+        if !self.is_valid() {
+            return Err(DecodeError::invalid("SymbolCode", "bad symbol code"));
+        }
+        Ok(8)
     }
 }
 
-#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
 pub struct Symbol {
     value: u64,
 }
 
+impl_string_serde!(Symbol, 8, Symbol::from_str, "a symbol such as 4,EOS");
+
 impl Symbol {
     pub fn new(name: &str, precision: u8) -> Self {
         let raw = name.as_bytes();
@@ -155,6 +171,27 @@ impl Symbol {
     }
 }
 
+impl FromStr for Symbol {
+    type Err = String;
+
+    /// Parses a symbol in the `precision,CODE` form, e.g. `4,EOS`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (precision, code) = s
+            .split_once(',')
+            .ok_or_else(|| format!("Invalid symbol: {s}"))?;
+        let precision: u8 = precision
+            .parse()
+            .map_err(|_| format!("Invalid symbol precision: {s}"))?;
+        if precision > MAX_PRECISION {
+            return Err(format!("Invalid symbol precision: {s}"));
+        }
+        let code = SymbolCode::from_str(code)?;
+        Ok(Symbol {
+            value: code.value() << 8 | precision as u64,
+        })
+    }
+}
+
 impl Display for Symbol {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_string())
@@ -170,20 +207,24 @@ impl Packer for Symbol {
         self.value.pack(enc)
     }
 
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(data.len() >= self.size(), "Symbol.unpack: buffer overflow");
-        self.value.unpack(data);
-        assert!(self.code().is_valid(), "Symbol.unpack: bad symbol value");
-        8
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "Symbol")?;
+        self.value.unpack(data)?;
+        if !self.code().is_valid() {
+            return Err(DecodeError::invalid("Symbol", "bad symbol value"));
+        }
+        Ok(8)
     }
 }
 
-#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
 pub struct Asset {
     amount: i64,
     symbol: Symbol,
 }
 
+impl_string_serde!(Asset, 16, Asset::from_str, "an asset such as 1.0000 EOS");
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum AssetStringParseStatus {
     Initial,
@@ -206,7 +247,61 @@ impl Asset {
     }
 
     pub fn from_string(s: &str) -> Self {
-        assert!(!s.is_empty(), "Asset.from_string: empty string");
+        s.parse().unwrap()
+    }
+
+    /// Parses a decimal amount such as `1.5` as an asset of `symbol`,
+    /// padding it to the precision of the symbol.
+    pub fn from_amount(amount: &str, symbol: Symbol) -> Result<Self, String> {
+        let parsed = Asset::from_str(&format!("{amount} {}", symbol.code()))?;
+        let precision = symbol.precision();
+        let decimals = parsed.symbol.precision();
+        if decimals > precision {
+            return Err(format!(
+                "Asset.from_amount: {amount} has more than {precision} decimals"
+            ));
+        }
+        let amount = 10i64
+            .checked_pow((precision - decimals) as u32)
+            .and_then(|scale| parsed.amount.checked_mul(scale))
+            .filter(|a| is_amount_within_range(*a))
+            .ok_or_else(|| String::from("Asset.from_amount: bad amount"))?;
+        Ok(Self { amount, symbol })
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn symbol(&self) -> Symbol {
+        self.symbol
+    }
+
+    pub fn as_string(self) -> String {
+        let precision = self.symbol.precision();
+        let sign = if self.amount < 0 { "-" } else { "" };
+        let amount = self.amount.unsigned_abs();
+        let scale = 10u64.pow(precision as u32);
+
+        let mut result = format!("{sign}{}", amount / scale);
+        if precision > 0 {
+            result += &format!(".{:0width$}", amount % scale, width = precision);
+        }
+        result + " " + &self.symbol.code().to_string()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        is_amount_within_range(self.amount) && self.symbol().is_valid()
+    }
+}
+
+impl FromStr for Asset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(String::from("Asset.from_string: empty string"));
+        }
         let mut status = AssetStringParseStatus::Initial;
         let mut raw = s.as_bytes();
 
@@ -223,52 +318,61 @@ impl Asset {
 
         for &c in raw {
             if c == b'.' {
-                assert!(
-                    status == AssetStringParseStatus::Initial,
-                    "Asset.from_string: invalid dot character"
-                );
+                if status != AssetStringParseStatus::Initial {
+                    return Err(String::from("Asset.from_string: invalid dot character"));
+                }
                 status = AssetStringParseStatus::FoundDot;
                 continue;
             } else if c == b' ' {
-                assert!(
-                    status == AssetStringParseStatus::Initial
-                        || status == AssetStringParseStatus::FoundDot,
-                    "Asset.from_string: invalid space character"
-                );
-                // if status == AssetStringParseStatus::FoundDot {
-                //     assert!(precision > 0, "Asset.from_string: invalid precision");
-                // }
+                if status != AssetStringParseStatus::Initial
+                    && status != AssetStringParseStatus::FoundDot
+                {
+                    return Err(String::from("Asset.from_string: invalid space character"));
+                }
                 status = AssetStringParseStatus::FoundSpace;
                 continue;
             }
 
             match status {
                 AssetStringParseStatus::Initial => {
-                    assert!(c.is_ascii_digit(), "Asset.from_string: bad amount");
+                    if !c.is_ascii_digit() {
+                        return Err(String::from("Asset.from_string: bad amount"));
+                    }
                     amount *= 10;
                     amount += (c - b'0') as i64;
-                    assert!(is_amount_within_range(amount), "bad amount");
+                    if !is_amount_within_range(amount) {
+                        return Err(String::from("Asset.from_string: bad amount"));
+                    }
                 }
                 AssetStringParseStatus::FoundDot => {
-                    assert!(c.is_ascii_digit(), "Asset.from_string: bad amount");
+                    if !c.is_ascii_digit() {
+                        return Err(String::from("Asset.from_string: bad amount"));
+                    }
                     amount *= 10;
                     amount += (c - b'0') as i64;
                     precision += 1;
-                    assert!(
-                        precision <= MAX_PRECISION,
-                        "Asset.from_string: bad precision"
-                    );
-                    assert!(is_amount_within_range(amount), "bad amount");
+                    if precision > MAX_PRECISION {
+                        return Err(String::from("Asset.from_string: bad precision"));
+                    }
+                    if !is_amount_within_range(amount) {
+                        return Err(String::from("Asset.from_string: bad amount"));
+                    }
                 }
                 AssetStringParseStatus::FoundSpace => {
-                    assert!(c.is_ascii_uppercase(), "Asset.from_string: bad symbol");
+                    if !c.is_ascii_uppercase() {
+                        return Err(String::from("Asset.from_string: bad symbol"));
+                    }
                     raw_symbol.push(c);
-                    assert!(raw_symbol.len() < 7, "Asset.from_string: bad symbol");
+                    if raw_symbol.len() >= 7 {
+                        return Err(String::from("Asset.from_string: bad symbol"));
+                    }
                 }
             }
         }
 
-        assert!(!raw_symbol.is_empty(), "Asset.from_string: bad symbol");
+        if raw_symbol.is_empty() {
+            return Err(String::from("Asset.from_string: bad symbol"));
+        }
 
         if minus {
             amount = -amount;
@@ -283,44 +387,10 @@ impl Asset {
         symbol <<= 8;
         symbol |= precision as u64;
 
-        Self {
+        Ok(Self {
             amount,
             symbol: Symbol { value: symbol },
-        }
-    }
-
-    pub fn amount(&self) -> i64 {
-        self.amount
-    }
-
-    pub fn symbol(&self) -> Symbol {
-        self.symbol
-    }
-
-    pub fn as_string(self) -> String {
-        let mut part1: i64 = self.amount;
-
-        for _ in 0..self.symbol.precision() {
-            part1 /= 10;
-        }
-
-        let mut part2: Vec<u8> = vec![0u8; self.symbol.precision()];
-
-        let mut tmp: i64 = self.amount;
-        for i in (0..self.symbol.precision()).rev() {
-            part2[i] = b'0' + (tmp % 10) as u8;
-            tmp /= 10;
-        }
-        let mut decimal = String::from_utf8(part2).unwrap();
-        if !decimal.is_empty() {
-            decimal = String::from(".") + decimal.as_str();
-        }
-
-        part1.to_string() + decimal.as_str() + " " + &self.symbol.code().to_string()
-    }
-
-    pub fn is_valid(&self) -> bool {
-        is_amount_within_range(self.amount) && self.symbol().is_valid()
+        })
     }
 }
 
@@ -392,17 +462,16 @@ impl Packer for Asset {
         enc.get_size() - pos
     }
 
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(data.len() >= self.size(), "Asset.unpack: buffer overflow");
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "Asset")?;
 
         let mut dec = Decoder::new(data);
-        dec.unpack(&mut self.amount);
-        assert!(
-            self.amount >= -MAX_AMOUNT && self.amount <= MAX_AMOUNT,
-            "Asset.unpack: bad asset amount"
-        );
-        dec.unpack(&mut self.symbol);
-        dec.get_pos()
+        dec.unpack(&mut self.amount)?;
+        if !is_amount_within_range(self.amount) {
+            return Err(DecodeError::invalid("Asset", "bad asset amount"));
+        }
+        dec.unpack(&mut self.symbol)?;
+        Ok(dec.get_pos())
     }
 }
 
@@ -457,7 +526,7 @@ where
     deserializer.deserialize_option(OptionalAssetVisitor)
 }
 
-#[derive(Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ExtendedAsset {
     quantity: Asset,
     contract: Name,
@@ -491,15 +560,12 @@ impl Packer for ExtendedAsset {
         enc.get_size() - pos
     }
 
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(
-            data.len() >= self.size(),
-            "ExtendedAsset.unpack: buffer overflow"
-        );
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "ExtendedAsset")?;
 
         let mut dec = Decoder::new(data);
-        dec.unpack(&mut self.quantity);
-        dec.unpack(&mut self.contract);
-        dec.get_pos()
+        dec.unpack(&mut self.quantity)?;
+        dec.unpack(&mut self.contract)?;
+        Ok(dec.get_pos())
     }
 }
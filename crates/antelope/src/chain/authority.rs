@@ -1,35 +1,33 @@
 use crate::chain::action::PermissionLevel;
-use crate::chain::public_key::{deserialize_public_key, PublicKey};
-use crate::serializer::{Decoder, Encoder, Packer};
-use antelope_client_macros::StructPacker;
+use crate::chain::public_key::PublicKey;
+use antelope_client_macros::{AbiType, StructPacker};
 use serde::{Deserialize, Serialize};
 
 // Assuming basic types like PublicKey and PermissionLevel are defined elsewhere
 
 /// KeyWeight associates a PublicKey with a Weight.
-#[derive(Serialize, Deserialize, Debug, Clone, Default, StructPacker)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, StructPacker, AbiType)]
 pub struct KeyWeight {
-    #[serde(deserialize_with = "deserialize_public_key")]
     pub key: PublicKey,
     pub weight: u16,
 }
 
 /// PermissionLevelWeight associates a PermissionLevel with a Weight.
-#[derive(Serialize, Deserialize, Debug, Clone, Default, StructPacker)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, StructPacker, AbiType)]
 pub struct PermissionLevelWeight {
     pub permission: PermissionLevel,
     pub weight: u16,
 }
 
 /// WaitWeight associates a wait time (in seconds) with a Weight.
-#[derive(Serialize, Deserialize, Debug, Clone, Default, StructPacker)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, StructPacker, AbiType)]
 pub struct WaitWeight {
     pub wait_sec: u32,
     pub weight: u16,
 }
 
 /// Authority defines a set of keys and/or accounts that can authorize an action.
-#[derive(Serialize, Deserialize, Debug, Clone, Default, StructPacker)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, StructPacker, AbiType)]
 pub struct Authority {
     pub threshold: u32,
     pub keys: Vec<KeyWeight>,
@@ -1,11 +1,72 @@
-use crate::serializer::{Encoder, Packer};
-use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
 
-#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+use crate::serializer::{ser::BINARY_EXTENSION_TOKEN, DecodeError, Encoder, Packer};
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
 pub struct BinaryExtension<T: Packer + Default> {
     value: Option<T>,
 }
 
+impl<T> Serialize for BinaryExtension<T>
+where
+    T: Packer + Default + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            // the value itself, or null when absent
+            self.value.serialize(serializer)
+        } else {
+            serializer.serialize_newtype_struct(BINARY_EXTENSION_TOKEN, &self.value)
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for BinaryExtension<T>
+where
+    T: Packer + Default + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return Option::<T>::deserialize(deserializer).map(BinaryExtension::new);
+        }
+
+        struct BinaryExtensionVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for BinaryExtensionVisitor<T>
+        where
+            T: Packer + Default + Deserialize<'de>,
+        {
+            type Value = BinaryExtension<T>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a binary extension")
+            }
+
+            fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+                Ok(BinaryExtension::new(None))
+            }
+
+            fn visit_some<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                T::deserialize(deserializer).map(|v| BinaryExtension::new(Some(v)))
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                Option::<T>::deserialize(deserializer).map(BinaryExtension::new)
+            }
+        }
+
+        deserializer
+            .deserialize_newtype_struct(BINARY_EXTENSION_TOKEN, BinaryExtensionVisitor(PhantomData))
+    }
+}
+
 impl<T> BinaryExtension<T>
 where
     T: Packer + Default,
@@ -39,15 +100,15 @@ where
         enc.get_size() - pos
     }
 
-    fn unpack(&mut self, data: &[u8]) -> usize {
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
         if !data.is_empty() {
             let mut value = T::default();
-            let size = value.unpack(data);
+            let size = value.unpack(data)?;
             self.value = Some(value);
-            size
+            Ok(size)
         } else {
             self.value = None;
-            0
+            Ok(0)
         }
     }
 }
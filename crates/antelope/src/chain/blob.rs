@@ -1,6 +1,6 @@
 use base64::{
     alphabet,
-    engine::{general_purpose::PAD, GeneralPurpose},
+    engine::{general_purpose::PAD, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
     Engine as _,
 };
 
@@ -18,6 +18,12 @@ pub struct Blob {
 
 pub const STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, PAD);
 
+/// Decodes base64 with or without trailing padding.
+const DECODER: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
 impl Blob {
     pub fn from(value: BlobType) -> Result<Blob, &'static str> {
         match value {
@@ -31,7 +37,7 @@ impl Blob {
         let value_without_padding: String = value.trim_end_matches('=').to_string();
 
         // Convert base64 string to bytes
-        match STANDARD.decode(value_without_padding) {
+        match DECODER.decode(value_without_padding) {
             Ok(bytes) => Ok(Blob { array: bytes }),
             Err(_) => Err("Invalid base64 string"),
         }
@@ -1,4 +1,3 @@
-use crate::chain::{Decoder, Encoder, Packer};
 use antelope_client_macros::StructPacker;
 use serde::{
     de::{self, Visitor},
@@ -8,8 +7,15 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
+use crate::serializer::json::hex_bytes;
+
 #[derive(Clone, Default, Eq, PartialEq, StructPacker, Serialize, Deserialize, Debug)]
+#[serde(transparent)]
 pub struct BlockId {
+    #[serde(
+        serialize_with = "hex_bytes::serialize",
+        deserialize_with = "deserialize_id_bytes"
+    )]
     pub bytes: Vec<u8>,
 }
 
@@ -33,6 +39,31 @@ impl BlockId {
             | u32::from(num_bytes[3])
     }
 
+    /// The `ref_block_num` of a transaction referencing this block, the low
+    /// 16 bits of its number.
+    pub fn ref_block_num(&self) -> Result<u16, String> {
+        match self.bytes.get(2..4) {
+            Some(&[high, low]) => Ok(u16::from_be_bytes([high, low])),
+            _ => Err(self.too_short("ref_block_num", 4)),
+        }
+    }
+
+    /// The `ref_block_prefix` of a transaction referencing this block, bytes
+    /// 8 to 12 of the id read as a little endian integer.
+    pub fn ref_block_prefix(&self) -> Result<u32, String> {
+        match self.bytes.get(8..12) {
+            Some(&[a, b, c, d]) => Ok(u32::from_le_bytes([a, b, c, d])),
+            _ => Err(self.too_short("ref_block_prefix", 12)),
+        }
+    }
+
+    fn too_short(&self, field: &str, expected: usize) -> String {
+        format!(
+            "BlockId.{field} expected at least {expected} bytes, got {}",
+            self.bytes.len()
+        )
+    }
+
     pub fn as_string(&self) -> String {
         self.block_num().to_string()
     }
@@ -44,6 +75,17 @@ impl Display for BlockId {
     }
 }
 
+/// Decodes the hex bytes of a `BlockId`, which must be 32 bytes long.
+fn deserialize_id_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let bytes = hex_bytes::deserialize(deserializer)?;
+    BlockId::from_bytes(&bytes)
+        .map(|id| id.bytes)
+        .map_err(de::Error::custom)
+}
+
 pub(crate) fn deserialize_block_id<'de, D>(deserializer: D) -> Result<BlockId, D::Error>
 where
     D: Deserializer<'de>,
@@ -3,26 +3,33 @@ use std::fmt::{Display, Formatter};
 
 use ripemd::{Digest as Ripemd160Digest, Ripemd160};
 use serde::de::Visitor;
-use serde::{de, Deserialize, Deserializer, Serialize};
-use serde_big_array::BigArray;
+use serde::{de, Deserializer};
 use sha2::{Sha256, Sha512};
 
 use crate::{
     chain::{Encoder, Packer},
-    util::{bytes_to_hex, hex_to_bytes, slice_copy},
+    serializer::{error::ensure_size, json::impl_string_serde, DecodeError},
+    util::{bytes_to_hex, slice_copy},
 };
 
-#[derive(Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug)]
 pub struct Checksum160 {
     pub data: [u8; 20],
 }
 
+impl_string_serde!(
+    Checksum160,
+    20,
+    Checksum160::from_hex,
+    "a hex string of 20 bytes"
+);
+
 impl Checksum160 {
     pub fn from_hex(s: &str) -> Result<Self, String> {
         if s.len() != 40 {
             return Err(String::from("Checksum160: bad hex string length"));
         }
-        let data = hex_to_bytes(s);
+        let data = hex::decode(s).map_err(|e| format!("Checksum160: {e}"))?;
         Self::from_bytes(data.as_slice())
     }
 
@@ -62,19 +69,26 @@ impl Packer for Checksum160 {
         pack_checksum(self.size(), &self.data, enc)
     }
 
-    fn unpack(&mut self, raw: &[u8]) -> usize {
+    fn unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
         let size = self.size();
-        assert!(raw.len() >= size, "Checksum160.unpack: buffer overflow!");
+        ensure_size(raw, size, "Checksum160")?;
         slice_copy(&mut self.data, &raw[..size]);
-        size
+        Ok(size)
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug)]
 pub struct Checksum256 {
     pub data: [u8; 32],
 }
 
+impl_string_serde!(
+    Checksum256,
+    32,
+    Checksum256::from_hex,
+    "a hex string of 32 bytes"
+);
+
 pub(crate) fn deserialize_checksum256<'de, D>(deserializer: D) -> Result<Checksum256, D::Error>
 where
     D: Deserializer<'de>,
@@ -115,7 +129,7 @@ impl Checksum256 {
         if s.len() != 64 {
             return Err(String::from("Checksum256: bad hex string length"));
         }
-        let data = hex_to_bytes(s);
+        let data = hex::decode(s).map_err(|e| format!("Checksum256: {e}"))?;
         Self::from_bytes(data.as_slice())
     }
 
@@ -169,26 +183,32 @@ impl Packer for Checksum256 {
         pack_checksum(self.size(), &self.data, enc)
     }
 
-    fn unpack(&mut self, raw: &[u8]) -> usize {
+    fn unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
         let size = self.size();
-        assert!(raw.len() >= size, "Checksum256.unpack: buffer overflow!");
+        ensure_size(raw, size, "Checksum256")?;
         slice_copy(&mut self.data, &raw[..size]);
-        size
+        Ok(size)
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Checksum512 {
-    #[serde(with = "BigArray")]
     pub data: [u8; 64],
 }
 
+impl_string_serde!(
+    Checksum512,
+    64,
+    Checksum512::from_hex,
+    "a hex string of 64 bytes"
+);
+
 impl Checksum512 {
     pub fn from_hex(s: &str) -> Result<Self, String> {
         if s.len() != 128 {
             return Err(String::from("Checksum512: bad hex string length"));
         }
-        let data = hex_to_bytes(s);
+        let data = hex::decode(s).map_err(|e| format!("Checksum512: {e}"))?;
         Ok(Self::from_bytes(data.as_slice()))
     }
 
@@ -229,11 +249,11 @@ impl Packer for Checksum512 {
         pack_checksum(self.size(), &self.data, enc)
     }
 
-    fn unpack(&mut self, raw: &[u8]) -> usize {
+    fn unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
         let size = self.size();
-        assert!(raw.len() >= size, "Checksum512.unpack: buffer overflow!");
+        ensure_size(raw, size, "Checksum512")?;
         slice_copy(&mut self.data, &raw[..size]);
-        size
+        Ok(size)
     }
 }
 
@@ -3,6 +3,7 @@ use std::fmt::{Display, Formatter};
 use serde::{Deserialize, Serialize};
 
 use crate::chain::{Encoder, Packer};
+use crate::serializer::{error::ensure_size, DecodeError};
 
 #[derive(Clone, Debug, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
 pub enum KeyType {
@@ -92,12 +93,10 @@ impl Packer for KeyType {
         self.size()
     }
 
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(
-            data.len() >= self.size(),
-            "KeyType::unpack: buffer overflow"
-        );
-        *self = KeyType::from_index(data[0]).unwrap();
-        self.size()
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "KeyType")?;
+        *self = KeyType::from_index(data[0])
+            .map_err(|_| DecodeError::bad_variant("KeyType", data[0] as u64))?;
+        Ok(self.size())
     }
 }
@@ -15,6 +15,7 @@ pub mod public_key;
 pub mod signature;
 pub mod time;
 pub mod transaction;
+pub mod transaction_extension;
 pub mod varint;
 
 #[macro_export]
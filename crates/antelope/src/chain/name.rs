@@ -1,12 +1,13 @@
 use std::{
     fmt,
     fmt::{Display, Formatter},
+    str::FromStr,
 };
 
 use serde::de::SeqAccess;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serializer};
 
-use crate::serializer::{Encoder, Packer};
+use crate::serializer::{error::ensure_size, json, DecodeError, Encoder, Packer};
 
 const INVALID_NAME_CHAR: u8 = 0xffu8;
 
@@ -111,17 +112,11 @@ pub fn n2s(value: u64) -> String {
         }
     }
 
-    let mut i = s.len() - 1;
-    while i != 0 {
-        if s[i] != b'.' {
-            break;
-        }
+    let mut i = s.len();
+    while i > 0 && s[i - 1] == b'.' {
         i -= 1;
     }
-    if i == 0 {
-        return String::from("");
-    }
-    String::from_utf8(s[0..i + 1].to_vec()).unwrap()
+    String::from_utf8(s[0..i].to_vec()).unwrap()
 }
 
 fn str_to_name(s: &str) -> u64 {
@@ -181,11 +176,33 @@ fn str_to_name_checked(s: &str) -> u64 {
 /// a wrapper around a 64-bit unsigned integer that represents a name in the
 /// Antelope blockchain
 #[repr(C, align(8))]
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Name {
     pub n: u64,
 }
 
+// Binary serde writes the raw u64 rather than the packed bytes, so that map
+// keys sort by value like `Ord` does; both encode to the same 8 bytes
+impl serde::Serialize for Name {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            json::serialize_display(self, serializer)
+        } else {
+            serializer.serialize_u64(self.n)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Name {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            json::deserialize_parsed(deserializer, "an account name", Name::from_str)
+        } else {
+            u64::deserialize(deserializer).map(|n| Name { n })
+        }
+    }
+}
+
 impl Name {
     pub fn new(s: &'static str) -> Self {
         Name { n: s2n(s) }
@@ -211,6 +228,20 @@ impl Name {
     }
 }
 
+impl FromStr for Name {
+    type Err = String;
+
+    /// Parses a name, rejecting invalid characters and names that are not
+    /// normalized (e.g. with trailing dots), as nodeos does.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let n = str_to_name(s);
+        if n == INVALID_NAME || n2s(n) != s {
+            return Err(format!("Invalid name: {s}"));
+        }
+        Ok(Name { n })
+    }
+}
+
 impl Display for Name {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_string())
@@ -226,10 +257,10 @@ impl Packer for Name {
         self.n.pack(enc)
     }
 
-    fn unpack(&mut self, raw: &[u8]) -> usize {
-        assert!(raw.len() >= 8, "Name.unpack: buffer overflow!");
+    fn unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(raw, 8, "Name")?;
         self.n = u64::from_ne_bytes(raw[0..8].try_into().unwrap());
-        8
+        Ok(8)
     }
 }
 
@@ -245,10 +276,22 @@ impl Ord for Name {
     }
 }
 
+pub(crate) fn serialize_name<S>(name: &Name, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&name.as_string())
+}
+
 pub(crate) fn deserialize_name<'de, D>(deserializer: D) -> Result<Name, D::Error>
 where
     D: Deserializer<'de>,
 {
+    // the binary form is the packed value, not a string
+    if !deserializer.is_human_readable() {
+        return Name::deserialize(deserializer);
+    }
+
     struct NameVisitor;
 
     impl serde::de::Visitor<'_> for NameVisitor {
@@ -1,6 +1,6 @@
 use std::fmt::{Debug, Display, Formatter};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
     base58::{decode_key, encode_check, encode_ripemd160_check},
@@ -10,14 +10,50 @@ use crate::{
     crypto::{
         generate::generate, get_public::get_public, shared_secrets::shared_secret, sign::sign,
     },
+    serializer::json::{deserialize_parsed, serialize_display},
 };
 
-#[derive(Default, Clone, Serialize, Deserialize)]
+#[derive(Default, Clone)]
 pub struct PrivateKey {
     pub key_type: KeyType,
     value: Vec<u8>,
 }
 
+/// The binary form of `PrivateKey`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "PrivateKey")]
+struct PrivateKeyFields {
+    key_type: KeyType,
+    value: Vec<u8>,
+}
+
+impl Serialize for PrivateKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serialize_display(self, serializer);
+        }
+        PrivateKeyFields {
+            key_type: self.key_type,
+            value: self.value.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PrivateKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return deserialize_parsed(deserializer, "a private key", |s| {
+                PrivateKey::from_str(s, false)
+            });
+        }
+        PrivateKeyFields::deserialize(deserializer).map(|k| PrivateKey {
+            key_type: k.key_type,
+            value: k.value,
+        })
+    }
+}
+
 impl PrivateKey {
     // TODO: should this be done via the ToString trait?
     //   If so, should other structs also do that?
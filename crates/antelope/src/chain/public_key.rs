@@ -1,18 +1,76 @@
 use crate::{
     base58::{decode_public_key, encode_ripemd160_check},
     chain::{key_type::KeyType, Decoder, Encoder, Packer},
+    serializer::{
+        error::ensure_size,
+        json::{deserialize_parsed, serialize_display},
+        DecodeError,
+    },
     util::bytes_to_hex,
 };
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct PublicKey {
     pub key_type: KeyType,
     pub value: Vec<u8>,
 }
 
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serialize_display(self, serializer);
+        }
+        // same layout as `Packer`: the key type followed by the raw key
+        let mut tuple = serializer.serialize_tuple(1 + self.value.len())?;
+        tuple.serialize_element(&self.key_type)?;
+        for b in &self.value {
+            tuple.serialize_element(b)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return deserialize_parsed(deserializer, "a public key", PublicKey::new_from_str);
+        }
+
+        struct PackedPublicKeyVisitor;
+
+        impl<'de> Visitor<'de> for PackedPublicKeyVisitor {
+            type Value = PublicKey;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a packed public key")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let key_type = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let mut value = Vec::with_capacity(33);
+                for i in 0..33 {
+                    let b: u8 = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i + 1, &self))?;
+                    value.push(b);
+                }
+                Ok(PublicKey { key_type, value })
+            }
+        }
+
+        deserializer.deserialize_tuple(34, PackedPublicKeyVisitor)
+    }
+}
+
 impl Packer for PublicKey {
     fn size(&self) -> usize {
         34usize
@@ -27,17 +85,18 @@ impl Packer for PublicKey {
         enc.get_size() - pos
     }
 
-    fn unpack(&mut self, data: &[u8]) -> usize {
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "PublicKey")?;
         let mut dec = Decoder::new(data);
-        let mut key_type = KeyType::default();
-        dec.unpack(&mut key_type);
-        self.value.reserve(32usize);
+        dec.unpack(&mut self.key_type)?;
+        self.value.clear();
+        self.value.reserve(33usize);
         for _ in 0..33 {
             let mut v: u8 = Default::default();
-            dec.unpack(&mut v);
+            dec.unpack(&mut v)?;
             self.value.push(v);
         }
-        dec.get_pos()
+        Ok(dec.get_pos())
     }
 }
 
@@ -101,6 +160,11 @@ pub fn deserialize_public_key<'de, D>(deserializer: D) -> Result<PublicKey, D::E
 where
     D: Deserializer<'de>,
 {
+    // the binary form is the packed value, not a string
+    if !deserializer.is_human_readable() {
+        return PublicKey::deserialize(deserializer);
+    }
+
     struct PublicKeyVisitor;
 
     impl serde::de::Visitor<'_> for PublicKeyVisitor {
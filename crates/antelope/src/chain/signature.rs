@@ -5,8 +5,9 @@ use ecdsa::RecoveryId;
 use k256::Secp256k1;
 use p256::NistP256;
 use serde::{
-    de::{self, Visitor},
-    Deserialize, Deserializer, Serialize,
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserialize, Deserializer, Serialize, Serializer,
 };
 
 use crate::chain::varint::VarUint32;
@@ -19,15 +20,80 @@ use crate::{
         Encoder, Packer,
     },
     crypto::{recover::recover_message, verify::verify_message},
+    serializer::{
+        error::ensure_size,
+        json::{deserialize_parsed, serialize_display},
+        DecodeError,
+    },
     util::slice_copy,
 };
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Signature {
     pub key_type: KeyType,
     value: Vec<u8>,
 }
 
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serialize_display(self, serializer);
+        }
+        // same layout as `Packer`: the key type followed by the raw signature
+        let mut tuple = serializer.serialize_tuple(1 + self.value.len())?;
+        tuple.serialize_element(&self.key_type)?;
+        for b in &self.value {
+            tuple.serialize_element(b)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return deserialize_parsed(deserializer, "a signature", Signature::from_string);
+        }
+
+        struct PackedSignatureVisitor;
+
+        impl<'de> Visitor<'de> for PackedSignatureVisitor {
+            type Value = Signature;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a packed signature")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let key_type = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let mut value = Vec::with_capacity(65);
+                for i in 0..65 {
+                    let b: u8 = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i + 1, &self))?;
+                    value.push(b);
+                }
+                if key_type == KeyType::WA {
+                    // webauthn signatures append the auth data and client json
+                    let mut enc = Encoder::new(0);
+                    for i in 0..2 {
+                        let data: Vec<u8> = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(66 + i, &self))?;
+                        data.pack(&mut enc);
+                    }
+                    value.extend_from_slice(enc.get_bytes());
+                }
+                Ok(Signature { key_type, value })
+            }
+        }
+
+        deserializer.deserialize_tuple(68, PackedSignatureVisitor)
+    }
+}
+
 impl Signature {
     pub const RECOVERY_ID_ADDITION: u8 = 31;
 
@@ -74,14 +140,16 @@ impl Signature {
             return Err(format!("String did not start with SIG_: {s}"));
         }
         let parts: Vec<&str> = s.split('_').collect();
-        let key_type = KeyType::from_string(parts[1]).unwrap();
+        if parts.len() != 3 {
+            return Err(format!("Invalid signature format: {s}"));
+        }
+        let key_type = KeyType::from_string(parts[1])?;
         let size = match key_type {
             KeyType::K1 | KeyType::R1 => Some(65),
             KeyType::WA => None,
         };
 
-        let value =
-            base58::decode_ripemd160_check(parts[2], size, Option::from(key_type), false).unwrap();
+        let value = base58::decode_ripemd160_check(parts[2], size, Option::from(key_type), false)?;
         Ok(Signature { key_type, value })
     }
 
@@ -201,32 +269,36 @@ impl Packer for Signature {
         self.size()
     }
 
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        self.key_type = KeyType::from_index(data[0]).unwrap();
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, 1, "Signature")?;
+        self.key_type = KeyType::from_index(data[0])
+            .map_err(|_| DecodeError::bad_variant("Signature", data[0] as u64))?;
         match self.key_type {
             KeyType::K1 | KeyType::R1 => {
+                ensure_size(data, 66, "Signature")?;
                 self.value = data[1..66].to_vec();
             }
             KeyType::WA => {
                 let mut size = 66; // size to start = 1 byte for key type, 65 bytes for compact signature
+                ensure_size(data, size, "Signature")?;
                 let mut auth_data = VarUint32::default();
                 // unpack() returns how many bytes were read to unpack the value
-                size += auth_data.unpack(&data[size..]); // after the compact sig comes a varuint32 to tell us the size of the auth data
+                size += auth_data.unpack(&data[size..]).map_err(|e| e.at(size))?; // after the compact sig comes a varuint32 to tell us the size of the auth data
                 size += auth_data.value() as usize; // add the auth data size
+                ensure_size(data, size, "Signature")?;
                 let mut client_json = VarUint32::default();
-                size += client_json.unpack(&data[size..]); // read the varuint32 size of the client_json
+                size += client_json.unpack(&data[size..]).map_err(|e| e.at(size))?; // read the varuint32 size of the client_json
                 size += client_json.value() as usize; // add the client_json size
-                                                      // set value to be the whole payload (after the key type byte):
-                                                      //      compact sig,
-                                                      //      varuint32 auth data size,
-                                                      //      auth data,
-                                                      //      varuint32 client_json size,
-                                                      //      client_json
+                ensure_size(data, size, "Signature")?;
+                // set value to be the whole payload (after the key type byte):
+                //      compact sig,
+                //      varuint32 auth data size,
+                //      auth data,
+                //      varuint32 client_json size,
+                //      client_json
                 self.value = data[1..size].to_vec();
             }
         }
-        let size = self.size();
-        assert!(data.len() >= size, "Signature::unpack: buffer overflow");
-        self.size()
+        Ok(self.size())
     }
 }
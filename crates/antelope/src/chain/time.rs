@@ -1,16 +1,19 @@
 use chrono::{NaiveDateTime, TimeZone, Utc};
-use serde::{de, Deserialize, Deserializer, Serialize};
+use serde::{de, Deserializer};
 use std::fmt;
 use std::str::FromStr;
 
 use crate::chain::{Encoder, Packer};
+use crate::serializer::{error::ensure_size, json::impl_string_serde, DecodeError};
 
-#[derive(Copy, Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
 pub struct TimePoint {
     /// elapsed in microseconds
     pub elapsed: u64,
 }
 
+impl_string_serde!(TimePoint, 8, TimePoint::from_str, "an ISO-8601 time point");
+
 impl FromStr for TimePoint {
     type Err = String;
 
@@ -31,9 +34,23 @@ impl TimePoint {
         let date_time = Utc.from_utc_datetime(&naive_date_time.unwrap());
 
         Ok(Self {
-            elapsed: (date_time.timestamp_millis() * 1000) as u64,
+            elapsed: date_time.timestamp_micros() as u64,
         })
     }
+
+    /// Formats as `2023-12-16T16:17:47.500`, the form used by nodeos.
+    pub fn as_string(&self) -> String {
+        format_datetime(
+            NaiveDateTime::from_timestamp_micros(self.elapsed as i64),
+            "%Y-%m-%dT%H:%M:%S%.3f",
+        )
+    }
+}
+
+impl fmt::Display for TimePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_string())
+    }
 }
 
 impl Packer for TimePoint {
@@ -45,20 +62,24 @@ impl Packer for TimePoint {
         self.elapsed.pack(enc)
     }
 
-    fn unpack(&mut self, raw: &[u8]) -> usize {
-        assert!(
-            raw.len() >= self.size(),
-            "TimePoint.unpack: buffer overflow!"
-        );
+    fn unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(raw, self.size(), "TimePoint")?;
         self.elapsed.unpack(raw)
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
 pub struct TimePointSec {
     pub seconds: u32,
 }
 
+impl_string_serde!(
+    TimePointSec,
+    4,
+    TimePointSec::from_str,
+    "an ISO-8601 time point"
+);
+
 impl TimePointSec {
     pub fn new(seconds: u32) -> Self {
         Self { seconds }
@@ -67,6 +88,35 @@ impl TimePointSec {
     pub fn seconds(&self) -> u32 {
         self.seconds
     }
+
+    pub fn from_timestamp(t: &str) -> Result<Self, String> {
+        let time_point = TimePoint::from_timestamp(t)?;
+        let seconds = u32::try_from(time_point.elapsed / 1_000_000)
+            .map_err(|_| format!("Timestamp out of range: {t}"))?;
+        Ok(Self { seconds })
+    }
+
+    /// Formats as `2023-12-16T16:17:47`, the form used by nodeos.
+    pub fn as_string(&self) -> String {
+        format_datetime(
+            NaiveDateTime::from_timestamp_opt(self.seconds as i64, 0),
+            "%Y-%m-%dT%H:%M:%S",
+        )
+    }
+}
+
+impl FromStr for TimePointSec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TimePointSec::from_timestamp(s)
+    }
+}
+
+impl fmt::Display for TimePointSec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_string())
+    }
 }
 
 impl Packer for TimePointSec {
@@ -78,15 +128,90 @@ impl Packer for TimePointSec {
         self.seconds.pack(enc)
     }
 
-    fn unpack(&mut self, raw: &[u8]) -> usize {
-        assert!(
-            raw.len() >= self.size(),
-            "TimePointSec.unpack: buffer overflow!"
-        );
+    fn unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(raw, self.size(), "TimePointSec")?;
         self.seconds.unpack(raw)
     }
 }
 
+/// Milliseconds between the unix epoch and the block timestamp epoch
+/// (2000-01-01T00:00:00.000).
+const BLOCK_TIMESTAMP_EPOCH_MS: u64 = 946_684_800_000;
+const BLOCK_INTERVAL_MS: u64 = 500;
+
+/// A block slot, counted in half seconds since 2000-01-01.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlockTimestamp {
+    pub slot: u32,
+}
+
+impl_string_serde!(
+    BlockTimestamp,
+    4,
+    BlockTimestamp::from_str,
+    "an ISO-8601 block timestamp"
+);
+
+impl BlockTimestamp {
+    pub fn new(slot: u32) -> Self {
+        Self { slot }
+    }
+
+    pub fn from_timestamp(t: &str) -> Result<Self, String> {
+        let millis = TimePoint::from_timestamp(t)?.elapsed / 1000;
+        let slot = millis
+            .checked_sub(BLOCK_TIMESTAMP_EPOCH_MS)
+            .and_then(|ms| u32::try_from(ms / BLOCK_INTERVAL_MS).ok())
+            .ok_or_else(|| format!("Timestamp out of range: {t}"))?;
+        Ok(Self { slot })
+    }
+
+    pub fn to_time_point(&self) -> TimePoint {
+        TimePoint {
+            elapsed: (self.slot as u64 * BLOCK_INTERVAL_MS + BLOCK_TIMESTAMP_EPOCH_MS) * 1000,
+        }
+    }
+
+    pub fn as_string(&self) -> String {
+        self.to_time_point().as_string()
+    }
+}
+
+impl FromStr for BlockTimestamp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BlockTimestamp::from_timestamp(s)
+    }
+}
+
+impl fmt::Display for BlockTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_string())
+    }
+}
+
+impl Packer for BlockTimestamp {
+    fn size(&self) -> usize {
+        4
+    }
+
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        self.slot.pack(enc)
+    }
+
+    fn unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(raw, self.size(), "BlockTimestamp")?;
+        self.slot.unpack(raw)
+    }
+}
+
+fn format_datetime(date_time: Option<NaiveDateTime>, format: &str) -> String {
+    date_time
+        .map(|d| d.format(format).to_string())
+        .unwrap_or_default()
+}
+
 pub(crate) fn deserialize_timepoint<'de, D>(deserializer: D) -> Result<TimePoint, D::Error>
 where
     D: Deserializer<'de>,
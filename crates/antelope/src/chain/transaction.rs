@@ -6,19 +6,47 @@ use serde_json::{json, Value};
 
 use crate::{
     chain::{
-        action::Action, checksum::Checksum256, signature::Signature, time::TimePointSec,
-        varint::VarUint32, Decoder, Encoder, Packer,
+        action::Action, checksum::Checksum256, private_key::PrivateKey, signature::Signature,
+        time::TimePointSec, varint::VarUint32, Encoder,
     },
-    util::{bytes_to_hex, zlib_compress},
+    serializer::{
+        decode_all,
+        json::{hex_bytes, hex_bytes_vec},
+    },
+    util::{bytes_to_hex, zlib_compress, zlib_decompress},
 };
 
 #[derive(Clone, Eq, PartialEq, Default, StructPacker, Serialize, Deserialize)]
+#[serde(
+    from = "TransactionExtensionFields",
+    into = "TransactionExtensionFields"
+)]
 pub struct TransactionExtension {
     pub ty: u16,
     pub data: Vec<u8>,
 }
 
-#[derive(Clone, Eq, PartialEq, Default, StructPacker, Serialize, Deserialize)]
+/// The serde form of `TransactionExtension`, a `[type, "hex data"]` pair in
+/// JSON as nodeos writes it.
+#[derive(Clone, Serialize, Deserialize)]
+struct TransactionExtensionFields(u16, #[serde(with = "hex_bytes")] Vec<u8>);
+
+impl From<TransactionExtensionFields> for TransactionExtension {
+    fn from(fields: TransactionExtensionFields) -> Self {
+        Self {
+            ty: fields.0,
+            data: fields.1,
+        }
+    }
+}
+
+impl From<TransactionExtension> for TransactionExtensionFields {
+    fn from(extension: TransactionExtension) -> Self {
+        Self(extension.ty, extension.data)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default, StructPacker, Serialize, Deserialize)]
 pub struct TransactionHeader {
     pub expiration: TimePointSec,
     pub ref_block_num: u16,
@@ -29,6 +57,7 @@ pub struct TransactionHeader {
 }
 
 #[derive(Clone, Eq, PartialEq, Default, StructPacker, Serialize, Deserialize)]
+#[serde(from = "TransactionFields", into = "TransactionFields")]
 pub struct Transaction {
     pub header: TransactionHeader,
     pub context_free_actions: Vec<Action>,
@@ -36,32 +65,203 @@ pub struct Transaction {
     pub extension: Vec<TransactionExtension>,
 }
 
+/// The serde form of `Transaction`, with the header fields inline as nodeos
+/// writes them. Nesting adds no bytes, so the binary form is the same.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename = "Transaction")]
+struct TransactionFields {
+    expiration: TimePointSec,
+    ref_block_num: u16,
+    ref_block_prefix: u32,
+    max_net_usage_words: VarUint32,
+    max_cpu_usage_ms: u8,
+    delay_sec: VarUint32,
+    context_free_actions: Vec<Action>,
+    actions: Vec<Action>,
+    transaction_extensions: Vec<TransactionExtension>,
+}
+
+impl From<TransactionFields> for Transaction {
+    fn from(fields: TransactionFields) -> Self {
+        Self {
+            header: TransactionHeader {
+                expiration: fields.expiration,
+                ref_block_num: fields.ref_block_num,
+                ref_block_prefix: fields.ref_block_prefix,
+                max_net_usage_words: fields.max_net_usage_words,
+                max_cpu_usage_ms: fields.max_cpu_usage_ms,
+                delay_sec: fields.delay_sec,
+            },
+            context_free_actions: fields.context_free_actions,
+            actions: fields.actions,
+            extension: fields.transaction_extensions,
+        }
+    }
+}
+
+impl From<Transaction> for TransactionFields {
+    fn from(trx: Transaction) -> Self {
+        Self {
+            expiration: trx.header.expiration,
+            ref_block_num: trx.header.ref_block_num,
+            ref_block_prefix: trx.header.ref_block_prefix,
+            max_net_usage_words: trx.header.max_net_usage_words,
+            max_cpu_usage_ms: trx.header.max_cpu_usage_ms,
+            delay_sec: trx.header.delay_sec,
+            context_free_actions: trx.context_free_actions,
+            actions: trx.actions,
+            transaction_extensions: trx.extension,
+        }
+    }
+}
+
 impl Transaction {
     pub fn id(&self) -> Vec<u8> {
         Checksum256::hash(Encoder::pack(self)).data.to_vec()
     }
 
+    /// Returns the data signed for a transaction without context free data.
     pub fn signing_data(&self, chain_id: &[u8]) -> Vec<u8> {
+        self.signing_data_with_cfd(chain_id, &[])
+    }
+
+    pub fn signing_digest(&self, chain_id: &[u8]) -> Vec<u8> {
+        self.signing_digest_with_cfd(chain_id, &[])
+    }
+
+    /// Returns the data signed for a transaction carrying the context free
+    /// data (CFD) `context_free_data`: the chain id, the packed transaction
+    /// and the hash of the packed CFD, or 32 zero bytes when there is none.
+    pub fn signing_data_with_cfd(&self, chain_id: &[u8], context_free_data: &[Vec<u8>]) -> Vec<u8> {
         let mut bytes = chain_id.to_vec();
         let encoded = &mut Encoder::pack(self);
         bytes.append(encoded);
-        bytes.append(&mut vec![0u8; 32]);
+        bytes.extend_from_slice(&context_free_data_digest(context_free_data).data);
         bytes
     }
 
-    pub fn signing_digest(&self, chain_id: &[u8]) -> Vec<u8> {
-        Checksum256::hash(self.signing_data(chain_id)).data.to_vec()
+    pub fn signing_digest_with_cfd(
+        &self,
+        chain_id: &[u8],
+        context_free_data: &[Vec<u8>],
+    ) -> Vec<u8> {
+        Checksum256::hash(self.signing_data_with_cfd(chain_id, context_free_data))
+            .data
+            .to_vec()
     }
 }
 
+/// Returns the hash of the packed context free data, or zero when there is
+/// none, as included in the signing digest.
+fn context_free_data_digest(context_free_data: &[Vec<u8>]) -> Checksum256 {
+    if context_free_data.is_empty() {
+        return Checksum256::default();
+    }
+    Checksum256::hash(Encoder::pack(&context_free_data.to_vec()))
+}
+
 #[derive(Clone, Eq, PartialEq, Default, StructPacker, Serialize, Deserialize)]
+#[serde(from = "SignedTransactionFields", into = "SignedTransactionFields")]
 pub struct SignedTransaction {
     pub transaction: Transaction,
     pub signatures: Vec<Signature>,
     pub context_free_data: Vec<Vec<u8>>,
 }
 
-#[derive(PartialEq)]
+/// The serde form of `SignedTransaction`, with the transaction fields inline
+/// as nodeos writes them.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename = "SignedTransaction")]
+struct SignedTransactionFields {
+    expiration: TimePointSec,
+    ref_block_num: u16,
+    ref_block_prefix: u32,
+    max_net_usage_words: VarUint32,
+    max_cpu_usage_ms: u8,
+    delay_sec: VarUint32,
+    context_free_actions: Vec<Action>,
+    actions: Vec<Action>,
+    transaction_extensions: Vec<TransactionExtension>,
+    signatures: Vec<Signature>,
+    #[serde(with = "hex_bytes_vec")]
+    context_free_data: Vec<Vec<u8>>,
+}
+
+impl From<SignedTransactionFields> for SignedTransaction {
+    fn from(fields: SignedTransactionFields) -> Self {
+        let transaction = TransactionFields {
+            expiration: fields.expiration,
+            ref_block_num: fields.ref_block_num,
+            ref_block_prefix: fields.ref_block_prefix,
+            max_net_usage_words: fields.max_net_usage_words,
+            max_cpu_usage_ms: fields.max_cpu_usage_ms,
+            delay_sec: fields.delay_sec,
+            context_free_actions: fields.context_free_actions,
+            actions: fields.actions,
+            transaction_extensions: fields.transaction_extensions,
+        };
+        Self {
+            transaction: transaction.into(),
+            signatures: fields.signatures,
+            context_free_data: fields.context_free_data,
+        }
+    }
+}
+
+impl From<SignedTransaction> for SignedTransactionFields {
+    fn from(signed: SignedTransaction) -> Self {
+        let trx = TransactionFields::from(signed.transaction);
+        Self {
+            expiration: trx.expiration,
+            ref_block_num: trx.ref_block_num,
+            ref_block_prefix: trx.ref_block_prefix,
+            max_net_usage_words: trx.max_net_usage_words,
+            max_cpu_usage_ms: trx.max_cpu_usage_ms,
+            delay_sec: trx.delay_sec,
+            context_free_actions: trx.context_free_actions,
+            actions: trx.actions,
+            transaction_extensions: trx.transaction_extensions,
+            signatures: signed.signatures,
+            context_free_data: signed.context_free_data,
+        }
+    }
+}
+
+impl SignedTransaction {
+    pub fn new(transaction: Transaction) -> Self {
+        Self {
+            transaction,
+            signatures: Vec::new(),
+            context_free_data: Vec::new(),
+        }
+    }
+
+    /// Attaches the context free data, usually one entry per context free
+    /// action. Signatures made before this no longer match.
+    pub fn with_context_free_data(mut self, context_free_data: Vec<Vec<u8>>) -> Self {
+        self.context_free_data = context_free_data;
+        self
+    }
+
+    pub fn signing_data(&self, chain_id: &[u8]) -> Vec<u8> {
+        self.transaction
+            .signing_data_with_cfd(chain_id, &self.context_free_data)
+    }
+
+    pub fn signing_digest(&self, chain_id: &[u8]) -> Vec<u8> {
+        self.transaction
+            .signing_digest_with_cfd(chain_id, &self.context_free_data)
+    }
+
+    /// Signs the transaction and its context free data with `private_key`
+    /// and adds the signature.
+    pub fn sign(&mut self, private_key: &PrivateKey, chain_id: &[u8]) {
+        let signature = private_key.sign_message(&self.signing_data(chain_id));
+        self.signatures.push(signature);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CompressionType {
     ZLIB,
     NONE,
@@ -74,14 +274,79 @@ impl CompressionType {
             CompressionType::ZLIB => 1,
         }
     }
+
+    pub fn from_index(index: u8) -> Result<Self, String> {
+        match index {
+            0 => Ok(CompressionType::NONE),
+            1 => Ok(CompressionType::ZLIB),
+            _ => Err(format!("Unknown compression type {index}")),
+        }
+    }
 }
 
+/// The most bytes the transaction or the context free data of a
+/// `PackedTransaction` may decompress to unless configured otherwise, the
+/// limit nodeos applies.
+pub const MAX_DECOMPRESSED_SIZE: usize = 1024 * 1024;
+
+/// A transaction as sent to nodeos, with the transaction and its context
+/// free data packed and optionally compressed. Fields missing from JSON
+/// default to empty, as nodeos allows.
 #[derive(Clone, Eq, PartialEq, Default, StructPacker, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PackedTransaction {
     signatures: Vec<Signature>,
+    #[serde(with = "compression_serde")]
     compression: Option<u8>,
+    #[serde(with = "hex_bytes")]
     packed_context_free_data: Vec<u8>,
+    #[serde(rename = "packed_trx", with = "hex_bytes")]
     packed_transaction: Vec<u8>,
+    /// Decompression limit, `MAX_DECOMPRESSED_SIZE` when not set.
+    #[serde(skip)]
+    #[packer(skip)]
+    max_decompressed_size: Option<usize>,
+}
+
+/// Serializes the compression of a `PackedTransaction` by name in JSON, as
+/// nodeos does, while also accepting the numeric form.
+mod compression_serde {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+
+    pub(super) fn serialize<S: Serializer>(
+        compression: &Option<u8>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return compression.serialize(serializer);
+        }
+        match compression {
+            None => serializer.serialize_none(),
+            Some(0) => serializer.serialize_str("none"),
+            Some(1) => serializer.serialize_str("zlib"),
+            Some(n) => serializer.serialize_u8(*n),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u8>, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Option::<u8>::deserialize(deserializer);
+        }
+        match Value::deserialize(deserializer)? {
+            Value::Null => Ok(None),
+            Value::String(s) if s == "none" => Ok(Some(0)),
+            Value::String(s) if s == "zlib" => Ok(Some(1)),
+            Value::Number(n) => n
+                .as_u64()
+                .and_then(|n| u8::try_from(n).ok())
+                .map(Some)
+                .ok_or_else(|| de::Error::custom(format!("invalid compression: {n}"))),
+            other => Err(de::Error::custom(format!("invalid compression: {other}"))),
+        }
+    }
 }
 
 impl PackedTransaction {
@@ -90,10 +355,16 @@ impl PackedTransaction {
         compression: CompressionType,
     ) -> Result<Self, String> {
         let mut packed_transaction = Encoder::pack(&signed.transaction);
-        let mut packed_context_free_data = Encoder::pack(&signed.context_free_data);
+        // as in nodeos, no context free data packs to nothing at all
+        let mut packed_context_free_data = Vec::new();
+        if !signed.context_free_data.is_empty() {
+            packed_context_free_data = Encoder::pack(&signed.context_free_data);
+        }
         if compression == CompressionType::ZLIB {
             packed_transaction = zlib_compress(packed_transaction.as_slice())?;
-            packed_context_free_data = zlib_compress(packed_context_free_data.as_slice())?;
+            if !packed_context_free_data.is_empty() {
+                packed_context_free_data = zlib_compress(packed_context_free_data.as_slice())?;
+            }
         }
 
         Ok(Self {
@@ -101,18 +372,107 @@ impl PackedTransaction {
             compression: Some(compression.index() as u8),
             packed_transaction,
             packed_context_free_data,
+            max_decompressed_size: None,
+        })
+    }
+
+    /// Parses the nodeos JSON form, e.g. `{"compression": "zlib",
+    /// "packed_trx": "78da..."}`.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        Self::deserialize(value).map_err(|e| format!("Invalid packed transaction: {e}"))
+    }
+
+    /// Sets the most bytes the transaction and the context free data may
+    /// each decompress to, `MAX_DECOMPRESSED_SIZE` by default.
+    pub fn with_max_decompressed_size(mut self, size: usize) -> Self {
+        self.max_decompressed_size = Some(size);
+        self
+    }
+
+    pub fn signatures(&self) -> &[Signature] {
+        &self.signatures
+    }
+
+    /// Returns the compression, `NONE` when it is not set.
+    pub fn compression(&self) -> Result<CompressionType, String> {
+        CompressionType::from_index(self.compression.unwrap_or(0))
+    }
+
+    /// Returns the packed transaction, still compressed.
+    pub fn packed_transaction(&self) -> &[u8] {
+        &self.packed_transaction
+    }
+
+    /// Returns the packed context free data, still compressed.
+    pub fn packed_context_free_data(&self) -> &[u8] {
+        &self.packed_context_free_data
+    }
+
+    /// Decompresses and unpacks the transaction.
+    pub fn get_transaction(&self) -> Result<Transaction, String> {
+        let data = self.decompress(&self.packed_transaction)?;
+        decode_all(&data).map_err(|e| format!("Invalid packed transaction: {e}"))
+    }
+
+    /// Decompresses and unpacks the context free data, which may be empty.
+    pub fn get_context_free_data(&self) -> Result<Vec<Vec<u8>>, String> {
+        if self.packed_context_free_data.is_empty() {
+            return Ok(Vec::new());
+        }
+        let data = self.decompress(&self.packed_context_free_data)?;
+        decode_all(&data).map_err(|e| format!("Invalid packed context free data: {e}"))
+    }
+
+    /// Returns true if the packed context free data is present.
+    pub fn has_context_free_data(&self) -> bool {
+        !self.packed_context_free_data.is_empty()
+    }
+
+    /// Returns true if the transaction has context free actions but its
+    /// context free data was pruned, as nodeos may do for old blocks. The
+    /// signatures can then no longer be checked against the digest.
+    pub fn is_pruned(&self) -> Result<bool, String> {
+        Ok(!self.has_context_free_data()
+            && !self.get_transaction()?.context_free_actions.is_empty())
+    }
+
+    /// Drops the context free data, keeping the transaction and signatures.
+    /// The transaction id does not cover the context free data, so it is
+    /// unchanged.
+    pub fn prune_context_free_data(&mut self) {
+        self.packed_context_free_data.clear();
+    }
+
+    pub fn get_signed_transaction(&self) -> Result<SignedTransaction, String> {
+        Ok(SignedTransaction {
+            transaction: self.get_transaction()?,
+            signatures: self.signatures.clone(),
+            context_free_data: self.get_context_free_data()?,
         })
     }
 
+    /// Returns the transaction id, the hash of the uncompressed transaction.
+    pub fn id(&self) -> Result<Vec<u8>, String> {
+        let data = self.decompress(&self.packed_transaction)?;
+        Ok(Checksum256::hash(data).data.to_vec())
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        match self.compression()? {
+            CompressionType::NONE => Ok(data.to_vec()),
+            CompressionType::ZLIB => zlib_decompress(
+                data,
+                self.max_decompressed_size.unwrap_or(MAX_DECOMPRESSED_SIZE),
+            ),
+        }
+    }
+
     pub fn to_json(&self) -> Value {
         let mut trx: HashMap<&str, Value> = HashMap::new();
         let signatures: Vec<String> = self.signatures.iter().map(|sig| sig.to_string()).collect();
         trx.insert("signatures", json!(signatures));
-        if self.compression.is_some() {
-            trx.insert(
-                "compression",
-                Value::Number(self.compression.unwrap().into()),
-            );
+        if let Some(compression) = self.compression {
+            trx.insert("compression", Value::Number(compression.into()));
         }
         trx.insert(
             "packed_context_free_data",
@@ -0,0 +1,123 @@
+use antelope_client_macros::StructPacker;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chain::{
+        checksum::Checksum256,
+        name::Name,
+        transaction::{Transaction, TransactionExtension},
+    },
+    serializer::{decode_all, DecodeError, Encoder, Packer},
+};
+
+/// A typed transaction extension, packed into `TransactionExtension::data`
+/// under the type id `ID`.
+///
+/// Implement it to register an extension type of your own.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::chain::{name::Name, transaction::Transaction};
+/// use antelope::chain::transaction_extension::ResourcePayer;
+/// use antelope::name;
+///
+/// let mut trx = Transaction::default();
+/// trx.set_extension(&ResourcePayer {
+///     payer: name!("sponsor"),
+///     max_net_bytes: 4096,
+///     max_cpu_us: 400,
+///     max_memory_bytes: 0,
+/// });
+/// assert_eq!(trx.extension[0].ty, 1);
+/// let payer: ResourcePayer = trx.get_extension().unwrap().unwrap();
+/// assert_eq!(payer.payer, name!("sponsor"));
+/// ```
+pub trait TypedExtension: Packer + Default {
+    /// The type id of the extension.
+    const ID: u16;
+}
+
+/// Identifies the transaction that created a deferred transaction, type 0.
+#[derive(Clone, Debug, Default, Eq, PartialEq, StructPacker, Serialize, Deserialize)]
+pub struct DeferredTransactionGenerationContext {
+    pub sender_trx_id: Checksum256,
+    pub sender_id: u128,
+    pub sender: Name,
+}
+
+impl TypedExtension for DeferredTransactionGenerationContext {
+    const ID: u16 = 0;
+}
+
+/// Has `payer` pay for the resources of the transaction up to the given
+/// limits, type 1.
+#[derive(Clone, Debug, Default, Eq, PartialEq, StructPacker, Serialize, Deserialize)]
+pub struct ResourcePayer {
+    pub payer: Name,
+    pub max_net_bytes: u64,
+    pub max_cpu_us: u64,
+    pub max_memory_bytes: u64,
+}
+
+impl TypedExtension for ResourcePayer {
+    const ID: u16 = 1;
+}
+
+impl TransactionExtension {
+    /// Packs a typed extension.
+    pub fn new<T: TypedExtension>(value: &T) -> Self {
+        Self {
+            ty: T::ID,
+            data: Encoder::pack(value),
+        }
+    }
+
+    /// Unpacks the data as `T`, which must use all of it. Returns `None` if
+    /// the extension has another type id.
+    pub fn decode<T: TypedExtension>(&self) -> Result<Option<T>, DecodeError> {
+        if self.ty != T::ID {
+            return Ok(None);
+        }
+        decode_all(&self.data).map(Some)
+    }
+}
+
+impl Transaction {
+    /// Adds the extension `value`, replacing one of the same type and
+    /// keeping the extensions sorted by type id.
+    pub fn set_extension<T: TypedExtension>(&mut self, value: &T) {
+        let extension = TransactionExtension::new(value);
+        match self.extension.binary_search_by_key(&T::ID, |e| e.ty) {
+            Ok(i) => self.extension[i] = extension,
+            Err(i) => self.extension.insert(i, extension),
+        }
+    }
+
+    /// Returns the extension of type `T`, if there is one.
+    pub fn get_extension<T: TypedExtension>(&self) -> Result<Option<T>, DecodeError> {
+        for extension in &self.extension {
+            if let Some(value) = extension.decode()? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks that the extensions are sorted by type id and that each type
+    /// appears at most once, as nodeos requires.
+    pub fn validate_extensions(&self) -> Result<(), String> {
+        for pair in self.extension.windows(2) {
+            let (previous, current) = (pair[0].ty, pair[1].ty);
+            if previous == current {
+                return Err(format!("Duplicate transaction extension {current}"));
+            }
+            if previous > current {
+                return Err(format!(
+                    "Transaction extension {current} is not sorted, it follows {previous}"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,50 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::serializer::{Encoder, Packer};
+use crate::serializer::{ser::VARUINT32_TOKEN, DecodeError, Encoder, Packer};
 
-#[derive(Copy, Clone, Eq, PartialEq, Default, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
 pub struct VarUint32 {
     /// The unsigned integer value.
     pub n: u32,
 }
 
+impl Serialize for VarUint32 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            self.n.serialize(serializer)
+        } else {
+            serializer.serialize_newtype_struct(VARUINT32_TOKEN, &self.n)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VarUint32 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return u32::deserialize(deserializer).map(VarUint32::new);
+        }
+
+        struct VarUint32Visitor;
+
+        impl<'de> Visitor<'de> for VarUint32Visitor {
+            type Value = VarUint32;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a varuint32")
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                u32::deserialize(deserializer).map(VarUint32::new)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(VARUINT32_TOKEN, VarUint32Visitor)
+    }
+}
+
 impl VarUint32 {
     /// Create a new VarUint32 instance with the given value.
     pub fn new(n: u32) -> Self {
@@ -59,7 +96,7 @@ impl Packer for VarUint32 {
     }
 
     /// Deserialize the VarUint32 value from the given byte slice.
-    fn unpack(&mut self, data: &[u8]) -> usize {
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
         let mut by: u32 = 0;
         let mut value: u32 = 0;
         let mut length: usize = 0;
@@ -68,12 +105,89 @@ impl Packer for VarUint32 {
             by += 7;
             length += 1;
             if (*b & 0x80) == 0 {
-                break;
+                self.n = value;
+                return Ok(length);
+            }
+            if by >= 32 {
+                return Err(DecodeError::invalid(
+                    "VarUint32",
+                    "malformed varuint32 data",
+                ));
             }
-            assert!(by < 32, "malformed varuint32 data");
         }
-        self.n = value;
-        length
+        Err(DecodeError::eof("VarUint32", length + 1, data.len()))
+    }
+}
+
+/// A signed 32-bit integer packed as a zigzag encoded varuint32, the ABI
+/// `varint32` type.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub struct VarInt32 {
+    /// The signed integer value.
+    pub n: i32,
+}
+
+impl Serialize for VarInt32 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            self.n.serialize(serializer)
+        } else {
+            VarUint32::new(self.zigzag()).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VarInt32 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return i32::deserialize(deserializer).map(VarInt32::new);
+        }
+        VarUint32::deserialize(deserializer).map(|v| VarInt32::from_zigzag(v.value()))
+    }
+}
+
+impl VarInt32 {
+    /// Create a new VarInt32 instance with the given value.
+    pub fn new(n: i32) -> Self {
+        Self { n }
+    }
+
+    /// Get the value of the VarInt32 instance.
+    pub fn value(&self) -> i32 {
+        self.n
+    }
+
+    /// Maps the value to an unsigned one, keeping small negative numbers
+    /// small: 0, -1, 1, -2 become 0, 1, 2, 3.
+    fn zigzag(&self) -> u32 {
+        ((self.n << 1) ^ (self.n >> 31)) as u32
+    }
+
+    fn from_zigzag(n: u32) -> Self {
+        Self::new(((n >> 1) as i32) ^ -((n & 1) as i32))
+    }
+}
+
+impl Packer for VarInt32 {
+    /// Calculate the size of the serialized VarInt32.
+    fn size(&self) -> usize {
+        VarUint32::new(self.zigzag()).size()
+    }
+
+    /// Serialize the VarInt32 value.
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        VarUint32::new(self.zigzag()).pack(enc)
+    }
+
+    /// Deserialize the VarInt32 value from the given byte slice.
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut value = VarUint32::default();
+        let size = value.unpack(data).map_err(|mut e| {
+            e.type_name = "VarInt32";
+            e
+        })?;
+        *self = Self::from_zigzag(value.value());
+        Ok(size)
     }
 }
 
@@ -106,9 +220,41 @@ mod tests {
             varuint32.pack(&mut encoder);
 
             let mut unpacked_varuint32 = VarUint32::default();
-            let _ = unpacked_varuint32.unpack(encoder.get_bytes());
+            unpacked_varuint32.unpack(encoder.get_bytes()).unwrap();
 
             assert_eq!(varuint32, unpacked_varuint32);
         }
     }
+
+    #[test]
+    fn test_varint32_pack_unpack() {
+        let cases = [
+            (0, vec![0]),
+            (-1, vec![1]),
+            (1, vec![2]),
+            (-64, vec![127]),
+            (64, vec![128, 1]),
+            (i32::MAX, vec![0xfe, 0xff, 0xff, 0xff, 0x0f]),
+            (i32::MIN, vec![0xff, 0xff, 0xff, 0xff, 0x0f]),
+        ];
+
+        for (value, expected) in cases {
+            let varint32 = VarInt32::new(value);
+            let data = Encoder::pack(&varint32);
+            assert_eq!(data, expected);
+            assert_eq!(varint32.size(), data.len());
+
+            let mut unpacked_varint32 = VarInt32::default();
+            unpacked_varint32.unpack(&data).unwrap();
+            assert_eq!(varint32, unpacked_varint32);
+        }
+    }
+
+    #[test]
+    fn test_varuint32_unpack_errors() {
+        let mut value = VarUint32::default();
+        assert!(value.unpack(&[]).is_err());
+        assert!(value.unpack(&[0x80, 0x80]).is_err());
+        assert!(value.unpack(&[0xff, 0xff, 0xff, 0xff, 0xff, 0x01]).is_err());
+    }
 }
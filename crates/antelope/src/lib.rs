@@ -1,3 +1,5 @@
+extern crate self as antelope;
+
 pub mod api;
 pub mod base58;
 pub mod chain;
@@ -5,4 +7,4 @@ pub mod crypto;
 pub mod serializer;
 pub mod util;
 
-pub use antelope_client_macros::{EnumPacker, StructPacker};
+pub use antelope_client_macros::{antelope_abi, AbiType, EnumPacker, StructPacker, UnpackBorrowed};
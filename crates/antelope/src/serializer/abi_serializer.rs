@@ -0,0 +1,684 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde_json::{Map, Number, Value};
+
+use crate::chain::{
+    abi::{AbiStruct, AbiVariant, ABI},
+    asset::{Asset, Symbol, SymbolCode},
+    checksum::{Checksum160, Checksum256, Checksum512},
+    name::Name,
+    public_key::PublicKey,
+    signature::Signature,
+    time::{BlockTimestamp, TimePoint, TimePointSec},
+    varint::{VarInt32, VarUint32},
+};
+use crate::serializer::{packer::Float128, DecodeError, Decoder, Encoder, Packer};
+
+/// Limit for typedef chains and nested types, guards against cyclic ABIs.
+const MAX_DEPTH: usize = 64;
+
+pub(crate) const BUILTIN_TYPES: [&str; 31] = [
+    "bool",
+    "int8",
+    "uint8",
+    "int16",
+    "uint16",
+    "int32",
+    "uint32",
+    "int64",
+    "uint64",
+    "int128",
+    "uint128",
+    "varint32",
+    "varuint32",
+    "float32",
+    "float64",
+    "float128",
+    "time_point",
+    "time_point_sec",
+    "block_timestamp_type",
+    "name",
+    "bytes",
+    "string",
+    "checksum160",
+    "checksum256",
+    "checksum512",
+    "public_key",
+    "signature",
+    "symbol",
+    "symbol_code",
+    "asset",
+    "extended_asset",
+];
+
+/// One step of the path to the value an `AbiError` refers to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+/// Error returned by `AbiSerializer`, with the JSON path of the failing value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AbiError {
+    pub path: Vec<PathSegment>,
+    pub message: String,
+}
+
+impl AbiError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            path: Vec::new(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn in_field(mut self, name: &str) -> Self {
+        self.path.insert(0, PathSegment::Field(name.to_string()));
+        self
+    }
+
+    fn at_index(mut self, index: usize) -> Self {
+        self.path.insert(0, PathSegment::Index(index));
+        self
+    }
+
+    /// Returns the path in `$.foo.bar[6].baz` form.
+    pub fn path_string(&self) -> String {
+        let mut path = String::from("$");
+        for segment in &self.path {
+            match segment {
+                PathSegment::Field(name) => {
+                    path.push('.');
+                    path.push_str(name);
+                }
+                PathSegment::Index(index) => path.push_str(&format!("[{index}]")),
+            }
+        }
+        path
+    }
+}
+
+impl Display for AbiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.message, self.path_string())
+    }
+}
+
+impl std::error::Error for AbiError {}
+
+impl From<DecodeError> for AbiError {
+    fn from(value: DecodeError) -> Self {
+        AbiError::new(value.to_string())
+    }
+}
+
+/// Converts between JSON and the binary format for the types of an `ABI`.
+///
+/// Supports typedefs, base structs, variants, `T?` optionals, `T[]` arrays
+/// and `T$` binary extensions, using the same JSON forms as nodeos.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::chain::abi::ABI;
+/// use antelope::serializer::AbiSerializer;
+/// use serde_json::json;
+///
+/// let abi = ABI::from_string(r#"{
+///     "version": "eosio::abi/1.2",
+///     "structs": [{"name": "greet", "base": "", "fields": [
+///         {"name": "who", "type": "name"},
+///         {"name": "times", "type": "uint8"}
+///     ]}]
+/// }"#).unwrap();
+/// let serializer = AbiSerializer::new(abi);
+///
+/// let value = json!({"who": "alice", "times": 3});
+/// let data = serializer.json_to_bin("greet", &value).unwrap();
+/// assert_eq!(data, vec![0, 0, 0, 0, 0, 0x85, 0x5c, 0x34, 3]);
+/// assert_eq!(serializer.bin_to_json("greet", &data).unwrap(), value);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AbiSerializer {
+    abi: ABI,
+    typedefs: HashMap<String, String>,
+    structs: HashMap<String, AbiStruct>,
+    variants: HashMap<String, AbiVariant>,
+    actions: HashMap<Name, String>,
+    tables: HashMap<Name, String>,
+    action_results: HashMap<Name, String>,
+}
+
+impl AbiSerializer {
+    pub fn new(abi: ABI) -> Self {
+        Self {
+            typedefs: abi
+                .types
+                .iter()
+                .map(|t| (t.new_type_name.clone(), t.r#type.clone()))
+                .collect(),
+            structs: abi
+                .structs
+                .iter()
+                .map(|s| (s.name.clone(), s.clone()))
+                .collect(),
+            variants: abi
+                .variants
+                .iter()
+                .map(|v| (v.name.clone(), v.clone()))
+                .collect(),
+            actions: abi
+                .actions
+                .iter()
+                .map(|a| (a.name, a.r#type.clone()))
+                .collect(),
+            tables: abi
+                .tables
+                .iter()
+                .map(|t| (t.name, t.r#type.clone()))
+                .collect(),
+            action_results: abi
+                .action_results
+                .iter()
+                .map(|r| (r.name, r.result_type.clone()))
+                .collect(),
+            abi,
+        }
+    }
+
+    pub fn abi(&self) -> &ABI {
+        &self.abi
+    }
+
+    /// Returns the type of the data for the given action.
+    pub fn action_type(&self, action: Name) -> Option<&str> {
+        self.actions.get(&action).map(String::as_str)
+    }
+
+    /// Returns the row type of the given table.
+    pub fn table_type(&self, table: Name) -> Option<&str> {
+        self.tables.get(&table).map(String::as_str)
+    }
+
+    /// Returns the return value type of the given action.
+    pub fn action_result_type(&self, action: Name) -> Option<&str> {
+        self.action_results.get(&action).map(String::as_str)
+    }
+
+    /// Encodes `value` as the ABI type `type_name`.
+    pub fn json_to_bin(&self, type_name: &str, value: &Value) -> Result<Vec<u8>, AbiError> {
+        let mut enc = Encoder::new(0);
+        self.encode(type_name, value, &mut enc, 0)?;
+        Ok(enc.get_bytes().to_vec())
+    }
+
+    /// Decodes `data` as the ABI type `type_name`.
+    pub fn bin_to_json(&self, type_name: &str, data: &[u8]) -> Result<Value, AbiError> {
+        let mut dec = Decoder::new(data);
+        self.decode(type_name, &mut dec, 0)
+    }
+
+    /// Encodes the data of `action`.
+    pub fn action_to_bin(&self, action: Name, value: &Value) -> Result<Vec<u8>, AbiError> {
+        let type_name = self
+            .action_type(action)
+            .ok_or_else(|| AbiError::new(format!("Unknown action: {action}")))?;
+        self.json_to_bin(type_name, value)
+    }
+
+    /// Decodes the data of `action`.
+    pub fn bin_to_action(&self, action: Name, data: &[u8]) -> Result<Value, AbiError> {
+        let type_name = self
+            .action_type(action)
+            .ok_or_else(|| AbiError::new(format!("Unknown action: {action}")))?;
+        self.bin_to_json(type_name, data)
+    }
+
+    /// Decodes a row of `table`.
+    pub fn bin_to_table_row(&self, table: Name, data: &[u8]) -> Result<Value, AbiError> {
+        let type_name = self
+            .table_type(table)
+            .ok_or_else(|| AbiError::new(format!("Unknown table: {table}")))?;
+        self.bin_to_json(type_name, data)
+    }
+
+    fn resolve<'a>(&'a self, type_name: &'a str) -> Result<&'a str, AbiError> {
+        let mut resolved = type_name;
+        for _ in 0..MAX_DEPTH {
+            match self.typedefs.get(resolved) {
+                Some(next) => resolved = next,
+                None => return Ok(resolved),
+            }
+        }
+        Err(AbiError::new(format!("Typedef cycle for type {type_name}")))
+    }
+
+    fn get_struct(&self, name: &str) -> Result<&AbiStruct, AbiError> {
+        let resolved = self.resolve(name)?;
+        self.structs
+            .get(resolved)
+            .ok_or_else(|| AbiError::new(format!("Unknown struct: {name}")))
+    }
+
+    fn encode(
+        &self,
+        type_name: &str,
+        value: &Value,
+        enc: &mut Encoder,
+        depth: usize,
+    ) -> Result<(), AbiError> {
+        if depth > MAX_DEPTH {
+            return Err(AbiError::new("Maximum nesting depth exceeded"));
+        }
+        let type_name = self.resolve(type_name)?;
+
+        if let Some(inner) = type_name.strip_suffix('$') {
+            return self.encode(inner, value, enc, depth + 1);
+        }
+        if let Some(inner) = type_name.strip_suffix('?') {
+            if value.is_null() {
+                0u8.pack(enc);
+                return Ok(());
+            }
+            1u8.pack(enc);
+            return self.encode(inner, value, enc, depth + 1);
+        }
+        if let Some(inner) = type_name.strip_suffix("[]") {
+            let items = value
+                .as_array()
+                .ok_or_else(|| expected("array", type_name, value))?;
+            VarUint32::new(items.len() as u32).pack(enc);
+            for (i, item) in items.iter().enumerate() {
+                self.encode(inner, item, enc, depth + 1)
+                    .map_err(|e| e.at_index(i))?;
+            }
+            return Ok(());
+        }
+        if BUILTIN_TYPES.contains(&type_name) {
+            return encode_builtin(type_name, value, enc);
+        }
+        if let Some(s) = self.structs.get(type_name) {
+            let object = value
+                .as_object()
+                .ok_or_else(|| expected("object", type_name, value))?;
+            let mut extensions_ended = false;
+            return self.encode_fields(s, object, enc, depth + 1, &mut extensions_ended);
+        }
+        if let Some(v) = self.variants.get(type_name) {
+            return self.encode_variant(v, value, enc, depth + 1);
+        }
+        Err(AbiError::new(format!("Unknown type: {type_name}")))
+    }
+
+    fn encode_fields(
+        &self,
+        s: &AbiStruct,
+        object: &Map<String, Value>,
+        enc: &mut Encoder,
+        depth: usize,
+        extensions_ended: &mut bool,
+    ) -> Result<(), AbiError> {
+        if depth > MAX_DEPTH {
+            return Err(AbiError::new("Maximum nesting depth exceeded"));
+        }
+        if !s.base.is_empty() {
+            let base = self.get_struct(&s.base)?;
+            self.encode_fields(base, object, enc, depth + 1, extensions_ended)?;
+        }
+        for field in &s.fields {
+            let field_value = object.get(&field.name);
+            if field.r#type.ends_with('$') {
+                if field_value.is_none() {
+                    *extensions_ended = true;
+                    continue;
+                }
+                if *extensions_ended {
+                    return Err(AbiError::new(
+                        "Binary extension field present after a missing one",
+                    )
+                    .in_field(&field.name));
+                }
+            }
+            let field_value = match field_value {
+                Some(v) => v,
+                None if self.resolve(&field.r#type)?.ends_with('?') => &Value::Null,
+                None => {
+                    return Err(AbiError::new(format!(
+                        "Missing field {} of struct {}",
+                        field.name, s.name
+                    ))
+                    .in_field(&field.name))
+                }
+            };
+            self.encode(&field.r#type, field_value, enc, depth + 1)
+                .map_err(|e| e.in_field(&field.name))?;
+        }
+        Ok(())
+    }
+
+    fn encode_variant(
+        &self,
+        v: &AbiVariant,
+        value: &Value,
+        enc: &mut Encoder,
+        depth: usize,
+    ) -> Result<(), AbiError> {
+        // nodeos uses ["type", value], the object form is accepted as well
+        let (variant_type, variant_value) = match value {
+            Value::Array(items) if items.len() == 2 && items[0].is_string() => {
+                (items[0].as_str().unwrap(), &items[1])
+            }
+            Value::Object(object) => match (object.get("type"), object.get("value")) {
+                (Some(Value::String(t)), Some(v)) => (t.as_str(), v),
+                _ => return Err(expected("variant", &v.name, value)),
+            },
+            _ => return Err(expected("variant", &v.name, value)),
+        };
+        let index = v
+            .types
+            .iter()
+            .position(|t| t == variant_type)
+            .ok_or_else(|| {
+                AbiError::new(format!(
+                    "Type {variant_type} is not part of variant {}",
+                    v.name
+                ))
+            })?;
+        VarUint32::new(index as u32).pack(enc);
+        self.encode(variant_type, variant_value, enc, depth + 1)
+            .map_err(|e| e.at_index(1))
+    }
+
+    fn decode(&self, type_name: &str, dec: &mut Decoder, depth: usize) -> Result<Value, AbiError> {
+        if depth > MAX_DEPTH {
+            return Err(AbiError::new("Maximum nesting depth exceeded"));
+        }
+        let type_name = self.resolve(type_name)?;
+
+        if let Some(inner) = type_name.strip_suffix('$') {
+            if dec.remaining() == 0 {
+                return Ok(Value::Null);
+            }
+            return self.decode(inner, dec, depth + 1);
+        }
+        if let Some(inner) = type_name.strip_suffix('?') {
+            return match unpack::<u8>(dec)? {
+                0 => Ok(Value::Null),
+                1 => self.decode(inner, dec, depth + 1),
+                tag => Err(AbiError::new(format!("Bad optional tag {tag}"))),
+            };
+        }
+        if let Some(inner) = type_name.strip_suffix("[]") {
+            let len = unpack::<VarUint32>(dec)?.value() as usize;
+            let mut items = Vec::with_capacity(len.min(dec.remaining()));
+            for i in 0..len {
+                items.push(
+                    self.decode(inner, dec, depth + 1)
+                        .map_err(|e| e.at_index(i))?,
+                );
+            }
+            return Ok(Value::Array(items));
+        }
+        if BUILTIN_TYPES.contains(&type_name) {
+            return decode_builtin(type_name, dec);
+        }
+        if let Some(s) = self.structs.get(type_name) {
+            let mut object = Map::new();
+            self.decode_fields(s, dec, &mut object, depth + 1)?;
+            return Ok(Value::Object(object));
+        }
+        if let Some(v) = self.variants.get(type_name) {
+            let index = unpack::<VarUint32>(dec)?.value() as usize;
+            let variant_type = v.types.get(index).ok_or_else(|| {
+                AbiError::new(format!("Bad variant index {index} for {}", v.name))
+            })?;
+            let value = self
+                .decode(variant_type, dec, depth + 1)
+                .map_err(|e| e.at_index(1))?;
+            return Ok(Value::Array(vec![
+                Value::String(variant_type.clone()),
+                value,
+            ]));
+        }
+        Err(AbiError::new(format!("Unknown type: {type_name}")))
+    }
+
+    /// Returns false once a binary extension field is missing from the data,
+    /// which ends the struct.
+    fn decode_fields(
+        &self,
+        s: &AbiStruct,
+        dec: &mut Decoder,
+        object: &mut Map<String, Value>,
+        depth: usize,
+    ) -> Result<bool, AbiError> {
+        if depth > MAX_DEPTH {
+            return Err(AbiError::new("Maximum nesting depth exceeded"));
+        }
+        if !s.base.is_empty() {
+            let base = self.get_struct(&s.base)?;
+            if !self.decode_fields(base, dec, object, depth + 1)? {
+                return Ok(false);
+            }
+        }
+        for field in &s.fields {
+            if field.r#type.ends_with('$') && dec.remaining() == 0 {
+                return Ok(false);
+            }
+            let value = self
+                .decode(&field.r#type, dec, depth + 1)
+                .map_err(|e| e.in_field(&field.name))?;
+            object.insert(field.name.clone(), value);
+        }
+        Ok(true)
+    }
+}
+
+fn expected(kind: &str, type_name: &str, value: &Value) -> AbiError {
+    AbiError::new(format!("Expected {kind} for type {type_name}, got {value}"))
+}
+
+fn unpack<T: Packer + Default>(dec: &mut Decoder) -> Result<T, AbiError> {
+    let mut value = T::default();
+    dec.unpack(&mut value)?;
+    Ok(value)
+}
+
+fn json_str<'a>(type_name: &str, value: &'a Value) -> Result<&'a str, AbiError> {
+    value
+        .as_str()
+        .ok_or_else(|| expected("string", type_name, value))
+}
+
+/// Accepts both JSON numbers and numeric strings, as nodeos does.
+fn json_number<T: FromStr>(type_name: &str, value: &Value) -> Result<T, AbiError> {
+    let parsed = match value {
+        Value::Number(n) => n.to_string().parse().ok(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    parsed.ok_or_else(|| expected("number", type_name, value))
+}
+
+fn json_float(type_name: &str, value: &Value) -> Result<f64, AbiError> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| expected("number", type_name, value))
+}
+
+fn json_hex(type_name: &str, value: &Value) -> Result<Vec<u8>, AbiError> {
+    let s = json_str(type_name, value)?;
+    hex::decode(s).map_err(|e| AbiError::new(format!("Invalid hex for {type_name}: {e}")))
+}
+
+fn parse_str<T>(type_name: &str, value: &Value) -> Result<T, AbiError>
+where
+    T: FromStr<Err = String>,
+{
+    json_str(type_name, value)?
+        .parse()
+        .map_err(|e: String| AbiError::new(e))
+}
+
+fn float_to_json(value: f64) -> Value {
+    match Number::from_f64(value) {
+        Some(n) => Value::Number(n),
+        None => Value::String(value.to_string()),
+    }
+}
+
+/// 64 bit integers outside of the 32 bit range are written as strings, like
+/// nodeos does, so they survive JSON parsers that use doubles.
+fn u64_to_json(value: u64) -> Value {
+    if value > u32::MAX as u64 {
+        Value::String(value.to_string())
+    } else {
+        Value::from(value)
+    }
+}
+
+fn i64_to_json(value: i64) -> Value {
+    if value > u32::MAX as i64 || value < -(u32::MAX as i64) {
+        Value::String(value.to_string())
+    } else {
+        Value::from(value)
+    }
+}
+
+fn encode_builtin(type_name: &str, value: &Value, enc: &mut Encoder) -> Result<(), AbiError> {
+    macro_rules! pack_number {
+        ($ty:ty) => {
+            json_number::<$ty>(type_name, value)?.pack(enc)
+        };
+    }
+
+    match type_name {
+        "bool" => value
+            .as_bool()
+            .ok_or_else(|| expected("bool", type_name, value))?
+            .pack(enc),
+        "int8" => pack_number!(i8),
+        "uint8" => pack_number!(u8),
+        "int16" => pack_number!(i16),
+        "uint16" => pack_number!(u16),
+        "int32" => pack_number!(i32),
+        "uint32" => pack_number!(u32),
+        "int64" => pack_number!(i64),
+        "uint64" => pack_number!(u64),
+        "int128" => pack_number!(i128),
+        "uint128" => pack_number!(u128),
+        "varint32" => VarInt32::new(json_number::<i32>(type_name, value)?).pack(enc),
+        "varuint32" => VarUint32::new(json_number::<u32>(type_name, value)?).pack(enc),
+        "float32" => (json_float(type_name, value)? as f32).pack(enc),
+        "float64" => json_float(type_name, value)?.pack(enc),
+        "float128" => {
+            let s = json_str(type_name, value)?;
+            let bytes = hex::decode(s.trim_start_matches("0x"))
+                .ok()
+                .and_then(|b| <[u8; 16]>::try_from(b).ok())
+                .ok_or_else(|| expected("16 byte hex string", type_name, value))?;
+            Float128::new(bytes).pack(enc)
+        }
+        "time_point" => parse_str::<TimePoint>(type_name, value)?.pack(enc),
+        "time_point_sec" => parse_str::<TimePointSec>(type_name, value)?.pack(enc),
+        "block_timestamp_type" => parse_str::<BlockTimestamp>(type_name, value)?.pack(enc),
+        "name" => parse_str::<Name>(type_name, value)?.pack(enc),
+        "bytes" => json_hex(type_name, value)?.pack(enc),
+        "string" => json_str(type_name, value)?.to_string().pack(enc),
+        "checksum160" => Checksum160::from_bytes(&json_hex(type_name, value)?)
+            .map_err(AbiError::new)?
+            .pack(enc),
+        "checksum256" => Checksum256::from_bytes(&json_hex(type_name, value)?)
+            .map_err(AbiError::new)?
+            .pack(enc),
+        "checksum512" => {
+            let bytes = json_hex(type_name, value)?;
+            if bytes.len() != 64 {
+                return Err(AbiError::new("Checksum512: bad byte array length"));
+            }
+            Checksum512::from_bytes(&bytes).pack(enc)
+        }
+        "public_key" => PublicKey::new_from_str(json_str(type_name, value)?)
+            .map_err(AbiError::new)?
+            .pack(enc),
+        "signature" => Signature::from_string(json_str(type_name, value)?)
+            .map_err(AbiError::new)?
+            .pack(enc),
+        "symbol" => parse_str::<Symbol>(type_name, value)?.pack(enc),
+        "symbol_code" => parse_str::<SymbolCode>(type_name, value)?.pack(enc),
+        "asset" => parse_str::<Asset>(type_name, value)?.pack(enc),
+        "extended_asset" => {
+            let object = value
+                .as_object()
+                .ok_or_else(|| expected("object", type_name, value))?;
+            let field = |name: &str| {
+                object.get(name).ok_or_else(|| {
+                    AbiError::new(format!("Missing field {name} of struct {type_name}"))
+                        .in_field(name)
+                })
+            };
+            let quantity = field("quantity")?;
+            let contract = field("contract")?;
+            parse_str::<Asset>("asset", quantity)
+                .map_err(|e| e.in_field("quantity"))?
+                .pack(enc);
+            parse_str::<Name>("name", contract)
+                .map_err(|e| e.in_field("contract"))?
+                .pack(enc)
+        }
+        _ => return Err(AbiError::new(format!("Unknown type: {type_name}"))),
+    };
+    Ok(())
+}
+
+fn decode_builtin(type_name: &str, dec: &mut Decoder) -> Result<Value, AbiError> {
+    let value = match type_name {
+        "bool" => Value::Bool(unpack(dec)?),
+        "int8" => Value::from(unpack::<i8>(dec)?),
+        "uint8" => Value::from(unpack::<u8>(dec)?),
+        "int16" => Value::from(unpack::<i16>(dec)?),
+        "uint16" => Value::from(unpack::<u16>(dec)?),
+        "int32" => Value::from(unpack::<i32>(dec)?),
+        "uint32" => Value::from(unpack::<u32>(dec)?),
+        "int64" => i64_to_json(unpack(dec)?),
+        "uint64" => u64_to_json(unpack(dec)?),
+        "int128" => Value::String(unpack::<i128>(dec)?.to_string()),
+        "uint128" => Value::String(unpack::<u128>(dec)?.to_string()),
+        "varint32" => Value::from(unpack::<VarInt32>(dec)?.value()),
+        "varuint32" => Value::from(unpack::<VarUint32>(dec)?.value()),
+        // go through the shortest decimal form so 0.1f32 stays 0.1
+        "float32" => float_to_json(unpack::<f32>(dec)?.to_string().parse().unwrap()),
+        "float64" => float_to_json(unpack(dec)?),
+        "float128" => Value::String(format!("0x{}", hex::encode(unpack::<Float128>(dec)?.data))),
+        "time_point" => Value::String(unpack::<TimePoint>(dec)?.as_string()),
+        "time_point_sec" => Value::String(unpack::<TimePointSec>(dec)?.as_string()),
+        "block_timestamp_type" => Value::String(unpack::<BlockTimestamp>(dec)?.as_string()),
+        "name" => Value::String(unpack::<Name>(dec)?.as_string()),
+        "bytes" => Value::String(hex::encode(unpack::<Vec<u8>>(dec)?)),
+        "string" => Value::String(unpack(dec)?),
+        "checksum160" => Value::String(unpack::<Checksum160>(dec)?.as_string()),
+        "checksum256" => Value::String(unpack::<Checksum256>(dec)?.as_string()),
+        "checksum512" => Value::String(unpack::<Checksum512>(dec)?.as_string()),
+        "public_key" => Value::String(unpack::<PublicKey>(dec)?.as_string()),
+        "signature" => Value::String(unpack::<Signature>(dec)?.as_string()),
+        "symbol" => Value::String(unpack::<Symbol>(dec)?.as_string()),
+        "symbol_code" => Value::String(unpack::<SymbolCode>(dec)?.as_string()),
+        "asset" => Value::String(unpack::<Asset>(dec)?.as_string()),
+        "extended_asset" => {
+            let quantity = unpack::<Asset>(dec).map_err(|e| e.in_field("quantity"))?;
+            let contract = unpack::<Name>(dec).map_err(|e| e.in_field("contract"))?;
+            let mut object = Map::new();
+            object.insert("quantity".into(), Value::String(quantity.as_string()));
+            object.insert("contract".into(), Value::String(contract.as_string()));
+            Value::Object(object)
+        }
+        _ => return Err(AbiError::new(format!("Unknown type: {type_name}"))),
+    };
+    Ok(value)
+}
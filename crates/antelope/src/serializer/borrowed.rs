@@ -0,0 +1,169 @@
+use crate::{
+    chain::{
+        action::{Action, PermissionLevel},
+        asset::{Asset, ExtendedAsset, Symbol, SymbolCode},
+        binary_extension::BinaryExtension,
+        checksum::{Checksum160, Checksum256, Checksum512},
+        name::Name,
+        public_key::PublicKey,
+        signature::Signature,
+        time::{BlockTimestamp, TimePoint, TimePointSec},
+        varint::{VarInt32, VarUint32},
+    },
+    serializer::{
+        error::{ensure_size, DecodeError},
+        packer::Float128,
+        Decoder, Packer,
+    },
+};
+
+/// Unpacks a value that may borrow from the input buffer, the zero-copy
+/// counterpart of `Packer::unpack`.
+///
+/// `&'a str` and `&'a [u8]` point into the input instead of being copied, and
+/// `Vec<T>` builds its elements directly instead of starting from `Default`.
+/// Structs, including ones with a lifetime, get an impl with
+/// `#[derive(UnpackBorrowed)]`.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::chain::name::Name;
+/// use antelope::name;
+/// use antelope::serializer::{Decoder, Encoder, UnpackBorrowed};
+///
+/// #[derive(UnpackBorrowed)]
+/// struct Row<'a> {
+///     owner: Name,
+///     memo: &'a str,
+///     data: &'a [u8],
+/// }
+///
+/// let data = [Encoder::pack(&name!("bob")), vec![2, b'h', b'i', 1, 7]].concat();
+/// let mut decoder = Decoder::new(&data);
+/// let row: Row = decoder.unpack_borrowed().unwrap();
+/// assert_eq!(row.owner.to_string(), "bob");
+/// assert_eq!(row.memo, "hi");
+/// assert_eq!(row.data, [7]);
+/// ```
+pub trait UnpackBorrowed<'a>: Sized {
+    /// Unpacks a value from the start of `data`.
+    ///
+    /// # Returns
+    ///
+    /// The value and the number of bytes read, or a `DecodeError` if the
+    /// data is truncated or malformed.
+    fn unpack_borrowed(data: &'a [u8]) -> Result<(Self, usize), DecodeError>;
+}
+
+/// Implements `UnpackBorrowed` for owned types through their `Packer` impl.
+macro_rules! impl_unpack_borrowed {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl<'a> UnpackBorrowed<'a> for $ty {
+                fn unpack_borrowed(data: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+                    let mut value = <$ty>::default();
+                    let size = value.unpack(data)?;
+                    Ok((value, size))
+                }
+            }
+        )*
+    };
+}
+
+impl_unpack_borrowed!(bool, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64);
+impl_unpack_borrowed!(Float128, String, VarInt32, VarUint32, Name, Symbol, SymbolCode, Asset);
+impl_unpack_borrowed!(ExtendedAsset, Checksum160, Checksum256, Checksum512);
+impl_unpack_borrowed!(
+    PublicKey,
+    Signature,
+    TimePoint,
+    TimePointSec,
+    BlockTimestamp
+);
+impl_unpack_borrowed!(PermissionLevel, Action);
+
+impl<'a> UnpackBorrowed<'a> for &'a [u8] {
+    fn unpack_borrowed(data: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+        let mut length = VarUint32::default();
+        let size = length.unpack(data)?;
+        let end = size + length.value() as usize;
+        ensure_size(data, end, "bytes").map_err(|e| e.at(size))?;
+        Ok((&data[size..end], end))
+    }
+}
+
+impl<'a> UnpackBorrowed<'a> for &'a str {
+    fn unpack_borrowed(data: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+        let (bytes, size) = <&[u8]>::unpack_borrowed(data)?;
+        match std::str::from_utf8(bytes) {
+            Ok(s) => Ok((s, size)),
+            Err(e) => Err(DecodeError::invalid("String", e.to_string())),
+        }
+    }
+}
+
+impl<'a, T> UnpackBorrowed<'a> for Vec<T>
+where
+    T: UnpackBorrowed<'a>,
+{
+    fn unpack_borrowed(data: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+        let mut dec = Decoder::new(data);
+        let mut length = VarUint32::default();
+        dec.unpack(&mut length)?;
+        // Cap the reservation by what is left, see `Packer for Vec<T>`.
+        let mut values = Vec::with_capacity((length.value() as usize).min(dec.remaining()));
+        for i in 0..length.value() {
+            let value = dec
+                .unpack_borrowed()
+                .map_err(|e| e.in_field(&i.to_string()))?;
+            values.push(value);
+        }
+        Ok((values, dec.get_pos()))
+    }
+}
+
+impl<'a, T> UnpackBorrowed<'a> for Option<T>
+where
+    T: UnpackBorrowed<'a>,
+{
+    fn unpack_borrowed(data: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+        if data.is_empty() {
+            return Ok((None, 0));
+        }
+        let mut dec = Decoder::new(data);
+        let mut tag = 0u8;
+        dec.unpack(&mut tag)?;
+        match tag {
+            0 => Ok((None, 1)),
+            1 => {
+                let value = dec.unpack_borrowed()?;
+                Ok((Some(value), dec.get_pos()))
+            }
+            _ => Err(DecodeError::bad_variant("Option", tag as u64)),
+        }
+    }
+}
+
+impl<'a, T> UnpackBorrowed<'a> for Box<T>
+where
+    T: UnpackBorrowed<'a>,
+{
+    fn unpack_borrowed(data: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+        let (value, size) = T::unpack_borrowed(data)?;
+        Ok((Box::new(value), size))
+    }
+}
+
+impl<'a, T> UnpackBorrowed<'a> for BinaryExtension<T>
+where
+    T: Packer + Default + UnpackBorrowed<'a>,
+{
+    fn unpack_borrowed(data: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+        if data.is_empty() {
+            return Ok((BinaryExtension::new(None), 0));
+        }
+        let (value, size) = T::unpack_borrowed(data)?;
+        Ok((BinaryExtension::new(Some(value)), size))
+    }
+}
@@ -0,0 +1,419 @@
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::Deserialize;
+
+use crate::{
+    chain::varint::VarUint32,
+    serializer::{
+        error::ensure_size,
+        ser::{BINARY_EXTENSION_TOKEN, VARUINT32_TOKEN},
+        DecodeError, Packer,
+    },
+};
+
+/// Unpacks a `T` from the Antelope binary format through its `Deserialize`
+/// impl, the inverse of `serializer::to_bytes`.
+///
+/// Fails if `data` holds more bytes than the value uses.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::serializer::from_bytes;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Transfer<'a> {
+///     amount: u32,
+///     memo: &'a str,
+/// }
+///
+/// let data = [1, 0, 0, 0, 2, b'h', b'i'];
+/// let transfer: Transfer = from_bytes(&data).unwrap();
+/// assert_eq!(transfer, Transfer { amount: 1, memo: "hi" });
+/// ```
+pub fn from_bytes<'de, T: Deserialize<'de>>(data: &'de [u8]) -> Result<T, DecodeError> {
+    let mut deserializer = BinaryDeserializer::new(data);
+    let value = T::deserialize(&mut deserializer)?;
+    if deserializer.remaining() > 0 {
+        return Err(DecodeError::invalid(
+            std::any::type_name::<T>(),
+            format!("{} trailing bytes", deserializer.remaining()),
+        )
+        .at(deserializer.pos));
+    }
+    Ok(value)
+}
+
+/// A `serde::Deserializer` reading the Antelope binary format, see
+/// `from_bytes`.
+///
+/// The format is not self-describing, so `deserialize_any` is not supported.
+pub struct BinaryDeserializer<'de> {
+    input: &'de [u8],
+    pos: usize,
+    /// Set while deserializing the content of a `VARUINT32_TOKEN` newtype.
+    varuint32: bool,
+}
+
+impl<'de> BinaryDeserializer<'de> {
+    pub fn new(input: &'de [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            varuint32: false,
+        }
+    }
+
+    /// Returns the number of bytes consumed so far.
+    pub fn get_pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of bytes left to deserialize.
+    pub fn remaining(&self) -> usize {
+        self.input.len() - self.pos
+    }
+
+    fn read<T: Packer + Default>(&mut self) -> Result<T, DecodeError> {
+        let mut value = T::default();
+        let size = value
+            .unpack(&self.input[self.pos..])
+            .map_err(|e| e.at(self.pos))?;
+        self.pos += size;
+        Ok(value)
+    }
+
+    fn read_length(&mut self) -> Result<usize, DecodeError> {
+        Ok(self.read::<VarUint32>()?.value() as usize)
+    }
+
+    fn read_bytes(&mut self) -> Result<&'de [u8], DecodeError> {
+        let len = self.read_length()?;
+        ensure_size(&self.input[self.pos..], len, "bytes").map_err(|e| e.at(self.pos))?;
+        let bytes = &self.input[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn read_str(&mut self) -> Result<&'de str, DecodeError> {
+        let start = self.pos;
+        let bytes = self.read_bytes()?;
+        std::str::from_utf8(bytes)
+            .map_err(|e| DecodeError::invalid("String", e.to_string()).at(start))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut BinaryDeserializer<'de> {
+    type Error = DecodeError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, DecodeError> {
+        Err(DecodeError::invalid("value", "the binary format is not self-describing").at(self.pos))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_bool(self.read()?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_i8(self.read()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_i16(self.read()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_i32(self.read()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_i64(self.read()?)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_i128(self.read()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_u8(self.read()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_u16(self.read()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        if std::mem::take(&mut self.varuint32) {
+            return visitor.visit_u32(self.read::<VarUint32>()?.value());
+        }
+        visitor.visit_u32(self.read()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_u64(self.read()?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_u128(self.read()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_f32(self.read()?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_f64(self.read()?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        let start = self.pos;
+        let s = self.read_str()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(DecodeError::invalid("char", "expected a single character").at(start)),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_borrowed_str(self.read_str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_borrowed_bytes(self.read_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        let start = self.pos;
+        match self.read::<u8>()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            tag => Err(DecodeError::bad_variant("Option", tag as u64).at(start)),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        if name == BINARY_EXTENSION_TOKEN {
+            // binary extensions are only left out at the end of the data
+            if self.remaining() == 0 {
+                return visitor.visit_none();
+            }
+            return visitor.visit_some(self);
+        }
+        self.varuint32 = name == VARUINT32_TOKEN;
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        let len = self.read_length()?;
+        visitor.visit_seq(Access {
+            de: self,
+            len,
+            fields: &[],
+            index: 0,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        visitor.visit_seq(Access {
+            de: self,
+            len,
+            fields: &[],
+            index: 0,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        let len = self.read_length()?;
+        visitor.visit_map(Access {
+            de: self,
+            len,
+            fields: &[],
+            index: 0,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        visitor.visit_seq(Access {
+            de: self,
+            len: fields.len(),
+            fields,
+            index: 0,
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        self.deserialize_any(visitor)
+    }
+}
+
+/// Reads a known number of sequence elements, map entries or struct fields.
+struct Access<'a, 'de> {
+    de: &'a mut BinaryDeserializer<'de>,
+    len: usize,
+    /// Field names when reading a struct, used for error paths.
+    fields: &'static [&'static str],
+    index: usize,
+}
+
+impl<'a, 'de> Access<'a, 'de> {
+    fn next<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, DecodeError> {
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(&mut *self.de)
+            .map_err(|e| match self.fields.get(index) {
+                Some(field) => e.in_field(field),
+                None => e,
+            })
+    }
+}
+
+impl<'a, 'de> de::SeqAccess<'de> for Access<'a, 'de> {
+    type Error = DecodeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DecodeError> {
+        if self.index == self.len {
+            return Ok(None);
+        }
+        self.next(seed).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
+
+impl<'a, 'de> de::MapAccess<'de> for Access<'a, 'de> {
+    type Error = DecodeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DecodeError> {
+        if self.index == self.len {
+            return Ok(None);
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, DecodeError> {
+        self.next(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut BinaryDeserializer<'de> {
+    type Error = DecodeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), DecodeError> {
+        let start = self.pos;
+        let index = self.read::<VarUint32>()?.value();
+        let value = seed
+            .deserialize(index.into_deserializer())
+            .map_err(|_: DecodeError| DecodeError::bad_variant("enum", index as u64).at(start))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut BinaryDeserializer<'de> {
+    type Error = DecodeError;
+
+    fn unit_variant(self) -> Result<(), DecodeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, DecodeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt::{Display, Formatter};
+
+/// The reason a value could not be decoded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DecodeErrorKind {
+    /// The input ended before the value was complete.
+    UnexpectedEof { needed: usize, remaining: usize },
+    /// A variant or option tag did not match any known variant.
+    BadVariant(u64),
+    /// The bytes were read but do not form a valid value.
+    Invalid(String),
+}
+
+impl Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeErrorKind::UnexpectedEof { needed, remaining } => {
+                write!(
+                    f,
+                    "unexpected end of input, needed {needed} bytes but {remaining} remain"
+                )
+            }
+            DecodeErrorKind::BadVariant(index) => write!(f, "bad variant index {index}"),
+            DecodeErrorKind::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// Error returned when packed data cannot be unpacked.
+///
+/// `offset` is relative to the buffer handed to the outermost `Decoder`, and
+/// `path` lists the enclosing fields from the outside in.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::serializer::{DecodeErrorKind, Decoder};
+///
+/// let data = [1u8, 2];
+/// let mut decoder = Decoder::new(&data);
+/// let mut value = 0u32;
+/// let err = decoder.unpack(&mut value).unwrap_err();
+/// assert_eq!(err.offset, 0);
+/// assert_eq!(err.type_name, "u32");
+/// assert_eq!(
+///     err.kind,
+///     DecodeErrorKind::UnexpectedEof {
+///         needed: 4,
+///         remaining: 2
+///     }
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodeError {
+    pub kind: DecodeErrorKind,
+    /// The type that failed to decode.
+    pub type_name: &'static str,
+    /// Position in the input where the failing value starts.
+    pub offset: usize,
+    /// Field names leading to the failing value.
+    pub path: Vec<String>,
+}
+
+impl DecodeError {
+    pub fn new(type_name: &'static str, kind: DecodeErrorKind) -> Self {
+        Self {
+            kind,
+            type_name,
+            offset: 0,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn eof(type_name: &'static str, needed: usize, remaining: usize) -> Self {
+        Self::new(
+            type_name,
+            DecodeErrorKind::UnexpectedEof { needed, remaining },
+        )
+    }
+
+    pub fn bad_variant(type_name: &'static str, index: u64) -> Self {
+        Self::new(type_name, DecodeErrorKind::BadVariant(index))
+    }
+
+    pub fn invalid(type_name: &'static str, message: impl Into<String>) -> Self {
+        Self::new(type_name, DecodeErrorKind::Invalid(message.into()))
+    }
+
+    /// Shifts the error offset by `pos`, used when a nested decoder reports
+    /// an error relative to its own slice.
+    pub fn at(mut self, pos: usize) -> Self {
+        self.offset += pos;
+        self
+    }
+
+    /// Records that the error happened inside the field `name`.
+    pub fn in_field(mut self, name: &str) -> Self {
+        self.path.insert(0, name.to_string());
+        self
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to decode {}", self.type_name)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path.join("."))?;
+        }
+        write!(f, " (offset {}): {}", self.offset, self.kind)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl serde::de::Error for DecodeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        DecodeError::invalid("value", msg.to_string())
+    }
+}
+
+/// Error returned when a value cannot be packed through `serializer::to_bytes`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EncodeError {
+    pub message: String,
+}
+
+impl EncodeError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to encode: {}", self.message)
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+impl serde::ser::Error for EncodeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        EncodeError::new(msg.to_string())
+    }
+}
+
+/// Error returned by `StreamDecoder` when reading from the underlying reader
+/// fails or the data read cannot be unpacked.
+#[derive(Debug)]
+pub enum StreamError {
+    Io(std::io::Error),
+    Decode(DecodeError),
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "failed to read: {e}"),
+            StreamError::Decode(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(e) => Some(e),
+            StreamError::Decode(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for StreamError {
+    fn from(e: std::io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+impl From<DecodeError> for StreamError {
+    fn from(e: DecodeError) -> Self {
+        StreamError::Decode(e)
+    }
+}
+
+/// Returns an error unless `data` holds at least `size` bytes.
+pub fn ensure_size(data: &[u8], size: usize, type_name: &'static str) -> Result<(), DecodeError> {
+    if data.len() < size {
+        return Err(DecodeError::eof(type_name, size, data.len()));
+    }
+    Ok(())
+}
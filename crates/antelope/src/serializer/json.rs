@@ -0,0 +1,193 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserializer, Serializer,
+};
+
+use crate::serializer::{Encoder, Packer};
+
+/// Serializes `value` through its `Display` impl, the JSON form nodeos uses
+/// for names, symbols, assets, keys, checksums and times.
+pub(crate) fn serialize_display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: fmt::Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Deserializes a value from its string form with `parse`.
+pub(crate) fn deserialize_parsed<'de, T, D>(
+    deserializer: D,
+    expecting: &'static str,
+    parse: fn(&str) -> Result<T, String>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ParseVisitor<T> {
+        expecting: &'static str,
+        parse: fn(&str) -> Result<T, String>,
+    }
+
+    impl<T> Visitor<'_> for ParseVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str(self.expecting)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+            (self.parse)(v).map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_str(ParseVisitor { expecting, parse })
+}
+
+/// Serializes the packed bytes of a fixed size value as a tuple, the binary
+/// form of the types using `impl_string_serde!`.
+pub(crate) fn serialize_packed<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Packer,
+    S: Serializer,
+{
+    let bytes = Encoder::pack(value);
+    let mut tuple = serializer.serialize_tuple(bytes.len())?;
+    for b in &bytes {
+        tuple.serialize_element(b)?;
+    }
+    tuple.end()
+}
+
+/// Deserializes a fixed size value from `size` packed bytes.
+pub(crate) fn deserialize_packed<'de, T, D>(deserializer: D, size: usize) -> Result<T, D::Error>
+where
+    T: Packer + Default,
+    D: Deserializer<'de>,
+{
+    struct PackedVisitor<T> {
+        size: usize,
+        marker: PhantomData<T>,
+    }
+
+    impl<'de, T: Packer + Default> Visitor<'de> for PackedVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{} packed bytes", self.size)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+            let mut bytes = Vec::with_capacity(self.size);
+            for i in 0..self.size {
+                let b: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                bytes.push(b);
+            }
+            let mut value = T::default();
+            value.unpack(&bytes).map_err(de::Error::custom)?;
+            Ok(value)
+        }
+    }
+
+    let visitor = PackedVisitor {
+        size,
+        marker: PhantomData,
+    };
+    deserializer.deserialize_tuple(size, visitor)
+}
+
+/// Implements `Serialize` and `Deserialize` for a fixed size chain type whose
+/// JSON form is its string representation. The binary form is the packed
+/// value, so `to_bytes` stays compatible with `Packer`.
+macro_rules! impl_string_serde {
+    ($ty:ty, $size:expr, $parse:expr, $expecting:expr) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    $crate::serializer::json::serialize_display(self, serializer)
+                } else {
+                    $crate::serializer::json::serialize_packed(self, serializer)
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    $crate::serializer::json::deserialize_parsed(deserializer, $expecting, $parse)
+                } else {
+                    $crate::serializer::json::deserialize_packed(deserializer, $size)
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use impl_string_serde;
+
+/// Serializes bytes as a hex string in JSON, for use with `#[serde(with)]`.
+/// The binary form is the length prefixed bytes, as `Vec<u8>` packs.
+pub(crate) mod hex_bytes {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(bytes))
+        } else {
+            bytes.serialize(serializer)
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+            hex::decode(s.as_ref()).map_err(de::Error::custom)
+        } else {
+            Vec::<u8>::deserialize(deserializer)
+        }
+    }
+}
+
+/// Serializes a list of byte strings as hex strings in JSON, for use with
+/// `#[serde(with)]`.
+pub(crate) mod hex_bytes_vec {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    struct Hex<'a>(&'a [u8]);
+
+    impl Serialize for Hex<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::hex_bytes::serialize(self.0, serializer)
+        }
+    }
+
+    struct HexBuf(Vec<u8>);
+
+    impl<'de> Deserialize<'de> for HexBuf {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            super::hex_bytes::deserialize(deserializer).map(HexBuf)
+        }
+    }
+
+    pub(crate) fn serialize<S: Serializer>(
+        values: &[Vec<u8>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|v| Hex(v)))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        let values = Vec::<HexBuf>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|v| v.0).collect())
+    }
+}
@@ -0,0 +1,385 @@
+use std::cmp::Ordering;
+
+use serde::{ser, Serialize};
+
+use crate::serializer::error::EncodeError;
+
+/// A map key captured through its `Serialize` impl, ordered like the derived
+/// `Ord` of the key: numbers by value, strings and sequences
+/// lexicographically, structs field by field and enum variants by index.
+///
+/// `to_bytes` sorts map entries by it, so that a `HashMap` is written in the
+/// key order its `Packer` impl uses.
+pub(crate) enum KeyOrder {
+    Unit,
+    Bool(bool),
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+    Char(char),
+    Str(String),
+    Option(Option<Box<KeyOrder>>),
+    Seq(Vec<KeyOrder>),
+    Variant(u32, Box<KeyOrder>),
+}
+
+impl KeyOrder {
+    pub(crate) fn of<T: Serialize + ?Sized>(key: &T) -> Result<Self, EncodeError> {
+        key.serialize(KeyCapture)
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            KeyOrder::Unit => 0,
+            KeyOrder::Bool(_) => 1,
+            KeyOrder::Signed(_) => 2,
+            KeyOrder::Unsigned(_) => 3,
+            KeyOrder::Float(_) => 4,
+            KeyOrder::Char(_) => 5,
+            KeyOrder::Str(_) => 6,
+            KeyOrder::Option(_) => 7,
+            KeyOrder::Seq(_) => 8,
+            KeyOrder::Variant(..) => 9,
+        }
+    }
+}
+
+impl Ord for KeyOrder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (KeyOrder::Bool(a), KeyOrder::Bool(b)) => a.cmp(b),
+            (KeyOrder::Signed(a), KeyOrder::Signed(b)) => a.cmp(b),
+            (KeyOrder::Unsigned(a), KeyOrder::Unsigned(b)) => a.cmp(b),
+            (KeyOrder::Float(a), KeyOrder::Float(b)) => a.total_cmp(b),
+            (KeyOrder::Char(a), KeyOrder::Char(b)) => a.cmp(b),
+            (KeyOrder::Str(a), KeyOrder::Str(b)) => a.cmp(b),
+            (KeyOrder::Option(a), KeyOrder::Option(b)) => a.cmp(b),
+            (KeyOrder::Seq(a), KeyOrder::Seq(b)) => a.cmp(b),
+            (KeyOrder::Variant(a, x), KeyOrder::Variant(b, y)) => a.cmp(b).then_with(|| x.cmp(y)),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for KeyOrder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for KeyOrder {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for KeyOrder {}
+
+/// Captures a value as a `KeyOrder`.
+struct KeyCapture;
+
+/// Collects the elements of a compound value, and the index of its enum
+/// variant if it is one.
+struct KeySeq {
+    variant: Option<u32>,
+    items: Vec<KeyOrder>,
+}
+
+impl KeySeq {
+    fn new(variant: Option<u32>) -> Self {
+        Self {
+            variant,
+            items: Vec::new(),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.items.push(KeyOrder::of(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<KeyOrder, EncodeError> {
+        let seq = KeyOrder::Seq(self.items);
+        Ok(match self.variant {
+            Some(index) => KeyOrder::Variant(index, Box::new(seq)),
+            None => seq,
+        })
+    }
+}
+
+impl ser::Serializer for KeyCapture {
+    type Ok = KeyOrder;
+    type Error = EncodeError;
+
+    type SerializeSeq = KeySeq;
+    type SerializeTuple = KeySeq;
+    type SerializeTupleStruct = KeySeq;
+    type SerializeTupleVariant = KeySeq;
+    type SerializeMap = KeySeq;
+    type SerializeStruct = KeySeq;
+    type SerializeStructVariant = KeySeq;
+
+    // Keys are captured in their binary form, as `to_bytes` writes them
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Signed(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Signed(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Signed(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Signed(v.into()))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Signed(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Unsigned(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Unsigned(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Unsigned(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Unsigned(v.into()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Unsigned(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Float(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Str(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Seq(
+            v.iter().map(|b| KeyOrder::Unsigned((*b).into())).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Option(None))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Option(Some(Box::new(KeyOrder::of(value)?))))
+    }
+
+    fn serialize_unit(self) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Unit)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Unit)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Variant(variant_index, Box::new(KeyOrder::Unit)))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<KeyOrder, EncodeError> {
+        KeyOrder::of(value)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<KeyOrder, EncodeError> {
+        Ok(KeyOrder::Variant(
+            variant_index,
+            Box::new(KeyOrder::of(value)?),
+        ))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<KeySeq, EncodeError> {
+        Ok(KeySeq::new(None))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<KeySeq, EncodeError> {
+        Ok(KeySeq::new(None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<KeySeq, EncodeError> {
+        Ok(KeySeq::new(None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<KeySeq, EncodeError> {
+        Ok(KeySeq::new(Some(variant_index)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<KeySeq, EncodeError> {
+        Ok(KeySeq::new(None))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<KeySeq, EncodeError> {
+        Ok(KeySeq::new(None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<KeySeq, EncodeError> {
+        Ok(KeySeq::new(Some(variant_index)))
+    }
+}
+
+impl ser::SerializeSeq for KeySeq {
+    type Ok = KeyOrder;
+    type Error = EncodeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<KeyOrder, EncodeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for KeySeq {
+    type Ok = KeyOrder;
+    type Error = EncodeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<KeyOrder, EncodeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for KeySeq {
+    type Ok = KeyOrder;
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<KeyOrder, EncodeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for KeySeq {
+    type Ok = KeyOrder;
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<KeyOrder, EncodeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for KeySeq {
+    type Ok = KeyOrder;
+    type Error = EncodeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), EncodeError> {
+        self.push(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<KeyOrder, EncodeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for KeySeq {
+    type Ok = KeyOrder;
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<KeyOrder, EncodeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for KeySeq {
+    type Ok = KeyOrder;
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<KeyOrder, EncodeError> {
+        self.finish()
+    }
+}
@@ -1,4 +1,19 @@
+pub mod abi_serializer;
+pub mod borrowed;
+pub mod de;
+pub mod error;
 pub mod formatter;
+pub(crate) mod json;
+mod key_order;
 pub mod packer;
+pub mod ser;
+pub mod stream;
 
-pub use packer::{Decoder, Encoder, Packer};
+pub use abi_serializer::{AbiError, AbiSerializer};
+pub use antelope_client_macros::UnpackBorrowed;
+pub use borrowed::UnpackBorrowed;
+pub use de::{from_bytes, BinaryDeserializer};
+pub use error::{DecodeError, DecodeErrorKind, EncodeError, StreamError};
+pub use packer::{decode_all, decode_exact, Decoder, Encoder, Packer};
+pub use ser::{to_bytes, BinarySerializer};
+pub use stream::{StreamDecoder, StreamEncoder};
@@ -1,7 +1,18 @@
 use core::mem::size_of;
 use serde::{Deserialize, Serialize};
-
-use crate::{chain::varint::VarUint32, util::slice_copy};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{
+    chain::varint::VarUint32,
+    serializer::{
+        error::{ensure_size, DecodeError},
+        UnpackBorrowed,
+    },
+    util::slice_copy,
+};
 
 ///
 /// The `Packer` trait provides methods for packing and unpacking values to and
@@ -18,13 +29,19 @@ use crate::{chain::varint::VarUint32, util::slice_copy};
 ///
 /// let mut decoder = Decoder::new(&encoder.get_bytes());
 /// let mut unpacked_value = 0u32;
-/// decoder.unpack(&mut unpacked_value);
+/// decoder.unpack(&mut unpacked_value).unwrap();
 ///
 /// assert_eq!(value, unpacked_value);
 /// ```
 pub trait Packer {
     /// Returns the size of the packed representation of this value in bytes.
-    fn size(&self) -> usize;
+    ///
+    /// Packing never calls this, so implementing it is optional. The default
+    /// packs the value into a scratch buffer and measures the result.
+    fn size(&self) -> usize {
+        let mut enc = Encoder::new(0);
+        self.pack(&mut enc)
+    }
 
     /// Packs this value into the given `Encoder`.
     ///
@@ -45,8 +62,9 @@ pub trait Packer {
     ///
     /// # Returns
     ///
-    /// The number of bytes read from the byte array.
-    fn unpack(&mut self, data: &[u8]) -> usize;
+    /// The number of bytes read from the byte array, or a `DecodeError` if
+    /// the data is truncated or malformed.
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError>;
 }
 
 /// The `Encoder` struct provides methods for packing values that implement the
@@ -70,6 +88,12 @@ pub struct Encoder {
     buf: Vec<u8>,
 }
 
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
 impl Encoder {
     /// Constructs a new `Encoder` with the given initial capacity.
     ///
@@ -95,6 +119,11 @@ impl Encoder {
         &self.buf
     }
 
+    /// Consumes the encoder, returning the packed bytes without copying them.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+
     /// Returns the number of packed bytes in this encoder.
     ///
     /// # Returns
@@ -104,6 +133,25 @@ impl Encoder {
         self.buf.len()
     }
 
+    /// Discards the packed bytes, keeping the allocated capacity so the
+    /// encoder can be reused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use antelope::serializer::{Encoder, Packer};
+    ///
+    /// let mut encoder = Encoder::default();
+    /// for value in [1u32, 2, 3] {
+    ///     encoder.clear();
+    ///     value.pack(&mut encoder);
+    ///     assert_eq!(encoder.get_bytes(), [value as u8, 0, 0, 0]);
+    /// }
+    /// ```
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+
     /// Allocates space in this encoder for packing a value of the given size.
     ///
     /// # Arguments
@@ -134,12 +182,10 @@ impl Encoder {
     /// assert_eq!(data, vec![210, 4, 0, 0]);
     /// ```
     pub fn pack<T: Packer>(value: &T) -> Vec<u8> {
-        // Create a new Encoder with the size of the value being packed
-        let mut enc = Self::new(value.size());
-        // Pack the value using the encoder
+        // The buffer grows as needed, so no size() pre-pass is required
+        let mut enc = Self::new(0);
         value.pack(&mut enc);
-        // Return the packed data as a vector of bytes
-        enc.get_bytes().to_vec()
+        enc.into_bytes()
     }
 }
 
@@ -153,35 +199,173 @@ impl Encoder {
 /// let data = &vec![210, 4, 0, 0];
 /// let mut decoder = Decoder::new(&data);
 /// let mut value = 0u32;
-/// decoder.unpack(&mut value);
+/// decoder.unpack(&mut value).unwrap();
 /// assert_eq!(value, 1234);
 /// ```
 pub struct Decoder<'a> {
     buf: &'a [u8],
     pos: usize,
+    strict: bool,
 }
 
 /// A struct for unpacking packed data
 impl<'a> Decoder<'a> {
     /// Creates a new `Decoder` instance from the given byte array.
     pub fn new(data: &'a [u8]) -> Self {
-        Self { buf: data, pos: 0 }
+        Self {
+            buf: data,
+            pos: 0,
+            strict: false,
+        }
+    }
+
+    /// Creates a `Decoder` that only accepts canonical encodings.
+    ///
+    /// Every value unpacked through it must pack back to exactly the bytes
+    /// it was read from, which rejects non-minimal `VarUint32`s, `bool`s
+    /// other than 0 or 1 and any other alternative encoding. Call `finish`
+    /// once done to also reject trailing bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use antelope::serializer::Decoder;
+    ///
+    /// // the length prefix 1 written in two bytes
+    /// let data = [0x81, 0x00, b'a'];
+    /// let mut value = String::new();
+    /// assert!(Decoder::new(&data).unpack(&mut value).is_ok());
+    /// assert!(Decoder::new_strict(&data).unpack(&mut value).is_err());
+    /// ```
+    pub fn new_strict(data: &'a [u8]) -> Self {
+        Self {
+            strict: true,
+            ..Self::new(data)
+        }
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
     }
 
     /// Unpacks the given value from the decoder
-    pub fn unpack<T>(&mut self, packer: &mut T) -> usize
+    ///
+    /// On failure the error offset is adjusted to be relative to the start of
+    /// this decoder's buffer.
+    pub fn unpack<T>(&mut self, packer: &mut T) -> Result<usize, DecodeError>
     where
         T: Packer,
     {
-        let size = packer.unpack(&self.buf[self.pos..]);
+        let size = packer
+            .unpack(&self.buf[self.pos..])
+            .map_err(|e| e.at(self.pos))?;
+        if self.strict {
+            ensure_canonical(packer, &self.buf[self.pos..self.pos + size])
+                .map_err(|e| e.at(self.pos))?;
+        }
         self.pos += size;
-        size
+        Ok(size)
+    }
+
+    /// Unpacks a value that may borrow from the decoder's buffer, see
+    /// `UnpackBorrowed`.
+    ///
+    /// Borrowed values cannot be packed back to check their encoding, so a
+    /// strict decoder refuses them instead of accepting non-canonical input.
+    ///
+    /// On failure the error offset is adjusted like in `unpack`.
+    pub fn unpack_borrowed<T>(&mut self) -> Result<T, DecodeError>
+    where
+        T: UnpackBorrowed<'a>,
+    {
+        if self.strict {
+            return Err(DecodeError::invalid(
+                "Decoder",
+                "borrowed values cannot be decoded strictly",
+            )
+            .at(self.pos));
+        }
+        let (value, size) =
+            T::unpack_borrowed(&self.buf[self.pos..]).map_err(|e| e.at(self.pos))?;
+        self.pos += size;
+        Ok(value)
     }
 
     /// Returns the current position of the decoder
     pub fn get_pos(&self) -> usize {
         self.pos
     }
+
+    /// Returns the number of bytes left to unpack
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns an error if any bytes are left to unpack.
+    pub fn finish(&self) -> Result<(), DecodeError> {
+        if self.remaining() > 0 {
+            return Err(DecodeError::invalid(
+                "Decoder",
+                format!("{} trailing bytes", self.remaining()),
+            )
+            .at(self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Unpacks a `T` that must use all of `data`, reporting any trailing bytes
+/// like `Decoder::finish`.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::serializer::decode_all;
+///
+/// assert_eq!(decode_all::<u16>(&[1, 0]).unwrap(), 1);
+/// assert!(decode_all::<u16>(&[1, 0, 0]).is_err());
+/// ```
+pub fn decode_all<T: Packer + Default>(data: &[u8]) -> Result<T, DecodeError> {
+    let mut dec = Decoder::new(data);
+    let mut value = T::default();
+    dec.unpack(&mut value)?;
+    dec.finish()?;
+    Ok(value)
+}
+
+/// Unpacks a `T` that must use all of `data` in its canonical encoding, see
+/// `Decoder::new_strict`.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::serializer::decode_exact;
+///
+/// assert_eq!(decode_exact::<u16>(&[1, 0]).unwrap(), 1);
+/// assert!(decode_exact::<u16>(&[1, 0, 0]).is_err());
+/// assert!(decode_exact::<bool>(&[2]).is_err());
+/// ```
+pub fn decode_exact<T: Packer + Default>(data: &[u8]) -> Result<T, DecodeError> {
+    let mut dec = Decoder::new_strict(data);
+    let mut value = T::default();
+    dec.unpack(&mut value)?;
+    dec.finish()?;
+    Ok(value)
+}
+
+/// Returns an error unless `value` packs to exactly `data`.
+fn ensure_canonical<T: Packer>(value: &T, data: &[u8]) -> Result<(), DecodeError> {
+    let packed = Encoder::pack(value);
+    if packed == data {
+        return Ok(());
+    }
+    // point at the first byte that differs
+    let offset = packed
+        .iter()
+        .zip(data)
+        .position(|(a, b)| a != b)
+        .unwrap_or(packed.len().min(data.len()));
+    Err(DecodeError::invalid(std::any::type_name::<T>(), "non-canonical encoding").at(offset))
 }
 
 /// A trait for packing and unpacking values
@@ -202,10 +386,10 @@ macro_rules! impl_packed {
             }
 
             /// Unpacks this value from the given data.
-            fn unpack(&mut self, data: &[u8]) -> usize {
-                assert!(data.len() >= self.size(), "number: buffer overflow");
+            fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+                ensure_size(data, self.size(), stringify!($ty))?;
                 *self = $ty::from_le_bytes(data[..self.size()].try_into().unwrap());
-                size_of::<$ty>()
+                Ok(size_of::<$ty>())
             }
         }
     };
@@ -229,16 +413,19 @@ impl Packer for bool {
     }
 
     /// Unpacks this value from the given data.
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(data.len() >= self.size(), "bool::unpack: buffer overflow");
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "bool")?;
         if data[0] == 1 {
             *self = true;
         } else if data[0] == 0 {
             *self = false;
         } else {
-            panic!("bool::unpack: invalid raw bool value");
+            return Err(DecodeError::invalid(
+                "bool",
+                format!("invalid raw bool value {}", data[0]),
+            ));
         }
-        self.size()
+        Ok(self.size())
     }
 }
 
@@ -257,10 +444,10 @@ impl Packer for i8 {
     }
 
     /// Unpacks this value from the given data.
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(data.len() >= self.size(), "i8::unpack: buffer overflow");
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "i8")?;
         *self = data[0] as i8;
-        self.size()
+        Ok(self.size())
     }
 }
 
@@ -279,10 +466,10 @@ impl Packer for u8 {
     }
 
     /// Unpacks this value from the given data.
-    fn unpack(&mut self, data: &[u8]) -> usize {
-        assert!(data.len() >= self.size(), "u8::unpack: buffer overflow");
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        ensure_size(data, self.size(), "u8")?;
         *self = data[0];
-        self.size()
+        Ok(self.size())
     }
 }
 
@@ -323,11 +510,11 @@ impl Packer for Float128 {
         self.size()
     }
 
-    fn unpack(&mut self, raw: &[u8]) -> usize {
+    fn unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
         let size = self.size();
-        assert!(raw.len() >= size, "Float128.unpack: buffer overflow!");
+        ensure_size(raw, size, "Float128")?;
         slice_copy(&mut self.data, &raw[..size]);
-        self.size()
+        Ok(self.size())
     }
 }
 
@@ -354,15 +541,16 @@ impl Packer for String {
     }
 
     /// Unpacks this value from the given data.
-    fn unpack(&mut self, data: &[u8]) -> usize {
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
         let mut length = VarUint32 { n: 0 };
-        let size = length.unpack(data);
-        if let Ok(s) = String::from_utf8(data[size..size + length.value() as usize].to_vec()) {
-            *self = s;
-        } else {
-            panic!("invalid utf8 string");
+        let size = length.unpack(data)?;
+        let end = size + length.value() as usize;
+        ensure_size(data, end, "String").map_err(|e| e.at(size))?;
+        match String::from_utf8(data[size..end].to_vec()) {
+            Ok(s) => *self = s,
+            Err(e) => return Err(DecodeError::invalid("String", e.to_string())),
         }
-        size + length.value() as usize
+        Ok(end)
     }
 }
 
@@ -373,15 +561,11 @@ where
 {
     /// Returns the size of this value in bytes.
     fn size(&self) -> usize {
-        if self.is_empty() {
-            return 1;
-        }
-
-        let mut size: usize = 0;
+        let mut size = VarUint32::new(self.len() as u32).size();
         for i in self {
             size += i.size();
         }
-        VarUint32::new(size as u32).size() + size
+        size
     }
 
     /// Packs this value into the given encoder.
@@ -398,17 +582,19 @@ where
     }
 
     /// Unpacks this value from the given data.
-    fn unpack(&mut self, data: &[u8]) -> usize {
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
         let mut dec = Decoder::new(data);
         let mut size = VarUint32 { n: 0 };
-        dec.unpack(&mut size);
-        self.reserve(size.value() as usize);
-        for _ in 0..size.value() {
+        dec.unpack(&mut size)?;
+        // Every element takes at least one byte in practice, so cap the
+        // reservation by what is left rather than trusting the length prefix.
+        self.reserve((size.value() as usize).min(data.len() - dec.get_pos()));
+        for i in 0..size.value() {
             let mut v: T = Default::default();
-            dec.unpack(&mut v);
+            dec.unpack(&mut v).map_err(|e| e.in_field(&i.to_string()))?;
             self.push(v);
         }
-        dec.get_pos()
+        Ok(dec.get_pos())
     }
 }
 
@@ -441,25 +627,27 @@ where
     }
 
     /// Unpacks this value from the given data.
-    fn unpack(&mut self, data: &[u8]) -> usize {
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
         if data.is_empty() {
             *self = None;
-            return 0;
+            return Ok(0);
         }
         let mut dec = Decoder::new(data);
         let mut ty: u8 = 0;
         let mut value: T = Default::default();
-        dec.unpack(&mut ty);
+        dec.unpack(&mut ty)?;
         if ty == 0 {
             *self = None;
-            return 1;
+            return Ok(1);
         }
 
-        assert_eq!(ty, 1, "bad option type!");
+        if ty != 1 {
+            return Err(DecodeError::bad_variant("Option", ty as u64));
+        }
 
-        dec.unpack(&mut value);
+        dec.unpack(&mut value)?;
         *self = Some(value);
-        dec.get_pos()
+        Ok(dec.get_pos())
     }
 }
 
@@ -479,7 +667,182 @@ where
     }
 
     /// Unpacks this value from the given data.
-    fn unpack(&mut self, data: &[u8]) -> usize {
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
         (**self).unpack(data)
     }
 }
+
+/// Implement `Packer` for tuples, packed element by element like a
+/// `pair<A, B>` struct with no length prefix.
+macro_rules! impl_packed_tuple {
+    ( $( $name:ident $index:tt ),+ ) => {
+        impl<$( $name ),+> Packer for ($( $name, )+)
+        where
+            $( $name: Packer ),+
+        {
+            /// Returns the size of this value in bytes.
+            fn size(&self) -> usize {
+                0 $( + self.$index.size() )+
+            }
+
+            /// Packs this value into the given encoder.
+            fn pack(&self, enc: &mut Encoder) -> usize {
+                let pos = enc.get_size();
+                $( self.$index.pack(enc); )+
+                enc.get_size() - pos
+            }
+
+            /// Unpacks this value from the given data.
+            fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+                let mut dec = Decoder::new(data);
+                $(
+                    dec.unpack(&mut self.$index)
+                        .map_err(|e| e.in_field(stringify!($index)))?;
+                )+
+                Ok(dec.get_pos())
+            }
+        }
+    };
+}
+
+impl_packed_tuple!(A 0);
+impl_packed_tuple!(A 0, B 1);
+impl_packed_tuple!(A 0, B 1, C 2);
+impl_packed_tuple!(A 0, B 1, C 2, D 3);
+impl_packed_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_packed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_packed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_packed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// Implement `Packer` for `[T; N]` type.
+///
+/// The elements are written one after another without a length prefix, like
+/// a `std::array<T, N>` in a contract, as `N` is part of the type.
+impl<T, const N: usize> Packer for [T; N]
+where
+    T: Packer,
+{
+    /// Returns the size of this value in bytes.
+    fn size(&self) -> usize {
+        let mut size = 0;
+        for i in self {
+            size += i.size();
+        }
+        size
+    }
+
+    /// Packs this value into the given encoder.
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        let pos = enc.get_size();
+        for v in self {
+            v.pack(enc);
+        }
+        enc.get_size() - pos
+    }
+
+    /// Unpacks this value from the given data.
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut dec = Decoder::new(data);
+        for (i, v) in self.iter_mut().enumerate() {
+            dec.unpack(v).map_err(|e| e.in_field(&i.to_string()))?;
+        }
+        Ok(dec.get_pos())
+    }
+}
+
+/// Packs `(key, value)` pairs like an ABI `map<K, V>`, a vector of pairs.
+fn pack_map<'a, K, V>(
+    len: usize,
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+    enc: &mut Encoder,
+) -> usize
+where
+    K: Packer + 'a,
+    V: Packer + 'a,
+{
+    let pos = enc.get_size();
+    VarUint32::new(len as u32).pack(enc);
+    for (k, v) in entries {
+        k.pack(enc);
+        v.pack(enc);
+    }
+    enc.get_size() - pos
+}
+
+/// Unpacks the pairs written by `pack_map`, handing each to `insert`.
+fn unpack_map<K, V>(data: &[u8], mut insert: impl FnMut(K, V)) -> Result<usize, DecodeError>
+where
+    K: Packer + Default,
+    V: Packer + Default,
+{
+    let mut dec = Decoder::new(data);
+    let mut size = VarUint32 { n: 0 };
+    dec.unpack(&mut size)?;
+    for i in 0..size.value() {
+        let mut entry: (K, V) = Default::default();
+        dec.unpack(&mut entry)
+            .map_err(|e| e.in_field(&i.to_string()))?;
+        insert(entry.0, entry.1);
+    }
+    Ok(dec.get_pos())
+}
+
+/// Implement `Packer` for `BTreeMap<K, V>` type, packed in key order.
+impl<K, V> Packer for BTreeMap<K, V>
+where
+    K: Packer + Default + Ord,
+    V: Packer + Default,
+{
+    /// Returns the size of this value in bytes.
+    fn size(&self) -> usize {
+        let mut size = VarUint32::new(self.len() as u32).size();
+        for (k, v) in self {
+            size += k.size() + v.size();
+        }
+        size
+    }
+
+    /// Packs this value into the given encoder.
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        pack_map(self.len(), self.iter(), enc)
+    }
+
+    /// Unpacks this value from the given data.
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        unpack_map(data, |k, v| {
+            self.insert(k, v);
+        })
+    }
+}
+
+/// Implement `Packer` for `HashMap<K, V>` type, packed in key order like a
+/// `BTreeMap`, so the same map always packs to the same bytes.
+impl<K, V, S> Packer for HashMap<K, V, S>
+where
+    K: Packer + Default + Eq + Hash + Ord,
+    V: Packer + Default,
+    S: BuildHasher,
+{
+    /// Returns the size of this value in bytes.
+    fn size(&self) -> usize {
+        let mut size = VarUint32::new(self.len() as u32).size();
+        for (k, v) in self {
+            size += k.size() + v.size();
+        }
+        size
+    }
+
+    /// Packs this value into the given encoder.
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        pack_map(self.len(), entries.into_iter(), enc)
+    }
+
+    /// Unpacks this value from the given data.
+    fn unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        unpack_map(data, |k, v| {
+            self.insert(k, v);
+        })
+    }
+}
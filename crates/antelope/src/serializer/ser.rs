@@ -0,0 +1,411 @@
+use serde::{ser, Serialize};
+
+use crate::{
+    chain::varint::VarUint32,
+    serializer::{error::EncodeError, key_order::KeyOrder, Encoder, Packer},
+};
+
+/// Newtype name marking a `u32` to be written as a varuint32.
+pub(crate) const VARUINT32_TOKEN: &str = "$antelope::VarUint32";
+/// Newtype name marking an optional value that is left out when absent,
+/// like `chain::binary_extension::BinaryExtension`.
+pub(crate) const BINARY_EXTENSION_TOKEN: &str = "$antelope::BinaryExtension";
+
+/// Packs `value` in the Antelope binary format through its `Serialize`
+/// impl, producing the same bytes as the matching `Packer` impl.
+///
+/// Sequences and maps are prefixed with their length as a varuint32. Map
+/// entries are sorted by key like their `Packer` impls do, comparing keys
+/// through their `Serialize` impl as a derived `Ord` would, so a `HashMap`
+/// packs the same as a `BTreeMap`.
+/// Structs and tuples are written field by field, options get a one byte tag
+/// and enum variants a varuint32 index. Fixed size arrays are written like
+/// tuples, with no length prefix, as their `Packer` impl does.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::serializer::to_bytes;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Transfer {
+///     amount: u32,
+///     memo: String,
+/// }
+///
+/// let data = to_bytes(&Transfer { amount: 1, memo: String::from("hi") }).unwrap();
+/// assert_eq!(data, vec![1, 0, 0, 0, 2, b'h', b'i']);
+/// ```
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, EncodeError> {
+    let mut serializer = BinarySerializer::new();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_bytes())
+}
+
+/// A `serde::Serializer` writing the Antelope binary format, see `to_bytes`.
+pub struct BinarySerializer {
+    enc: Encoder,
+    /// Set while serializing the content of a `VARUINT32_TOKEN` newtype.
+    varuint32: bool,
+    /// Set while serializing the option in a `BINARY_EXTENSION_TOKEN` newtype.
+    binary_extension: bool,
+}
+
+impl Default for BinarySerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BinarySerializer {
+    pub fn new() -> Self {
+        Self {
+            enc: Encoder::new(0),
+            varuint32: false,
+            binary_extension: false,
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.enc.get_bytes().to_vec()
+    }
+
+    fn write_length(&mut self, len: Option<usize>) -> Result<(), EncodeError> {
+        let len = len.ok_or_else(|| EncodeError::new("sequence length must be known"))?;
+        let len = u32::try_from(len).map_err(|_| EncodeError::new("sequence too long"))?;
+        VarUint32::new(len).pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn write_variant(&mut self, variant_index: u32) {
+        VarUint32::new(variant_index).pack(&mut self.enc);
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut BinarySerializer {
+    type Ok = ();
+    type Error = EncodeError;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), EncodeError> {
+        if std::mem::take(&mut self.varuint32) {
+            VarUint32::new(v).pack(&mut self.enc);
+        } else {
+            v.pack(&mut self.enc);
+        }
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), EncodeError> {
+        v.pack(&mut self.enc);
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), EncodeError> {
+        self.serialize_str(v.encode_utf8(&mut [0u8; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), EncodeError> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), EncodeError> {
+        self.write_length(Some(v.len()))?;
+        self.enc.alloc(v.len()).copy_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), EncodeError> {
+        if !std::mem::take(&mut self.binary_extension) {
+            0u8.pack(&mut self.enc);
+        }
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), EncodeError> {
+        if !std::mem::take(&mut self.binary_extension) {
+            1u8.pack(&mut self.enc);
+        }
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), EncodeError> {
+        self.write_variant(variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        self.varuint32 = name == VARUINT32_TOKEN;
+        self.binary_extension = name == BINARY_EXTENSION_TOKEN;
+        value.serialize(&mut *self)?;
+        if std::mem::take(&mut self.varuint32) {
+            return Err(EncodeError::new("varuint32 must wrap a u32"));
+        }
+        if std::mem::take(&mut self.binary_extension) {
+            return Err(EncodeError::new("binary extension must wrap an option"));
+        }
+        Ok(())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        self.write_variant(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, EncodeError> {
+        self.write_length(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, EncodeError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, EncodeError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, EncodeError> {
+        self.write_variant(variant_index);
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer<'a>, EncodeError> {
+        self.write_length(len)?;
+        Ok(MapSerializer {
+            ser: self,
+            entries: Vec::new(),
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, EncodeError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, EncodeError> {
+        self.write_variant(variant_index);
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut BinarySerializer {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut BinarySerializer {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut BinarySerializer {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut BinarySerializer {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+/// Buffers the entries of a map to write them sorted by key.
+pub struct MapSerializer<'a> {
+    ser: &'a mut BinarySerializer,
+    entries: Vec<(KeyOrder, BinarySerializer)>,
+}
+
+impl ser::SerializeMap for MapSerializer<'_> {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), EncodeError> {
+        let mut entry = BinarySerializer::new();
+        key.serialize(&mut entry)?;
+        self.entries.push((KeyOrder::of(key)?, entry));
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        match self.entries.last_mut() {
+            Some((_, entry)) => value.serialize(entry),
+            None => Err(EncodeError::new("map value without a key")),
+        }
+    }
+
+    fn end(mut self) -> Result<(), EncodeError> {
+        self.entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (_, entry) in self.entries {
+            let bytes = entry.enc.get_bytes();
+            self.ser.enc.alloc(bytes.len()).copy_from_slice(bytes);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut BinarySerializer {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut BinarySerializer {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use std::io::{ErrorKind, Read, Write};
+
+use bytes::{buf::Reader, Buf};
+
+use crate::serializer::{
+    error::{DecodeErrorKind, StreamError},
+    Encoder, Packer,
+};
+
+/// Minimum number of bytes requested from the reader at a time.
+const CHUNK_SIZE: usize = 8192;
+
+/// Packs values straight into an `io::Write`, the streaming counterpart of
+/// `Encoder`.
+///
+/// Each value is packed into a reused buffer and then written out, so only
+/// one value at a time is held in memory. A `bytes::BufMut` can be written to
+/// through `BufMut::writer`.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::serializer::StreamEncoder;
+///
+/// let mut encoder = StreamEncoder::new(Vec::new());
+/// encoder.pack(&1u32).unwrap();
+/// encoder.pack(&String::from("hi")).unwrap();
+/// assert_eq!(encoder.get_size(), 7);
+/// assert_eq!(encoder.into_inner(), vec![1, 0, 0, 0, 2, b'h', b'i']);
+/// ```
+pub struct StreamEncoder<W: Write> {
+    writer: W,
+    enc: Encoder,
+    size: usize,
+}
+
+impl<W: Write> StreamEncoder<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            enc: Encoder::new(0),
+            size: 0,
+        }
+    }
+
+    /// Packs `value` and writes it to the writer.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written.
+    pub fn pack<T: Packer>(&mut self, value: &T) -> std::io::Result<usize> {
+        self.enc.clear();
+        value.pack(&mut self.enc);
+        self.writer.write_all(self.enc.get_bytes())?;
+        self.size += self.enc.get_size();
+        Ok(self.enc.get_size())
+    }
+
+    /// Returns the total number of bytes written so far.
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Unpacks values incrementally from an `io::Read`, the streaming
+/// counterpart of `Decoder`.
+///
+/// Input is buffered in chunks and a value is retried with more input when
+/// it runs past the buffered bytes, so only about one value at a time is
+/// held in memory. A value is returned as soon as it decodes, so a trailing
+/// `Option` or `BinaryExtension` cut off by a short read would be taken as
+/// absent; read such values with `unpack_until_eof`.
+///
+/// # Examples
+///
+/// ```
+/// use antelope::serializer::StreamDecoder;
+///
+/// let data: &[u8] = &[1, 0, 0, 0, 2, b'h', b'i'];
+/// let mut decoder = StreamDecoder::new(data);
+/// let mut number = 0u32;
+/// let mut text = String::new();
+/// decoder.unpack(&mut number).unwrap();
+/// decoder.unpack(&mut text).unwrap();
+/// assert_eq!((number, text.as_str()), (1, "hi"));
+/// assert!(decoder.is_eof().unwrap());
+/// ```
+pub struct StreamDecoder<R: Read> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Start of the unconsumed bytes in `buf`.
+    start: usize,
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: Read> StreamDecoder<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            start: 0,
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    /// Unpacks the next value from the stream into `packer`.
+    ///
+    /// On failure the error offset is relative to the start of the stream.
+    /// `packer` is only overwritten when unpacking succeeds.
+    pub fn unpack<T>(&mut self, packer: &mut T) -> Result<usize, StreamError>
+    where
+        T: Packer + Default,
+    {
+        self.unpack_value(packer, false)
+    }
+
+    /// Unpacks the next value like `unpack`, but a value that uses up all of
+    /// the buffered bytes is retried once more input or the end of the
+    /// stream arrives. Use it for values ending in an `Option` or
+    /// `BinaryExtension`, which are only absent at the end of the stream.
+    pub fn unpack_until_eof<T>(&mut self, packer: &mut T) -> Result<usize, StreamError>
+    where
+        T: Packer + Default,
+    {
+        self.unpack_value(packer, true)
+    }
+
+    fn unpack_value<T>(&mut self, packer: &mut T, until_eof: bool) -> Result<usize, StreamError>
+    where
+        T: Packer + Default,
+    {
+        self.fill(1)?;
+        loop {
+            let mut value = T::default();
+            match value.unpack(&self.buf[self.start..]) {
+                Ok(size) if until_eof && size == self.buffered() && !self.eof => {
+                    // The value may end in an extension cut off by the read
+                    self.fill(size + 1)?;
+                }
+                Ok(size) => {
+                    self.start += size;
+                    self.pos += size;
+                    *packer = value;
+                    return Ok(size);
+                }
+                Err(e) if matches!(e.kind, DecodeErrorKind::UnexpectedEof { .. }) && !self.eof => {
+                    self.fill(self.buffered() * 2)?;
+                }
+                Err(e) => return Err(e.at(self.pos).into()),
+            }
+        }
+    }
+
+    /// Returns true once every byte of the stream has been unpacked.
+    pub fn is_eof(&mut self) -> std::io::Result<bool> {
+        self.fill(1)?;
+        Ok(self.buffered() == 0)
+    }
+
+    /// Returns the number of bytes unpacked from the stream so far.
+    pub fn get_pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn buffered(&self) -> usize {
+        self.buf.len() - self.start
+    }
+
+    /// Reads until at least `size` bytes are buffered or the reader is
+    /// exhausted.
+    fn fill(&mut self, size: usize) -> std::io::Result<()> {
+        if self.buffered() >= size || self.eof {
+            return Ok(());
+        }
+        self.buf.drain(..self.start);
+        self.start = 0;
+        let capacity = size.max(CHUNK_SIZE);
+        while self.buf.len() < size && !self.eof {
+            let len = self.buf.len();
+            self.buf.resize(capacity, 0);
+            let read = loop {
+                match self.reader.read(&mut self.buf[len..]) {
+                    Ok(read) => break read,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        self.buf.truncate(len);
+                        return Err(e);
+                    }
+                }
+            };
+            self.buf.truncate(len + read);
+            self.eof = read == 0;
+        }
+        Ok(())
+    }
+}
+
+impl<B: Buf> StreamDecoder<Reader<B>> {
+    /// Creates a decoder reading from a `bytes::Buf`.
+    pub fn from_buf(buf: B) -> Self {
+        Self::new(buf.reader())
+    }
+}
@@ -1,6 +1,6 @@
-use std::io::Write;
+use std::io::{Read, Write};
 
-use flate2::{write::ZlibEncoder, Compression};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 use hex::{decode, encode};
 
 pub fn hex_to_bytes(hex: &str) -> Vec<u8> {
@@ -37,3 +37,17 @@ pub fn zlib_compress(bytes: &[u8]) -> Result<Vec<u8>, String> {
     }
     Ok(compressed_bytes.unwrap())
 }
+
+/// Decompresses `bytes`, failing once they decompress to more than `limit`
+/// bytes so that untrusted input cannot exhaust memory.
+pub fn zlib_decompress(bytes: &[u8], limit: usize) -> Result<Vec<u8>, String> {
+    let mut decompressed = Vec::new();
+    ZlibDecoder::new(bytes)
+        .take(limit as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| format!("Error during decompression: {e}"))?;
+    if decompressed.len() > limit {
+        return Err(format!("Decompressed data exceeds {limit} bytes"));
+    }
+    Ok(decompressed)
+}
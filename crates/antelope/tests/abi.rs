@@ -0,0 +1,486 @@
+use antelope::api::system::structs::{NewAccountAction, TransferAction};
+use antelope::chain::{
+    abi::{
+        AbiAction, AbiBuilder, AbiChangeKind, AbiErrorMessage, AbiExtension, AbiFeature, AbiField,
+        AbiType, AbiTypeDef, AbiValidationError, Compatibility, ABI,
+    },
+    action::PermissionLevel,
+    asset::Asset,
+    authority::Authority,
+    binary_extension::BinaryExtension,
+    name::Name,
+    Decoder, Encoder,
+};
+use antelope::serializer::AbiSerializer;
+use antelope::{name, AbiType, EnumPacker, StructPacker};
+
+mod token {
+    antelope::antelope_abi!("tests/abi/eosio.token.abi.json");
+}
+
+mod bindings {
+    antelope::antelope_abi!("tests/abi/bindings.abi.json");
+}
+
+#[test]
+fn generated_token_bindings() {
+    let transfer = token::Transfer {
+        from: name!("alice"),
+        to: name!("bob"),
+        quantity: Asset::from_string("1.0000 EOS"),
+        memo: String::from("hello"),
+    };
+    let expected = TransferAction {
+        from: name!("alice"),
+        to: name!("bob"),
+        quantity: Asset::from_string("1.0000 EOS"),
+        memo: String::from("hello"),
+    };
+    assert_eq!(Encoder::pack(&transfer), Encoder::pack(&expected));
+
+    let authorization = vec![PermissionLevel::new(name!("alice"), name!("active"))];
+    let action = token::actions::transfer(
+        name!("eosio.token"),
+        authorization.clone(),
+        transfer.clone(),
+    );
+    assert_eq!(action.account, name!("eosio.token"));
+    assert_eq!(action.name, name!("transfer"));
+    assert_eq!(token::actions::TRANSFER, name!("transfer"));
+    assert_eq!(action.authorization, authorization);
+    assert_eq!(action.data, Encoder::pack(&transfer));
+
+    assert_eq!(token::tables::ACCOUNTS, name!("accounts"));
+    let row = token::tables::Stat {
+        supply: Asset::from_string("1.0000 EOS"),
+        max_supply: Asset::from_string("10.0000 EOS"),
+        issuer: name!("eosio"),
+    };
+    let data = Encoder::pack(&row);
+    let mut decoded = token::CurrencyStats::default();
+    Decoder::new(&data).unpack(&mut decoded).unwrap();
+    assert_eq!(decoded, row);
+}
+
+#[test]
+fn generated_bindings() {
+    let info = bindings::Info {
+        owner: name!("alice"),
+        r#type: 1,
+        value: bindings::ValueVariant::String(String::from("value")),
+        friends: vec![name!("bob")],
+        next: Some(Box::new(bindings::Info {
+            owner: name!("bob"),
+            value: bindings::ValueVariant::AssetArray(vec![Asset::from_string("1.0000 EOS")]),
+            memo: BinaryExtension::new(Some(String::from("inner"))),
+            ..Default::default()
+        })),
+        memo: BinaryExtension::new(Some(String::from("memo"))),
+    };
+    let data = Encoder::pack(&info);
+    let mut decoded = bindings::tables::Infos::default();
+    Decoder::new(&data).unpack(&mut decoded).unwrap();
+    assert_eq!(decoded, info);
+
+    let owner: bindings::AccountName = name!("alice");
+    let friends: bindings::Names = vec![owner];
+    assert_eq!(friends, vec![name!("alice")]);
+
+    let action = bindings::actions::set_info(name!("contract"), vec![], info);
+    assert_eq!(action.name, name!("set.info"));
+    assert_eq!(bindings::actions::SET_INFO, name!("set.info"));
+
+    let action = bindings::actions::clear(name!("contract"), vec![], bindings::Empty {});
+    assert!(action.data.is_empty());
+
+    // A table named after its row struct refers to the struct
+    assert_eq!(bindings::tables::CONFIG, name!("config"));
+    let config: bindings::tables::Config = bindings::Config {
+        admin: name!("alice"),
+        paused: true,
+    };
+    assert_eq!(
+        Encoder::pack(&config),
+        [Encoder::pack(&name!("alice")), vec![1]].concat()
+    );
+}
+
+#[derive(Debug, Clone, Default, PartialEq, StructPacker, AbiType)]
+#[abi(name = "account")]
+struct Account {
+    balance: Asset,
+}
+
+#[derive(Debug, Clone, PartialEq, EnumPacker, AbiType)]
+enum Payload {
+    Name(Name),
+    Names(Vec<Name>),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, StructPacker, AbiType)]
+struct SetPayload {
+    owner: Name,
+    r#type: u8,
+    payload: Payload,
+    next: Option<Box<SetPayload>>,
+    #[abi(name = "note")]
+    memo: BinaryExtension<String>,
+}
+
+#[test]
+fn derived_abi_matches_token_abi() {
+    let abi = AbiBuilder::new()
+        .action::<TransferAction>(name!("transfer"))
+        .table::<Account>(name!("accounts"))
+        .build();
+    let token = ABI::from_string(include_str!("abi/eosio.token.abi.json")).unwrap();
+
+    assert_eq!(abi.version, "eosio::abi/1.2");
+    assert_eq!(abi.actions.len(), 1);
+    assert_eq!(abi.actions[0].name, name!("transfer"));
+    assert_eq!(abi.actions[0].r#type, "transfer");
+    assert_eq!(abi.tables[0].name, name!("accounts"));
+    assert_eq!(abi.tables[0].r#type, "account");
+    for s in &abi.structs {
+        let expected = token.structs.iter().find(|t| t.name == s.name).unwrap();
+        assert_eq!(s, expected);
+    }
+}
+
+#[test]
+fn derived_abi_definitions() {
+    assert_eq!(SetPayload::abi_type(), "set_payload");
+    assert_eq!(<Vec<u8>>::abi_type(), "bytes");
+    assert_eq!(<Vec<Vec<Name>>>::abi_type(), "name[][]");
+
+    let abi = AbiBuilder::new()
+        .action::<SetPayload>(name!("setpayload"))
+        .action::<NewAccountAction>(name!("newaccount"))
+        .build();
+    let structs: Vec<&str> = abi.structs.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        structs,
+        vec![
+            "set_payload",
+            "newaccount",
+            "authority",
+            "key_weight",
+            "permission_level_weight",
+            "permission_level",
+            "wait_weight",
+        ]
+    );
+    let types: Vec<(&str, &str)> = abi.structs[0]
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.r#type.as_str()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            ("owner", "name"),
+            ("type", "uint8"),
+            ("payload", "payload"),
+            ("next", "set_payload?"),
+            ("note", "string$"),
+        ]
+    );
+    assert_eq!(abi.variants.len(), 1);
+    assert_eq!(abi.variants[0].name, "payload");
+    assert_eq!(abi.variants[0].types, vec!["name", "name[]", "bytes"]);
+
+    // The derived ABI encodes exactly like the Rust types.
+    let value = SetPayload {
+        owner: name!("alice"),
+        r#type: 2,
+        payload: Payload::Names(vec![name!("bob")]),
+        next: Some(Box::new(SetPayload {
+            payload: Payload::Bytes(vec![1, 2]),
+            memo: BinaryExtension::new(Some(String::from("inner"))),
+            ..Default::default()
+        })),
+        memo: BinaryExtension::new(Some(String::from("outer"))),
+    };
+    let data = Encoder::pack(&value);
+    let serializer = AbiSerializer::new(abi);
+    let json = serializer
+        .bin_to_action(name!("setpayload"), &data)
+        .unwrap();
+    assert_eq!(json["note"], "outer");
+    assert_eq!(
+        serializer
+            .action_to_bin(name!("setpayload"), &json)
+            .unwrap(),
+        data
+    );
+
+    let new_account = NewAccountAction {
+        creator: name!("eosio"),
+        name: name!("alice"),
+        owner: Authority::new(1),
+        active: Authority::new(1),
+    };
+    let data = Encoder::pack(&new_account);
+    let json = serializer
+        .bin_to_action(name!("newaccount"), &data)
+        .unwrap();
+    assert_eq!(
+        serializer
+            .action_to_bin(name!("newaccount"), &json)
+            .unwrap(),
+        data
+    );
+}
+
+#[derive(Debug, Clone, Default, PartialEq, StructPacker, AbiType)]
+struct Tagged {
+    owner: Name,
+    #[packer(skip)]
+    cached: u64,
+    event: Event,
+    #[packer(extension)]
+    tag: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, EnumPacker, AbiType)]
+enum Event {
+    #[packer(index = 1)]
+    Value(u32),
+    #[packer(index = 0)]
+    Owner(Name),
+}
+
+#[test]
+fn derived_abi_packer_attributes() {
+    let abi = AbiBuilder::new().action::<Tagged>(name!("tag")).build();
+    let types: Vec<(&str, &str)> = abi.structs[0]
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.r#type.as_str()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![("owner", "name"), ("event", "event"), ("tag", "uint32$")]
+    );
+    // variants are listed by their packed index
+    assert_eq!(abi.variants[0].types, vec!["name", "uint32"]);
+
+    let serializer = AbiSerializer::new(abi);
+    for value in [
+        Tagged {
+            owner: name!("alice"),
+            cached: 0,
+            event: Event::Value(7),
+            tag: Some(3),
+        },
+        Tagged {
+            owner: name!("bob"),
+            cached: 0,
+            event: Event::Owner(name!("carol")),
+            tag: None,
+        },
+    ] {
+        let data = Encoder::pack(&value);
+        let json = serializer.bin_to_action(name!("tag"), &data).unwrap();
+        assert_eq!(serializer.action_to_bin(name!("tag"), &json).unwrap(), data);
+        let mut decoded = Tagged::default();
+        Decoder::new(&data).unpack(&mut decoded).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
+
+const FULL_ABI: &str = r#"{
+    "version": "eosio::abi/1.2",
+    "types": [{"new_type_name": "account_name", "type": "name"}],
+    "structs": [
+        {"name": "hi", "base": "", "fields": [{"name": "user", "type": "account_name"}, {"name": "memo", "type": "string$"}]},
+        {"name": "greeting", "base": "", "fields": [{"name": "user", "type": "name"}, {"name": "count", "type": "uint64"}]}
+    ],
+    "actions": [{"name": "hi", "type": "hi", "ricardian_contract": "say hi"}],
+    "tables": [{"name": "greetings", "index_type": "i64", "key_names": [], "key_types": [], "type": "greeting"}],
+    "ricardian_clauses": [{"id": "clause", "body": "text"}],
+    "error_messages": [{"error_code": 42, "error_msg": "no greeting"}, {"error_code": "18446744073709551615", "error_msg": "max"}],
+    "abi_extensions": [[1, "0a0b"]],
+    "variants": [{"name": "greeting_or_name", "types": ["greeting", "name"]}],
+    "action_results": [{"name": "hi", "result_type": "uint64"}],
+    "kv_tables": {
+        "kvgreetings": {
+            "type": "greeting",
+            "primary_index": {"name": "user", "type": "name"},
+            "secondary_indices": {"count": {"type": "uint64"}, "abc": {"type": "name"}}
+        }
+    }
+}"#;
+
+#[test]
+fn abi_json_and_binary_round_trip() {
+    let abi = ABI::from_string(FULL_ABI).unwrap();
+    assert_eq!(
+        abi.error_messages[1],
+        AbiErrorMessage {
+            error_code: u64::MAX,
+            error_msg: String::from("max"),
+        }
+    );
+    assert_eq!(
+        abi.abi_extensions,
+        vec![AbiExtension {
+            r#type: 1,
+            data: vec![0x0a, 0x0b],
+        }]
+    );
+    assert_eq!(abi.kv_tables[0].name, name!("kvgreetings"));
+    assert_eq!(abi.kv_tables[0].primary_index.name, name!("user"));
+    // keys keep their document order
+    assert_eq!(abi.kv_tables[0].secondary_indices[0].name, name!("count"));
+    assert_eq!(abi.kv_tables[0].secondary_indices[1].name, name!("abc"));
+
+    let json = abi.to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["actions"][0]["name"], "hi");
+    assert_eq!(value["abi_extensions"][0][1], "0a0b");
+    assert_eq!(ABI::from_string(&json).unwrap(), abi);
+
+    let bytes = abi.to_bytes();
+    assert_eq!(ABI::from_bytes(&bytes).unwrap(), abi);
+
+    assert_eq!(abi.version_number(), Some((1, 2)));
+    assert_eq!(
+        abi.features(),
+        vec![
+            AbiFeature::BinaryExtensions,
+            AbiFeature::Variants,
+            AbiFeature::ActionResults,
+            AbiFeature::KvTables,
+        ]
+    );
+    assert_eq!(abi.required_version(), (1, 2));
+}
+
+#[test]
+fn abi_binary_extensions() {
+    let mut abi = ABI::from_string(FULL_ABI).unwrap();
+    abi.kv_tables.clear();
+    let bytes = abi.to_bytes();
+    assert_eq!(ABI::from_bytes(&bytes).unwrap(), abi);
+
+    // A 1.0 ABI ends right after the extensions.
+    let mut old = abi.clone();
+    old.version = String::from("eosio::abi/1.0");
+    old.variants.clear();
+    old.action_results.clear();
+    let bytes = old.to_bytes();
+    let decoded = ABI::from_bytes(&bytes[..bytes.len() - 2]).unwrap();
+    assert_eq!(decoded, old);
+    assert_eq!(decoded.required_version(), (1, 1));
+
+    assert!(ABI::from_bytes(&[bytes.as_slice(), &[0, 0, 0]].concat()).is_err());
+    assert!(ABI::from_bytes(&bytes[..10]).is_err());
+    assert!(ABI::from_string("{\"types\": []}").is_err());
+}
+
+#[test]
+fn abi_validation() {
+    let abi = ABI::from_string(FULL_ABI).unwrap();
+    assert_eq!(abi.validate(), Ok(()));
+    let token = ABI::from_string(include_str!("abi/eosio.token.abi.json")).unwrap();
+    assert_eq!(token.validate(), Ok(()));
+
+    let mut broken = abi.clone();
+    broken.types.push(AbiTypeDef {
+        new_type_name: String::from("a"),
+        r#type: String::from("b"),
+    });
+    broken.types.push(AbiTypeDef {
+        new_type_name: String::from("b"),
+        r#type: String::from("a"),
+    });
+    broken.structs[1].fields[1].r#type = String::from("uint65?");
+    broken.structs.push(broken.structs[0].clone());
+    broken.actions.push(AbiAction {
+        name: name!("hi"),
+        r#type: String::from("hi"),
+        ricardian_contract: String::new(),
+    });
+    broken.tables[0].r#type = String::from("greetings_row");
+
+    let errors = broken.validate().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            AbiValidationError::DuplicateType(String::from("hi")),
+            AbiValidationError::DuplicateAction(name!("hi")),
+            AbiValidationError::TypedefCycle(String::from("a")),
+            AbiValidationError::TypedefCycle(String::from("b")),
+            AbiValidationError::UnknownType {
+                context: String::from("field greeting.count"),
+                type_name: String::from("uint65?"),
+            },
+            AbiValidationError::MissingTableType {
+                table: name!("greetings"),
+                type_name: String::from("greetings_row"),
+            },
+        ]
+    );
+    assert_eq!(
+        errors[5].to_string(),
+        "table greetings uses missing row type greetings_row"
+    );
+}
+
+#[test]
+fn abi_diff() {
+    let old = ABI::from_string(FULL_ABI).unwrap();
+    assert!(old.diff(&old).is_empty());
+
+    let mut new = old.clone();
+    new.structs[1].fields.push(AbiField {
+        name: String::from("note"),
+        r#type: String::from("string$"),
+    });
+    new.variants[0].types.push(String::from("uint64"));
+    new.actions.push(AbiAction {
+        name: name!("bye"),
+        r#type: String::from("hi"),
+        ricardian_contract: String::new(),
+    });
+    let diff = old.diff(&new);
+    assert_eq!(diff.changes.len(), 3);
+    assert!(diff.is_compatible());
+    assert_eq!(diff.changes[0].item, "field greeting.note");
+    assert_eq!(diff.changes[0].kind, AbiChangeKind::Added);
+
+    let mut new = old.clone();
+    new.structs[1].fields.swap(0, 1);
+    new.structs[0].fields.remove(1);
+    new.variants[0].types.insert(0, String::from("uint64"));
+    new.tables.clear();
+    let diff = old.diff(&new);
+    assert!(!diff.is_compatible());
+    let changes: Vec<String> = diff.changes.iter().map(|c| c.to_string()).collect();
+    assert_eq!(
+        changes,
+        vec![
+            "field hi.memo removed (breaking)",
+            "field greeting.user reordered (breaking)",
+            "field greeting.count reordered (breaking)",
+            "variant greeting_or_name changed from greeting, name to uint64, greeting, name (breaking)",
+            "table greetings removed (breaking)",
+        ]
+    );
+
+    // A field in the middle shifts the binary layout even as an extension.
+    let mut new = old.clone();
+    new.structs[1].fields.insert(
+        1,
+        AbiField {
+            name: String::from("note"),
+            r#type: String::from("string$"),
+        },
+    );
+    let diff = old.diff(&new);
+    assert_eq!(diff.changes.len(), 1);
+    assert_eq!(diff.changes[0].compatibility, Compatibility::Breaking);
+    assert_eq!(diff.breaking_changes().count(), 1);
+}
@@ -20,13 +20,23 @@ use antelope_client_macros::StructPacker;
 
 #[test]
 fn asset() {
-    // TODO: Should asset support negative values?
-    //assert_eq!(Asset::from_string("-1.2345 NEGS").to_string(), "-1.2345 NEGS");
-    //assert.equal(Asset.from('-1.2345 NEGS').toString(), '-1.2345 NEGS')
-    //assert.equal(Asset.from('-0.2345 NEGS').toString(), '-0.2345 NEGS')
-    //assert.equal(Asset.from('-99999999999 DUCKS').toString(), '-99999999999
-    // DUCKS') assert.equal(Asset.from('-0.0000000000001 DUCKS').toString(),
-    // '-0.0000000000001 DUCKS')
+    assert_eq!(
+        Asset::from_string("-1.2345 NEGS").to_string(),
+        "-1.2345 NEGS"
+    );
+    assert_eq!(
+        Asset::from_string("-0.2345 NEGS").to_string(),
+        "-0.2345 NEGS"
+    );
+    assert_eq!(
+        Asset::from_string("-99999999999 DUCKS").to_string(),
+        "-99999999999 DUCKS"
+    );
+    assert_eq!(
+        Asset::from_string("-0.0000000000001 DUCKS").to_string(),
+        "-0.0000000000001 DUCKS"
+    );
+    assert!("1.0000 bad".parse::<Asset>().is_err());
     assert_eq!(
         Asset::from_string("0.0000000000000 DUCKS").to_string(),
         "0.0000000000000 DUCKS"
@@ -54,7 +64,7 @@ fn asset() {
     let symbol_bytes = Encoder::pack(&symbol);
     let mut symbol_decoder = Decoder::new(symbol_bytes.as_slice());
     let symbol_unpacked = &mut Symbol::default();
-    symbol_decoder.unpack(symbol_unpacked);
+    symbol_decoder.unpack(symbol_unpacked).unwrap();
     assert_eq!(symbol.to_string(), symbol_unpacked.to_string());
     /*
        // test null asset
@@ -103,6 +113,29 @@ fn asset() {
     */
 }
 
+#[test]
+fn asset_from_amount() {
+    let tlos = Symbol::new("TLOS", 4);
+    assert_eq!(
+        Asset::from_amount("1.5", tlos).unwrap().to_string(),
+        "1.5000 TLOS"
+    );
+    assert_eq!(
+        Asset::from_amount("2", tlos).unwrap().to_string(),
+        "2.0000 TLOS"
+    );
+    assert_eq!(
+        Asset::from_amount("-0.0001", tlos).unwrap().to_string(),
+        "-0.0001 TLOS"
+    );
+    assert_eq!(
+        Asset::from_amount("1.23456", tlos).unwrap_err(),
+        "Asset.from_amount: 1.23456 has more than 4 decimals"
+    );
+    assert!(Asset::from_amount("4611686018427387", tlos).is_err());
+    assert!(Asset::from_amount("1,5", tlos).is_err());
+}
+
 #[test]
 fn block_id() {
     let string = "048865fb643bca3b644647177f0cf363f7956794d0a7ec3bc6d29d93d9637308";
@@ -288,7 +321,7 @@ fn transaction() {
 
     let transfer_decoded = &mut Transfer::default();
     let mut decoder = Decoder::new(&transaction.actions[0].data);
-    decoder.unpack(transfer_decoded);
+    decoder.unpack(transfer_decoded).unwrap();
     assert_eq!(transfer_decoded.from, name!("foo"));
     /*
 
@@ -598,7 +631,7 @@ fn transaction_signature_verification() {
 
     // Decoding the transaction
     let mut transaction = Transaction::default();
-    transaction.unpack(encoded_transaction.as_slice());
+    transaction.unpack(encoded_transaction.as_slice()).unwrap();
 
     println!("CHAIN ID: {}", bytes_to_hex(&chain_id.to_vec()));
     println!();
@@ -958,33 +991,430 @@ fn authority() {
     )
     */
 }
-/*
 
-    test('packed transaction', function () {
-        // uncompressed packed transaction
-        const uncompressed = PackedTransaction.from({
-            packed_trx:
-                '34b6c664cb1b3056b588000000000190e2a51c5f25af590000000000e94c4402308db3ee1bf7a88900000000a8ed3232e04c9bae3b75a88900000000a8ed323210e04c9bae3b75a889529e9d0f0001000000',
+#[test]
+fn nodeos_json() {
+    use antelope::chain::{
+        asset::ExtendedAsset,
+        time::{BlockTimestamp, TimePoint, TimePointSec},
+        transaction::{
+            CompressionType, PackedTransaction, SignedTransaction, TransactionExtension,
+        },
+        varint::VarUint32,
+    };
+    use serde_json::json;
+
+    assert_eq!(
+        serde_json::to_value(name!("eosio.token")).unwrap(),
+        json!("eosio.token")
+    );
+    assert_eq!(
+        serde_json::to_value(Asset::from_string("1.0000 EOS")).unwrap(),
+        json!("1.0000 EOS")
+    );
+    assert_eq!(
+        serde_json::to_value(Symbol::new("EOS", 4)).unwrap(),
+        json!("4,EOS")
+    );
+    assert_eq!(
+        serde_json::to_value(ExtendedAsset::new(
+            Asset::from_string("1.0000 EOS"),
+            name!("eosio.token")
+        ))
+        .unwrap(),
+        json!({"quantity": "1.0000 EOS", "contract": "eosio.token"})
+    );
+    assert_eq!(
+        serde_json::to_value(TimePoint {
+            elapsed: 1_529_090_267_500_000
         })
-        assert.instanceOf(uncompressed.getTransaction(), Transaction)
+        .unwrap(),
+        json!("2018-06-15T19:17:47.500")
+    );
+    assert_eq!(
+        serde_json::to_value(TimePointSec::new(1_529_090_267)).unwrap(),
+        json!("2018-06-15T19:17:47")
+    );
+    assert_eq!(
+        serde_json::to_value(BlockTimestamp::new(1)).unwrap(),
+        json!("2000-01-01T00:00:00.500")
+    );
+    let checksum = Checksum256::hash(b"hello".to_vec());
+    assert_eq!(
+        serde_json::to_value(checksum).unwrap(),
+        json!(checksum.as_string())
+    );
+    let block_id = BlockId::from_bytes(&[1u8; 32]).unwrap();
+    assert_eq!(
+        serde_json::to_value(&block_id).unwrap(),
+        json!("01".repeat(32))
+    );
 
-        // zlib compressed packed transation
-        const compressedString =
-            '78dacb3d782c659f64208be036062060345879fad9aa256213401c8605cb2633322c79c8c0e8bd651e88bfe2ad9191204c80e36d735716638b77330300024516b4'
+    let key = "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63";
+    let public_key = PublicKey::new_from_str(key).unwrap();
+    assert_eq!(serde_json::to_value(&public_key).unwrap(), json!(key));
+    let private_key =
+        PrivateKey::from_str("5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3", false).unwrap();
+    let json = serde_json::to_value(&private_key).unwrap();
+    assert_eq!(json, json!(private_key.as_string()));
+    assert_eq!(
+        serde_json::from_value::<PrivateKey>(json)
+            .unwrap()
+            .to_bytes(),
+        private_key.to_bytes()
+    );
 
-        // This is a compressed transaction and should throw since it cannot be read without a compression flag
-        const compressedError = PackedTransaction.from({
-            packed_trx: compressedString,
-        })
-        assert.throws(() => compressedError.getTransaction())
+    let signed: SignedTransaction = serde_json::from_value(json!({
+        "expiration": "2023-11-14T22:13:20",
+        "ref_block_num": 1,
+        "ref_block_prefix": 2,
+        "max_net_usage_words": 300,
+        "max_cpu_usage_ms": 0,
+        "delay_sec": 0,
+        "context_free_actions": [],
+        "actions": [{
+            "account": "eosio.token",
+            "name": "transfer",
+            "authorization": [{"actor": "alice", "permission": "active"}],
+            "data": "102700000000000004454f5300000000"
+        }],
+        "transaction_extensions": [[1, "0a0b"]],
+        "signatures": [
+            "SIG_K1_KdNTcLLSyzUFC4AdMxEDn58X8ZN368euanvet4jucUdSPXvLkgsG32tpcqVvnDR9Xv1f7HsTm6kocjeZzFGvUSc2yCbdEA"
+        ],
+        "context_free_data": ["010203"]
+    }))
+    .unwrap();
+    let header = &signed.transaction.header;
+    assert_eq!(header.expiration, TimePointSec::new(1_700_000_000));
+    assert_eq!(header.max_net_usage_words, VarUint32::new(300));
+    let action = &signed.transaction.actions[0];
+    assert_eq!(action.account, name!("eosio.token"));
+    assert_eq!(action.authorization[0].actor, name!("alice"));
+    assert_eq!(
+        action.data,
+        Encoder::pack(&Asset::from_string("1.0000 EOS"))
+    );
+    assert!(
+        signed.transaction.extension
+            == vec![TransactionExtension {
+                ty: 1,
+                data: vec![10, 11],
+            }]
+    );
+    assert_eq!(signed.context_free_data, vec![vec![1, 2, 3]]);
+
+    // serialising gives back the same JSON
+    let json = serde_json::to_value(&signed).unwrap();
+    assert!(serde_json::from_value::<SignedTransaction>(json.clone()).unwrap() == signed);
+    assert_eq!(
+        json["actions"][0]["data"],
+        "102700000000000004454f5300000000"
+    );
+    assert_eq!(json["transaction_extensions"], json!([[1, "0a0b"]]));
+    assert!(json.get("header").is_none());
+    let trx_json = serde_json::to_value(&signed.transaction).unwrap();
+    assert_eq!(trx_json["expiration"], "2023-11-14T22:13:20");
+    assert!(trx_json.get("signatures").is_none());
+
+    let packed = PackedTransaction::from_signed(signed.clone(), CompressionType::NONE).unwrap();
+    let json = serde_json::to_value(&packed).unwrap();
+    assert_eq!(json["compression"], "none");
+    assert_eq!(
+        json["packed_trx"],
+        bytes_to_hex(&Encoder::pack(&signed.transaction))
+    );
+    assert!(serde_json::from_value::<PackedTransaction>(json).unwrap() == packed);
+
+    // malformed strings are errors, not panics
+    assert!(serde_json::from_value::<Name>(json!("Not.A.Name")).is_err());
+    assert!(serde_json::from_value::<Asset>(json!("1.0000")).is_err());
+    assert!(serde_json::from_value::<Checksum160>(json!("zz".repeat(20))).is_err());
+    assert!(serde_json::from_value::<Signature>(json!("SIG_K1_bad")).is_err());
+    assert!(serde_json::from_value::<Action>(json!({
+        "account": "eosio.token",
+        "name": "transfer",
+        "authorization": [],
+        "data": "not hex"
+    }))
+    .is_err());
+}
 
-        // This is a compressed transaction and should succeed since it has a compression flag
-        const compressedSuccess = PackedTransaction.from({
-            compression: 1,
-            packed_trx: compressedString,
+#[test]
+fn action_data() {
+    use antelope::chain::abi::ABI;
+    use antelope::serializer::AbiSerializer;
+    use serde_json::json;
+
+    #[derive(Debug, Default, PartialEq, StructPacker)]
+    struct Transfer {
+        from: Name,
+        to: Name,
+        quantity: Asset,
+        memo: String,
+    }
+
+    let transfer = Transfer {
+        from: name!("alice"),
+        to: name!("bob"),
+        quantity: Asset::from_string("1.0000 EOS"),
+        memo: String::from("hi"),
+    };
+    let mut action = Action::new(
+        name!("eosio.token"),
+        name!("transfer"),
+        PermissionLevel::new(name!("alice"), name!("active")),
+        Encoder::pack(&transfer),
+    );
+    // `new` packs the bytes again with a length prefix
+    assert!(action.decode_data::<Transfer>().is_err());
+    action.data = Encoder::pack(&transfer);
+    assert_eq!(action.decode_data::<Transfer>().unwrap(), transfer);
+    action.data.push(0);
+    let err = action.decode_data::<Transfer>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to decode Decoder (offset 35): 1 trailing bytes"
+    );
+    action.data.pop();
+
+    let hex = bytes_to_hex(&action.data);
+    let object = json!({"from": "alice", "to": "bob", "quantity": "1.0000 EOS", "memo": "hi"});
+    let with_data = |data: serde_json::Value| {
+        json!({
+            "account": "eosio.token",
+            "name": "transfer",
+            "authorization": [{"actor": "alice", "permission": "active"}],
+            "data": data,
         })
-        assert.instanceOf(compressedSuccess.getTransaction(), Transaction)
-    })
-})
+    };
+
+    // hex data, and object data with the payload in hex_data, as nodeos writes
+    let parsed: Action = serde_json::from_value(with_data(json!(hex))).unwrap();
+    assert_eq!(parsed, action);
+    let mut json = with_data(object.clone());
+    json["hex_data"] = json!(hex);
+    let parsed: Action = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, action);
+
+    // object data alone needs the ABI
+    let err = serde_json::from_value::<Action>(with_data(object.clone())).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("data of eosio.token::transfer is not hex"));
+    let abi =
+        AbiSerializer::new(ABI::from_string(include_str!("abi/eosio.token.abi.json")).unwrap());
+    assert_eq!(Action::from_json(&with_data(object), &abi).unwrap(), action);
+    let err = Action::from_json(&with_data(json!({"from": "alice"})), &abi).unwrap_err();
+    assert_eq!(err.path_string(), "$.data.to");
+}
+
+#[test]
+fn packed_transaction() {
+    use antelope::chain::transaction::{CompressionType, PackedTransaction, MAX_DECOMPRESSED_SIZE};
+    use serde_json::json;
+
+    // uncompressed packed transaction
+    let uncompressed = PackedTransaction::from_json(&json!({
+        "packed_trx": "34b6c664cb1b3056b588000000000190e2a51c5f25af590000000000e94c4402308db3ee1bf7a88900000000a8ed3232e04c9bae3b75a88900000000a8ed323210e04c9bae3b75a889529e9d0f0001000000"
+    }))
+    .unwrap();
+    assert_eq!(uncompressed.compression().unwrap(), CompressionType::NONE);
+    let transaction = uncompressed.get_transaction().unwrap();
+    assert_eq!(transaction.header.ref_block_num, 7115);
+    assert_eq!(transaction.actions[0].account, name!("farmersworld"));
+    assert_eq!(uncompressed.id().unwrap(), transaction.id());
+
+    // zlib compressed packed transaction
+    let compressed = "78dacb3d782c659f64208be036062060345879fad9aa256213401c8605cb2633322c79c8c0e8bd651e88bfe2ad9191204c80e36d735716638b77330300024516b4";
+
+    // cannot be read without the compression flag
+    let compressed_error =
+        PackedTransaction::from_json(&json!({"packed_trx": compressed})).unwrap();
+    assert!(compressed_error.get_transaction().is_err());
+
+    let compressed_success =
+        PackedTransaction::from_json(&json!({"compression": 1, "packed_trx": compressed})).unwrap();
+    assert_eq!(
+        compressed_success.compression().unwrap(),
+        CompressionType::ZLIB
+    );
+    let signed = compressed_success.get_signed_transaction().unwrap();
+    assert!(signed.context_free_data.is_empty());
+    assert_eq!(compressed_success.id().unwrap(), signed.transaction.id());
+
+    // back and forth through from_signed
+    for compression in [CompressionType::NONE, CompressionType::ZLIB] {
+        let packed = PackedTransaction::from_signed(signed.clone(), compression).unwrap();
+        assert!(packed.get_signed_transaction().unwrap() == signed);
+        assert!(PackedTransaction::from_json(&packed.to_json()).unwrap() == packed);
+        assert!(packed.signatures().is_empty());
+        assert_eq!(packed.id().unwrap(), signed.transaction.id());
+    }
+
+    // decompression stops at a configurable limit
+    let large = signed
+        .clone()
+        .with_context_free_data(vec![vec![0; MAX_DECOMPRESSED_SIZE]]);
+    let packed = PackedTransaction::from_signed(large.clone(), CompressionType::ZLIB).unwrap();
+    assert!(packed.packed_context_free_data().len() < 4096);
+    assert_eq!(
+        packed.get_signed_transaction().err().unwrap(),
+        format!("Decompressed data exceeds {MAX_DECOMPRESSED_SIZE} bytes")
+    );
+    let packed = packed.with_max_decompressed_size(2 * MAX_DECOMPRESSED_SIZE);
+    assert!(packed.get_signed_transaction().unwrap() == large);
+
+    let unknown =
+        PackedTransaction::from_json(&json!({"compression": 7, "packed_trx": ""})).unwrap();
+    assert_eq!(
+        unknown.get_transaction().err().unwrap(),
+        "Unknown compression type 7"
+    );
+    assert!(PackedTransaction::from_json(&json!({"packed_trx": "zz"})).is_err());
+}
+
+#[test]
+fn transaction_context_free_data() {
+    use antelope::chain::transaction::{CompressionType, PackedTransaction, SignedTransaction};
+
+    let chain_id = hex_to_bytes("2a02a0053e5a8cf73a56ba0fda11e4d92e0238a4a2aa74fccf46d5a910746840");
+    let trx = Transaction {
+        header: TransactionHeader::default(),
+        context_free_actions: vec![Action {
+            account: name!("eosio.null"),
+            name: name!("nonce"),
+            authorization: vec![],
+            data: vec![],
+        }],
+        actions: vec![],
+        extension: vec![],
+    };
+    let cfd = vec![b"nonce".to_vec()];
+
+    // without CFD the digest ends in 32 zero bytes
+    assert_eq!(
+        trx.signing_data(&chain_id),
+        trx.signing_data_with_cfd(&chain_id, &[])
+    );
+    assert!(trx.signing_data(&chain_id).ends_with(&[0; 32]));
+
+    // with CFD it ends in the hash of the packed CFD
+    let data = trx.signing_data_with_cfd(&chain_id, &cfd);
+    let cfd_hash = Checksum256::hash(hex_to_bytes("01056e6f6e6365"));
+    assert_eq!(
+        data,
+        [
+            chain_id.clone(),
+            Encoder::pack(&trx),
+            cfd_hash.data.to_vec()
+        ]
+        .concat()
+    );
+    assert_eq!(
+        trx.signing_digest_with_cfd(&chain_id, &cfd),
+        Checksum256::hash(data.clone()).data.to_vec()
+    );
+
+    let private_key =
+        PrivateKey::from_str("5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3", false).unwrap();
+    let mut signed = SignedTransaction::new(trx.clone()).with_context_free_data(cfd.clone());
+    assert_eq!(signed.signing_data(&chain_id), data);
+    signed.sign(&private_key, &chain_id);
+    assert_eq!(
+        signed.signatures[0].recover_message(&data),
+        private_key.to_public()
+    );
+
+    for compression in [CompressionType::NONE, CompressionType::ZLIB] {
+        let mut packed = PackedTransaction::from_signed(signed.clone(), compression).unwrap();
+        assert!(packed.has_context_free_data());
+        assert!(!packed.is_pruned().unwrap());
+        assert_eq!(packed.get_context_free_data().unwrap(), cfd);
+        let id = packed.id().unwrap();
+
+        packed.prune_context_free_data();
+        assert!(!packed.has_context_free_data());
+        assert!(packed.is_pruned().unwrap());
+        assert!(packed.get_context_free_data().unwrap().is_empty());
+        assert_eq!(packed.id().unwrap(), id);
+    }
+
+    // nothing to prune without context free actions, nor CFD to pack
+    let plain = SignedTransaction::new(Transaction::default());
+    let packed = PackedTransaction::from_signed(plain, CompressionType::ZLIB).unwrap();
+    assert!(!packed.has_context_free_data());
+    assert!(!packed.is_pruned().unwrap());
+}
+
+#[test]
+fn transaction_extensions() {
+    use antelope::chain::transaction::TransactionExtension;
+    use antelope::chain::transaction_extension::{
+        DeferredTransactionGenerationContext, ResourcePayer,
+    };
+
+    let payer = ResourcePayer {
+        payer: name!("sponsor"),
+        max_net_bytes: 4096,
+        max_cpu_us: 400,
+        max_memory_bytes: 0,
+    };
+    let extension = TransactionExtension::new(&payer);
+    assert_eq!(extension.ty, 1);
+    assert_eq!(
+        bytes_to_hex(&extension.data),
+        "000000e0523c69c5001000000000000090010000000000000000000000000000"
+    );
+    assert_eq!(
+        extension.decode::<ResourcePayer>().unwrap(),
+        Some(payer.clone())
+    );
+    assert_eq!(
+        extension
+            .decode::<DeferredTransactionGenerationContext>()
+            .unwrap(),
+        None
+    );
+    let truncated = TransactionExtension {
+        ty: 1,
+        data: extension.data[..8].to_vec(),
+    };
+    assert!(truncated.decode::<ResourcePayer>().is_err());
 
- */
+    let context = DeferredTransactionGenerationContext {
+        sender_trx_id: Checksum256::hash(b"trx".to_vec()),
+        sender_id: 42,
+        sender: name!("alice"),
+    };
+    let mut trx = Transaction::default();
+    trx.set_extension(&payer);
+    trx.set_extension(&context);
+    assert_eq!(
+        trx.extension.iter().map(|e| e.ty).collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    assert!(trx.validate_extensions().is_ok());
+    assert_eq!(trx.get_extension().unwrap(), Some(context));
+    assert_eq!(trx.get_extension().unwrap(), Some(payer.clone()));
+
+    // setting it again replaces it
+    let other = ResourcePayer {
+        max_cpu_us: 1000,
+        ..payer
+    };
+    trx.set_extension(&other);
+    assert_eq!(trx.extension.len(), 2);
+    assert_eq!(trx.get_extension().unwrap(), Some(other));
+
+    trx.extension.reverse();
+    assert_eq!(
+        trx.validate_extensions().unwrap_err(),
+        "Transaction extension 0 is not sorted, it follows 1"
+    );
+    trx.extension = vec![extension.clone(), extension];
+    assert_eq!(
+        trx.validate_extensions().unwrap_err(),
+        "Duplicate transaction extension 1"
+    );
+}
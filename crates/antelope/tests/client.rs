@@ -1,16 +1,34 @@
+use antelope::api::signer::{KeyRing, RemoteSigner, Signer};
+use antelope::api::token::TokenAPI;
+use antelope::api::transaction_builder::ReferenceBlock;
+use antelope::api::transaction_watcher::{TransactionEvent, WatchError};
 use antelope::api::v1::structs::{ErrorResponse, SendTransactionResponse, TransactionState};
+use antelope::chain::abi::ABI;
+use antelope::chain::action::{Action, PermissionLevel};
 use antelope::chain::block_id::BlockId;
-use antelope::chain::time::TimePoint;
+use antelope::chain::private_key::PrivateKey;
+use antelope::chain::time::{TimePoint, TimePointSec};
+use antelope::chain::transaction::{SignedTransaction, Transaction, TransactionHeader};
+use antelope::serializer::AbiSerializer;
 use antelope::{
     api::{
         client::APIClient,
-        v1::structs::{ClientError, GetTableRowsParams},
+        v1::structs::{
+            ClientError, GetTableByScopeParams, GetTableRowsParams, IndexPosition, TableIndexType,
+            TableKeyType,
+        },
+    },
+    chain::{
+        asset::{Asset, SymbolCode},
+        checksum::Checksum256,
+        name::Name,
     },
-    chain::{asset::Asset, checksum::Checksum256, name::Name},
-    name,
-    serializer::{Decoder, Encoder, Packer},
-    StructPacker,
+    name, StructPacker,
 };
+use futures_util::{FutureExt, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
 
 mod utils;
 use utils::mock_provider::MockProvider;
@@ -67,6 +85,128 @@ async fn chain_get_info() {
     assert_eq!(result_unwrapped.head_block_producer, name!("bp.boid"));
 }
 
+#[tokio::test]
+async fn transaction_builder() {
+    let client = APIClient::custom_provider(MockProvider {}).unwrap();
+    let info = client.v1_chain.get_info().await.unwrap();
+
+    // The defaults match the header `transact` has always used
+    let trx = client.transaction_builder().build().await.unwrap();
+    assert_eq!(trx.header, info.get_transaction_header(90).unwrap());
+    assert_eq!(trx.header.ref_block_num, 0x00e8);
+
+    let alice = PermissionLevel::new(name!("alice"), name!("active"));
+    let bob = PermissionLevel::new(name!("bob"), name!("active"));
+    let trx = client
+        .transaction_builder()
+        .reference_block(ReferenceBlock::Head)
+        .expire_seconds(300)
+        .max_cpu_usage_ms(5)
+        .max_net_usage_words(1024)
+        .delay_sec(10)
+        .action(Action::new(name!("token"), name!("open"), alice, 1u64))
+        .action(Action::new(name!("token"), name!("open"), bob, 2u64))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(trx.header.ref_block_num, 0x0238);
+    assert_eq!(
+        trx.header.ref_block_prefix,
+        info.head_block_id.ref_block_prefix().unwrap()
+    );
+    assert_eq!(
+        trx.header.expiration,
+        info.get_transaction_header(300).unwrap().expiration
+    );
+    assert_eq!(trx.header.max_cpu_usage_ms, 5);
+    assert_eq!(trx.header.max_net_usage_words.value(), 1024);
+    assert_eq!(trx.header.delay_sec.value(), 10);
+    assert_eq!(trx.actions.len(), 2);
+
+    let block =
+        BlockId::from_bytes(&[&[0, 0, 0x30, 0x39][..], &[0; 4], &[1, 2, 3, 4], &[0; 20]].concat())
+            .unwrap();
+    let trx = client
+        .transaction_builder()
+        .reference_block(ReferenceBlock::Block(block))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(trx.header.ref_block_num, 0x3039);
+    assert_eq!(trx.header.ref_block_prefix, 0x04030201);
+
+    // A short block id or an expiration past the end of time is an error
+    for bytes in [vec![0, 1], vec![0, 0, 0x30, 0x39]] {
+        let short = BlockId { bytes };
+        assert!(short.ref_block_prefix().is_err());
+        let result = client
+            .transaction_builder()
+            .reference_block(ReferenceBlock::Block(short))
+            .build()
+            .await;
+        assert!(matches!(result, Err(ClientError::SIMPLE(_))));
+    }
+    assert!(BlockId { bytes: vec![0, 1] }.ref_block_num().is_err());
+    assert!(serde_json::from_str::<BlockId>("\"0001\"").is_err());
+    let result = client
+        .transaction_builder()
+        .expire_seconds(u32::MAX)
+        .build()
+        .await;
+    assert!(matches!(result, Err(ClientError::SIMPLE(_))));
+
+    let alice_key =
+        PrivateKey::from_str("5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3", false).unwrap();
+    let bob_key =
+        PrivateKey::from_str("5KWu5C8FDdNcoCLta3hXuyDKJcxAgaaza3MLkwRJWwEz9C2dn5u", false).unwrap();
+    let carol_key =
+        PrivateKey::from_str("5KGNiwTYdDWVBc9RCC28hsi7tqHGUsikn9Gs8Yii93fXbkYzxGi", false).unwrap();
+    // only the keys the transaction needs sign it, nodeos rejects others
+    let ring = KeyRing::new(
+        MockProvider {},
+        vec![alice_key.clone(), carol_key, bob_key.clone()],
+    );
+    let signed = client
+        .transaction_builder()
+        .action(Action::new(name!("token"), name!("open"), alice, 1u64))
+        .action(Action::new(name!("token"), name!("open"), bob, 2u64))
+        .build_signed(&ring)
+        .await
+        .unwrap();
+    let signing_data = signed.signing_data(&info.chain_id.data);
+    assert_eq!(signed.signatures.len(), 2);
+    assert!(signed.signatures[0].verify_message(&signing_data, &alice_key.to_public()));
+    assert!(signed.signatures[1].verify_message(&signing_data, &bob_key.to_public()));
+}
+
+#[tokio::test]
+async fn remote_signer() {
+    let key =
+        PrivateKey::from_str("5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3", false).unwrap();
+    let chain_id =
+        Checksum256::from_hex("4667b205c6838ef70ff7988f6e8257e8be0e1284a2f59699054a018f743b1d11")
+            .unwrap();
+    let trx = Transaction {
+        header: TransactionHeader {
+            expiration: TimePointSec::new(1702743557),
+            ref_block_num: 0xe8,
+            ref_block_prefix: 1237873559,
+            ..Default::default()
+        },
+        actions: vec![Action::new(
+            name!("token"),
+            name!("open"),
+            PermissionLevel::new(name!("alice"), name!("active")),
+            1u64,
+        )],
+        ..Default::default()
+    };
+
+    let signer = RemoteSigner::new(MockProvider {}, vec![key.to_public()]);
+    let signatures = signer.sign(&chain_id, &trx, &[]).await.unwrap();
+    assert_eq!(signatures, key.sign(&chain_id, &trx, &[]).await.unwrap());
+}
+
 #[tokio::test]
 async fn chain_send_transaction() {
     let mock_provider = MockProvider {};
@@ -182,6 +322,27 @@ pub async fn chain_get_abi() {
     assert_eq!(abi_object.abi.tables[0].name, name!("accounts"));
 }
 
+#[tokio::test]
+pub async fn chain_get_raw_abi() {
+    let mock_provider = MockProvider {};
+    let client = APIClient::custom_provider(mock_provider).expect("Failed to create API client");
+
+    let raw_abi = client
+        .v1_chain
+        .get_raw_abi(name!("eosio.token"))
+        .await
+        .unwrap();
+    assert_eq!(raw_abi.account_name, "eosio.token");
+
+    let abi = raw_abi.decode_abi().unwrap().unwrap();
+    let bytes = abi.to_bytes();
+    assert_eq!(Checksum256::hash(bytes), raw_abi.abi_hash);
+    assert_eq!(
+        abi,
+        ABI::from_string(include_str!("abi/eosio.token.abi.json")).unwrap()
+    );
+}
+
 #[test]
 fn test_send_transaction_response() {
     let response_json = r#"{
@@ -478,6 +639,508 @@ async fn test_get_transaction_status_unknown() {
     // deserialization doesn't fail due to missing optional fields.
 }
 
+#[tokio::test]
+async fn chain_compute_and_read_only_transaction() {
+    let client = APIClient::custom_provider(MockProvider {}).unwrap();
+    let info = client.v1_chain.get_info().await.unwrap();
+
+    // Computed without a signature to estimate its resources
+    let trx = make_mock_transaction(&info, Asset::from_string("0.0420 TLOS"));
+    let computed = client
+        .v1_chain
+        .compute_transaction(SignedTransaction::new(trx.clone()))
+        .await
+        .unwrap();
+    assert_eq!(computed.processed.cpu_usage_us(), 185);
+    assert_eq!(computed.processed.net_usage_words(), 16);
+    assert_eq!(computed.processed.action_traces.len(), 1);
+
+    let mut query = trx;
+    query.actions = vec![Action::new_ex(
+        name!("eosio.token"),
+        name!("getbal"),
+        vec![],
+        name!("corecorecore"),
+    )];
+    let result = client
+        .v1_chain
+        .send_read_only_transaction(SignedTransaction::new(query))
+        .await
+        .unwrap();
+    assert!(result.processed.receipt.is_none());
+    assert_eq!(result.processed.cpu_usage_us(), 92);
+    assert_eq!(result.processed.net_usage_words(), 13);
+
+    let abi = AbiSerializer::new(
+        ABI::from_string(
+            r#"{
+                "version": "eosio::abi/1.2",
+                "structs": [{"name": "getbal", "base": "", "fields": [{"name": "owner", "type": "name"}]}],
+                "actions": [{"name": "getbal", "type": "getbal", "ricardian_contract": ""}],
+                "action_results": [{"name": "getbal", "result_type": "asset"}]
+            }"#,
+        )
+        .unwrap(),
+    );
+    let trace = &result.processed.action_traces[0];
+    assert_eq!(
+        trace.return_value(&abi).unwrap(),
+        Some(json!("1.0000 TLOS"))
+    );
+    assert_eq!(
+        trace.decode_return_value::<Asset>().unwrap(),
+        Asset::from_string("1.0000 TLOS")
+    );
+    assert_eq!(
+        computed.processed.action_traces[0]
+            .return_value(&abi)
+            .unwrap(),
+        None
+    );
+
+    // A failed execution is reported as a server error
+    let failing = make_mock_transaction(&info, Asset::from_string("0.0420 NUNYA"));
+    let error = client
+        .v1_chain
+        .compute_transaction(SignedTransaction::new(failing))
+        .await
+        .err()
+        .unwrap();
+    match error {
+        ClientError::SERVER(err) => assert_eq!(err.error.code, Some(3050003)),
+        _ => panic!("Failure response should be of type ClientError::SERVER"),
+    }
+}
+
+#[tokio::test]
+async fn chain_table_pagination() {
+    #[derive(Debug, StructPacker, Default)]
+    struct UserRow {
+        account: Name,
+    }
+
+    #[derive(Debug, StructPacker, Default)]
+    struct AccountRow {
+        balance: Asset,
+    }
+
+    let client = APIClient::custom_provider(MockProvider {}).unwrap();
+
+    let params = GetTableRowsParams {
+        code: name!("fuel.gm"),
+        table: name!("users"),
+        scope: None,
+        lower_bound: None,
+        upper_bound: None,
+        limit: Some(2),
+        reverse: None,
+        index_position: None,
+        key_type: None,
+        show_payer: None,
+    };
+    let first = client
+        .v1_chain
+        .get_table_rows::<UserRow>(params.clone())
+        .await
+        .unwrap();
+    assert!(first.more);
+    assert_eq!(first.next_key, Some(TableIndexType::UINT64(3)));
+
+    // The second page is requested from next_key
+    let rows: Vec<_> = client
+        .v1_chain
+        .get_table_rows_stream::<UserRow>(params)
+        .map(|row| row.unwrap().account)
+        .collect()
+        .await;
+    assert_eq!(rows, vec![name!("alice"), name!("bob"), name!("carol")]);
+
+    let scopes: Vec<_> = client
+        .v1_chain
+        .get_table_by_scope_stream(GetTableByScopeParams {
+            code: name!("eosio.token"),
+            table: Some(name!("accounts")),
+            ..Default::default()
+        })
+        .map(|scope| scope.unwrap())
+        .collect()
+        .await;
+    assert_eq!(scopes.len(), 2);
+    assert_eq!(scopes[0].scope, name!("corecorecore"));
+    assert_eq!(scopes[1].scope, name!("teamgreymass"));
+    assert_eq!(scopes[1].count, 1);
+
+    let balances: Vec<_> = client
+        .v1_chain
+        .get_all_table_rows_stream::<AccountRow>(GetTableRowsParams {
+            code: name!("eosio.token"),
+            table: name!("accounts"),
+            scope: None,
+            lower_bound: None,
+            upper_bound: None,
+            limit: None,
+            reverse: None,
+            index_position: None,
+            key_type: None,
+            show_payer: None,
+        })
+        .map(|row| row.unwrap())
+        .map(|(scope, row)| (scope, row.balance.symbol().code().to_string()))
+        .collect()
+        .await;
+    assert_eq!(
+        balances,
+        vec![
+            (name!("corecorecore"), String::from("TLOS")),
+            (name!("teamgreymass"), String::from("TLOS")),
+        ]
+    );
+
+    // A secondary index without bounds is paged with its own key type
+    let rows: Vec<_> = client
+        .v1_chain
+        .get_table_rows_stream::<UserRow>(GetTableRowsParams {
+            code: name!("fuel.gm"),
+            table: name!("users"),
+            limit: Some(1),
+            index_position: Some(IndexPosition::SECONDARY),
+            key_type: Some(TableKeyType::CHECKSUM256),
+            ..Default::default()
+        })
+        .map(|row| row.unwrap().account)
+        .collect()
+        .await;
+    assert_eq!(rows, vec![name!("alice"), name!("bob")]);
+
+    // A next_key equal to the bound, from more than `limit` rows sharing a
+    // key, ends the stream with an error instead of repeating the page
+    let key =
+        Checksum256::from_hex("9b4c3e5ad0f21e7c6a8d4b0e3f5a7c9d1e2b4f6a8c0d2e4f6a8b0c2d4e6f8a0b")
+            .unwrap();
+    let rows: Vec<_> = client
+        .v1_chain
+        .get_table_rows_stream::<UserRow>(GetTableRowsParams {
+            code: name!("fuel.gm"),
+            table: name!("users"),
+            lower_bound: Some(TableIndexType::CHECKSUM256(key)),
+            limit: Some(2),
+            index_position: Some(IndexPosition::SECONDARY),
+            ..Default::default()
+        })
+        .collect()
+        .await;
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1].as_ref().unwrap().account, name!("carol"));
+    assert!(matches!(rows[2], Err(ClientError::SIMPLE(_))));
+
+    // Bounds of another key type are an error instead of a panic
+    let mismatched = [
+        GetTableRowsParams {
+            lower_bound: Some(TableIndexType::UINT64(1)),
+            upper_bound: Some(TableIndexType::NAME(name!("bob"))),
+            ..Default::default()
+        },
+        GetTableRowsParams {
+            lower_bound: Some(TableIndexType::UINT64(1)),
+            key_type: Some(TableKeyType::CHECKSUM256),
+            ..Default::default()
+        },
+    ];
+    for params in mismatched {
+        let err = client
+            .v1_chain
+            .get_table_rows::<UserRow>(params)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::SIMPLE(_)));
+    }
+
+    // Server errors keep their type, from the rows and from the scopes
+    let params = GetTableRowsParams {
+        code: name!("nocontract"),
+        table: name!("accounts"),
+        scope: None,
+        lower_bound: None,
+        upper_bound: None,
+        limit: None,
+        reverse: None,
+        index_position: None,
+        key_type: None,
+        show_payer: None,
+    };
+    let table_query_error = |e: &ClientError<ErrorResponse>| match e {
+        ClientError::SERVER(e) => e.error.error.code == Some(3060003),
+        _ => false,
+    };
+    let err = client
+        .v1_chain
+        .get_table_rows::<AccountRow>(params.clone())
+        .await
+        .unwrap_err();
+    assert!(table_query_error(&err));
+    let rows: Vec<_> = client
+        .v1_chain
+        .get_all_table_rows_stream::<AccountRow>(params)
+        .collect()
+        .await;
+    assert_eq!(rows.len(), 1);
+    assert!(table_query_error(rows[0].as_ref().unwrap_err()));
+
+    // An unexpected next_key is an error instead of a panic
+    assert!(TableIndexType::UINT64(0).parse_like("alice").is_err());
+    assert_eq!(
+        TableIndexType::NAME(name!("a")).parse_like("alice"),
+        Ok(TableIndexType::NAME(name!("alice")))
+    );
+}
+
+#[tokio::test]
+async fn chain_get_table_rows_json_and_abi() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct AccountRow {
+        balance: Asset,
+    }
+
+    let client = APIClient::custom_provider(MockProvider {}).unwrap();
+    let params = GetTableRowsParams {
+        code: name!("eosio.token"),
+        table: name!("accounts"),
+        scope: Some(name!("corecorecore")),
+        lower_bound: None,
+        upper_bound: None,
+        limit: None,
+        reverse: None,
+        index_position: None,
+        key_type: None,
+        show_payer: Some(true),
+    };
+
+    let rows = client
+        .v1_chain
+        .get_table_rows_json::<AccountRow>(params.clone())
+        .await
+        .unwrap();
+    assert!(!rows.more);
+    assert_eq!(
+        rows.rows,
+        vec![AccountRow {
+            balance: Asset::from_string("9999.4956 TLOS")
+        }]
+    );
+    assert_eq!(rows.ram_payers, Some(vec![name!("corecorecore")]));
+
+    let abi =
+        AbiSerializer::new(ABI::from_string(include_str!("abi/eosio.token.abi.json")).unwrap());
+    let rows = client
+        .v1_chain
+        .get_table_rows_abi(params, &abi)
+        .await
+        .unwrap();
+    assert_eq!(rows.rows, vec![json!({"balance": "9999.4956 TLOS"})]);
+    assert_eq!(rows.ram_payers, Some(vec![name!("corecorecore")]));
+}
+
+#[tokio::test]
+async fn chain_currency_balance_and_stats() {
+    let client = APIClient::custom_provider(MockProvider {}).unwrap();
+    let tlos = SymbolCode::new("TLOS");
+
+    let balances = client
+        .v1_chain
+        .get_currency_balance(name!("eosio.token"), name!("corecorecore"), Some(tlos))
+        .await
+        .unwrap();
+    assert_eq!(balances, vec![Asset::from_string("1234.5678 TLOS")]);
+
+    let stats = client
+        .v1_chain
+        .get_currency_stats(name!("eosio.token"), tlos)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.supply, Asset::from_string("420000000.0000 TLOS"));
+    assert_eq!(
+        stats.max_supply,
+        Asset::from_string("10000000000.0000 TLOS")
+    );
+    assert_eq!(stats.issuer, name!("eosio"));
+
+    let missing = client
+        .v1_chain
+        .get_currency_stats(name!("eosio.token"), SymbolCode::new("NUNYA"))
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+}
+
+#[tokio::test]
+async fn token_api() {
+    let client = APIClient::custom_provider(MockProvider {}).unwrap();
+    let token = TokenAPI::new(client);
+    let tlos = SymbolCode::new("TLOS");
+
+    // The precision comes from the token stats
+    assert_eq!(
+        token.asset("1.5", tlos).await.unwrap(),
+        Asset::from_string("1.5000 TLOS")
+    );
+    assert_eq!(
+        token.balance(name!("corecorecore"), tlos).await.unwrap(),
+        Asset::from_string("1234.5678 TLOS")
+    );
+    assert!(token.asset("1.5", SymbolCode::new("NUNYA")).await.is_err());
+
+    let private_key =
+        PrivateKey::from_str("5JW71y3njNNVf9fiGaufq8Up5XiGk68jZ5tYhKpy69yyU9cr7n9", false).unwrap();
+    let response = token
+        .issue(
+            "1.5",
+            tlos,
+            String::from("Testing antelope-rs"),
+            private_key,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        response.transaction_id,
+        "6f2b8c4e1d9a3f7b5c0e8d2a4f6b1c3e5a7d9f0b2c4e6a8d1f3b5c7e9a0d2f4b"
+    );
+}
+
+// Time is paused so the polls and resubmit delays are exact
+#[tokio::test(start_paused = true)]
+async fn transaction_watcher() {
+    let client = APIClient::custom_provider(MockProvider {}).unwrap();
+    let irreversible =
+        Checksum256::from_hex("a3f5b2d1c05f0e9b6d4c3a2b1e0f9d8c7b6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e")
+            .unwrap();
+    let locally_applied =
+        Checksum256::from_hex("ed7cd6ad6298cbbf653963d8e1a5dc16ee4ca45fc8aaee6caa3663a56db55bbd")
+            .unwrap();
+    let unknown =
+        Checksum256::from_hex("01320dfb16105aa87973a2aac02297e0666f9d369970eb70a5c7e3d2cc50e9ff")
+            .unwrap();
+
+    // An irreversible transaction is also in a block
+    let status = client
+        .watch_transaction(irreversible)
+        .wait_for(TransactionState::InBlock)
+        .await
+        .unwrap();
+    assert_eq!(status.state, TransactionState::Irreversible);
+    assert_eq!(status.block_number, Some(79));
+
+    let result = client
+        .watch_transaction(locally_applied)
+        .poll_interval(Duration::from_millis(10))
+        .timeout(Duration::from_millis(50))
+        .wait_for_irreversible()
+        .await;
+    assert!(matches!(
+        result,
+        Err(WatchError::Timeout(Some(TransactionState::LocallyApplied)))
+    ));
+
+    // Dropped after its expiration, so it is not sent again
+    let info = client.v1_chain.get_info().await.unwrap();
+    let expired = sign_mock_transaction(
+        &make_mock_transaction(&info, Asset::from_string("0.0420 TLOS")),
+        &info,
+    );
+    let result = client
+        .watch_transaction(unknown)
+        .resubmit(expired)
+        .wait_for_irreversible()
+        .await;
+    assert!(matches!(result, Err(WatchError::Expired)));
+
+    // Dropped before its expiration, so it is sent again once per resubmit
+    // delay, instead of on every poll
+    let mut trx = make_mock_transaction(&info, Asset::from_string("0.0420 TLOS"));
+    trx.header.expiration = TimePointSec::new(1731346800);
+    let signed = sign_mock_transaction(&trx, &info);
+    let resubmissions = |events: &[Result<TransactionEvent, WatchError>]| {
+        events
+            .iter()
+            .filter(|e| matches!(e, Ok(TransactionEvent::Resubmitted)))
+            .count()
+    };
+    let events: Vec<_> = client
+        .watch_transaction(unknown)
+        .resubmit(signed.clone())
+        .poll_interval(Duration::from_millis(10))
+        .timeout(Duration::from_millis(100))
+        .stream()
+        .collect()
+        .await;
+    assert_eq!(resubmissions(&events), 0);
+    assert!(matches!(
+        &events[0],
+        Ok(TransactionEvent::State(s)) if s.state == TransactionState::Unknown
+    ));
+    assert!(matches!(
+        events.last(),
+        Some(Err(WatchError::Timeout(Some(TransactionState::Unknown))))
+    ));
+
+    let mut events = Box::pin(
+        client
+            .watch_transaction(unknown)
+            .resubmit(signed.clone())
+            .resubmit_after(Duration::from_millis(60))
+            .poll_interval(Duration::from_millis(10))
+            .timeout(Duration::from_millis(100))
+            .stream(),
+    );
+    assert!(matches!(
+        events.next().await,
+        Some(Ok(TransactionEvent::State(s))) if s.state == TransactionState::Unknown
+    ));
+    // polled every 10ms, but only sent again once dropped for 60ms
+    let mut next = events.next();
+    for _ in 0..6 {
+        assert!((&mut next).now_or_never().is_none());
+        tokio::time::advance(Duration::from_millis(10)).await;
+    }
+    assert!(matches!(
+        next.now_or_never(),
+        Some(Some(Ok(TransactionEvent::Resubmitted)))
+    ));
+    let events: Vec<_> = events.collect().await;
+    assert_eq!(resubmissions(&events), 0);
+
+    // A forked out transaction is sent again right away
+    let forked_out =
+        Checksum256::from_hex("7c1e6b9f0d3a5e2c4b8a1f6d9e0c3b7a2d5f8e1c4a7b0d3e6f9a2c5b8e1d4f70")
+            .unwrap();
+    let events: Vec<_> = client
+        .watch_transaction(forked_out)
+        .resubmit(signed.clone())
+        .poll_interval(Duration::from_millis(10))
+        .timeout(Duration::from_millis(100))
+        .stream()
+        .collect()
+        .await;
+    assert_eq!(resubmissions(&events), 1);
+    assert!(matches!(
+        &events[0],
+        Ok(TransactionEvent::State(s)) if s.state == TransactionState::ForkedOut
+    ));
+    assert!(matches!(events[1], Ok(TransactionEvent::Resubmitted)));
+
+    // Without resubmissions left it is only watched
+    let events: Vec<_> = client
+        .watch_transaction(forked_out)
+        .resubmit(signed)
+        .max_resubmissions(0)
+        .poll_interval(Duration::from_millis(10))
+        .timeout(Duration::from_millis(50))
+        .stream()
+        .collect()
+        .await;
+    assert_eq!(resubmissions(&events), 0);
+}
+
 #[tokio::test]
 pub async fn chain_get_table_rows() {
     #[derive(StructPacker, Default)]
@@ -500,6 +1163,7 @@ pub async fn chain_get_table_rows() {
             limit: None,
             reverse: None,
             index_position: None,
+            key_type: None,
             show_payer: None,
         })
         .await
@@ -532,6 +1196,7 @@ pub async fn chain_get_table_rows() {
             limit: Some(1),
             reverse: None,
             index_position: None,
+            key_type: None,
             show_payer: None,
         })
         .await
@@ -5,12 +5,13 @@ use antelope::chain::binary_extension::BinaryExtension;
 use antelope::{
     chain::{name::Name, signature::Signature, Decoder, Encoder},
     name,
-    serializer::Packer,
+    serializer::{AbiSerializer, DecodeErrorKind},
     util,
     util::{bytes_to_hex, hex_to_bytes},
 };
 use antelope_client_macros::{EnumPacker, StructPacker};
 use digest::Digest;
+use serde_json::{json, Value};
 use sha2::Sha256;
 
 mod test_bytes;
@@ -63,7 +64,7 @@ fn bytes() {
     let data_bytes = hex_to_bytes(data);
     let mut decoder = Decoder::new(data_bytes.as_slice());
     let mut bytes2 = Vec::<u8>::default();
-    decoder.unpack(&mut bytes2);
+    decoder.unpack(&mut bytes2).unwrap();
     assert_eq!(bytes, bytes2);
 }
 
@@ -76,7 +77,7 @@ fn name() {
     let data_bytes = hex_to_bytes(data);
     let mut decoder = Decoder::new(data_bytes.as_slice());
     let mut name2 = Name::default();
-    decoder.unpack(&mut name2);
+    decoder.unpack(&mut name2).unwrap();
     assert_eq!(name1, name2);
     let name3 = Name::from_u64(6712742083569909760);
     assert_eq!(name1, name3);
@@ -84,6 +85,10 @@ fn name() {
     assert_eq!(name!(".me").to_string(), ".me");
     assert_eq!(name!("you").to_string(), "you");
     assert_eq!(name!("you.me").to_string(), "you.me");
+    assert_eq!(name!("a").to_string(), "a");
+    assert_eq!("foobar".parse::<Name>().unwrap(), name1);
+    assert!("Foobar".parse::<Name>().is_err());
+    assert!("foobar.".parse::<Name>().is_err());
 }
 /*
 
@@ -310,7 +315,7 @@ fn signature() {
 
     let mut decoder = Decoder::new(data.as_slice());
     let decoded_sig = &mut Signature::default();
-    let decoded_size = decoder.unpack(decoded_sig);
+    let decoded_size = decoder.unpack(decoded_sig).unwrap();
     assert_eq!(decoded_size, 66);
     assert_eq!(decoded_sig.to_string(), json);
 }
@@ -326,7 +331,7 @@ fn signature_wa() {
 
     let mut decoder = Decoder::new(data.as_slice());
     let decoded_sig = &mut Signature::default();
-    let decoded_size = decoder.unpack(decoded_sig);
+    let decoded_size = decoder.unpack(decoded_sig).unwrap();
     let decoded_sig_str = decoded_sig.to_string();
     assert_eq!(decoded_size, 220);
     assert_eq!(decoded_sig_str, sig_str);
@@ -428,6 +433,62 @@ test('decoding errors', function () {
 
 */
 
+#[test]
+fn decoding_errors() {
+    #[derive(Default, StructPacker)]
+    struct Type3 {
+        baz: i8,
+    }
+
+    #[derive(Default, StructPacker)]
+    struct Type2 {
+        bar: Vec<Type3>,
+    }
+
+    #[derive(Default, StructPacker)]
+    struct Type1 {
+        foo: Option<Type2>,
+    }
+
+    let data = hex_to_bytes("0107010203040506");
+    let mut decoder = Decoder::new(data.as_slice());
+    let err = decoder.unpack(&mut Type1::default()).unwrap_err();
+    assert_eq!(err.path, vec!["foo", "bar", "6", "baz"]);
+    assert_eq!(err.type_name, "i8");
+    assert_eq!(err.offset, 8);
+    assert_eq!(
+        err.kind,
+        DecodeErrorKind::UnexpectedEof {
+            needed: 1,
+            remaining: 0
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "failed to decode i8 at foo.bar.6.baz (offset 8): unexpected end of input, needed 1 bytes but 0 remain"
+    );
+
+    let data = hex_to_bytes("02");
+    let err = Decoder::new(data.as_slice())
+        .unpack(&mut Type1::default())
+        .unwrap_err();
+    assert_eq!(err.kind, DecodeErrorKind::BadVariant(2));
+    assert_eq!(err.path, vec!["foo"]);
+
+    let data = hex_to_bytes("0566");
+    let err = Decoder::new(data.as_slice())
+        .unpack(&mut String::default())
+        .unwrap_err();
+    assert_eq!(err.type_name, "String");
+    assert_eq!(err.offset, 1);
+
+    let data = hex_to_bytes("02");
+    let err = Decoder::new(data.as_slice())
+        .unpack(&mut bool::default())
+        .unwrap_err();
+    assert!(matches!(err.kind, DecodeErrorKind::Invalid(_)));
+}
+
 #[test]
 fn variant() {
     #[derive(StructPacker, Default)]
@@ -450,7 +511,7 @@ fn variant() {
     let data_bytes = hex_to_bytes(data);
     let mut decoder = Decoder::new(data_bytes.as_slice());
     let decoded_uint8 = &mut MyVariant::default();
-    let decoded_size = decoder.unpack(decoded_uint8);
+    let decoded_size = decoder.unpack(decoded_uint8).unwrap();
     assert_eq!(decoded_size, 2);
     match decoded_uint8 {
         MyVariant::MyUint8(value) => assert_eq!(value, &255),
@@ -466,7 +527,7 @@ fn variant() {
     let data_bytes = hex_to_bytes(data);
     let mut decoder = Decoder::new(data_bytes.as_slice());
     let decoded_opt_struct = &mut MyVariant::default();
-    let decoded_size = decoder.unpack(decoded_opt_struct);
+    let decoded_size = decoder.unpack(decoded_opt_struct).unwrap();
     assert_eq!(decoded_size, 3);
     match decoded_opt_struct {
         MyVariant::StructOption(opt) => {
@@ -478,6 +539,12 @@ fn variant() {
             panic!("Expected MyUint8");
         }
     };
+
+    let data_bytes = hex_to_bytes("09ff");
+    let mut decoder = Decoder::new(data_bytes.as_slice());
+    let err = decoder.unpack(&mut MyVariant::default()).unwrap_err();
+    assert_eq!(err.type_name, "MyVariant");
+    assert_eq!(err.kind, DecodeErrorKind::BadVariant(9));
 }
 
 /*
@@ -839,82 +906,122 @@ test('complex type', function () {
     })
 })
 
-test('typestresser abi', function () {
-    // eslint-disable-next-line @typescript-eslint/no-var-requires
-
-    const abi = typestresserAbi
-    // .readFileSync(__dirname + '/')
-    // .toString()
-    const object = {
-        bool: true,
-        int8: 127,
-        uint8: 255,
-        int16: 32767,
-        uint16: 65535,
-        int32: 2147483647,
-        uint32: 4294967295,
-        int64: '9223372036854775807',
-        uint64: '18446744073709551615',
-        int128: '170141183460469231731687303715884105727',
-        uint128: '340282366920938463463374607431768211455',
-        varint32: 2147483647,
-        varuint32: 4294967295,
-        float32: '3.1415925',
-        float64: '3.141592653589793',
-        float128: '0xbeefbeefbeefbeefbeefbeefbeefbeef',
-        time_point: '2020-02-02T02:02:02.222',
-        time_point_sec: '2020-02-02T02:02:02',
-        block_timestamp_type: '2020-02-02T02:02:02.500',
-        name: 'foobar',
-        bytes: 'beef',
-        string: 'hello',
-        checksum160: 'ffffffffffffffffffffffffffffffffffffffff',
-        checksum256: 'ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff',
-        checksum512:
-            'ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff',
-        public_key: 'PUB_K1_5AHoNnWetuDhKWSDx3WUf8W7Dg5xjHCMc4yHmmSiaJCFvvAgnB',
-        signature:
-            'SIG_K1_KfPLgpw35iX8nfDzhbcmSBCr7nEGNEYXgmmempQspDJYBCKuAEs5rm3s4ZuLJY428Ca8ZhvR2Dkwu118y3NAoMDxhicRj9',
-        symbol: '7,PI',
-        symbol_code: 'PI',
-        asset: '3.1415926 PI',
-        extended_asset: {
-            quantity: '3.1415926 PI',
-            contract: 'pi.token',
+*/
+
+#[test]
+fn typestresser_abi() {
+    let abi = ABI::from_string(include_str!("typestresser.abi.json")).unwrap();
+    let serializer = AbiSerializer::new(abi);
+    let object: Value = serde_json::from_str(
+        r#"{
+        "bool": true,
+        "int8": 127,
+        "uint8": 255,
+        "int16": 32767,
+        "uint16": 65535,
+        "int32": 2147483647,
+        "uint32": 4294967295,
+        "int64": "9223372036854775807",
+        "uint64": "18446744073709551615",
+        "int128": "170141183460469231731687303715884105727",
+        "uint128": "340282366920938463463374607431768211455",
+        "varint32": 2147483647,
+        "varuint32": 4294967295,
+        "float32": 3.1415925,
+        "float64": 3.141592653589793,
+        "float128": "0xbeefbeefbeefbeefbeefbeefbeefbeef",
+        "time_point": "2020-02-02T02:02:02.222",
+        "time_point_sec": "2020-02-02T02:02:02",
+        "block_timestamp_type": "2020-02-02T02:02:02.500",
+        "name": "foobar",
+        "bytes": "beef",
+        "string": "hello",
+        "checksum160": "ffffffffffffffffffffffffffffffffffffffff",
+        "checksum256": "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "checksum512": "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "public_key": "PUB_K1_5AHoNnWetuDhKWSDx3WUf8W7Dg5xjHCMc4yHmmSiaJCFvvAgnB",
+        "signature": "SIG_K1_KfPLgpw35iX8nfDzhbcmSBCr7nEGNEYXgmmempQspDJYBCKuAEs5rm3s4ZuLJY428Ca8ZhvR2Dkwu118y3NAoMDxhicRj9",
+        "symbol": "7,PI",
+        "symbol_code": "PI",
+        "asset": "3.1415926 PI",
+        "extended_asset": {
+            "quantity": "3.1415926 PI",
+            "contract": "pi.token"
         },
-        alias1: true,
-        alias2: true,
-        alias3: {
-            bool: true,
+        "alias1": true,
+        "alias2": true,
+        "alias3": {
+            "bool": true
         },
-        alias4: ['int8', 1],
-        alias5: [true, true],
-        alias6: null,
-        extension: {
-            message: 'hello',
-            extension: {
-                message: 'world',
-                extension: null,
-            },
-        },
-    }
+        "alias4": ["int8", 1],
+        "alias5": [true, true],
+        "alias6": null,
+        "extension": {
+            "message": "hello",
+            "extension": {
+                "message": "world",
+                "extension": null
+            }
+        }
+    }"#,
+    )
+    .unwrap();
 
-    const data = Serializer.encode({object, type: 'all_types', abi})
-    assert.equal(
-        data.hexString,
-        '017fffff7fffffffffff7fffffffffffffffffffffff7fffffffffffffffffffffffffffffffffffffffffffffff7fffff' +
-            'fffffffffffffffffffffffffffffeffffff0fffffffff0fda0f4940182d4454fb210940beefbeefbeefbeefbeefbeefbe' +
-            'efbeefb07d56318e9d05009a2d365e35d4914b000000005c73285d02beef0568656c6c6fffffffffffffffffffffffffff' +
-            'ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff' +
-            'ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff' +
-            'ffffffffff000223e0ae8aacb41b06dc74af1a56b2eb69133f07f7f75bd1d5e53316bff195edf400205150a67288c3b393' +
-            'fdba9061b05019c54b12bdac295fc83bebad7cd63c7bb67d5cb8cc220564da006240a58419f64d06a5c6e1fc62889816a6' +
-            'c3dfdd231ed38907504900000000005049000000000000765edf01000000000750490000000000765edf01000000000750' +
-            '49000000000000000053419a81ab0101010001020101000568656c6c6f0105776f726c6400'
+    let data = serializer.json_to_bin("all_types", &object).unwrap();
+    assert_eq!(
+        bytes_to_hex(&data),
+        String::from("017fffff7fffffffffff7fffffffffffffffffffffff7fffffffffffffffffffffffffffffffffffffffffffffff7fffff")
+            + "fffffffffffffffffffffffffffffeffffff0fffffffff0fda0f4940182d4454fb210940beefbeefbeefbeefbeefbeefbe"
+            + "efbeefb07d56318e9d05009a2d365e35d4914b000000005c73285d02beef0568656c6c6fffffffffffffffffffffffffff"
+            + "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+            + "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+            + "ffffffffff000223e0ae8aacb41b06dc74af1a56b2eb69133f07f7f75bd1d5e53316bff195edf400205150a67288c3b393"
+            + "fdba9061b05019c54b12bdac295fc83bebad7cd63c7bb67d5cb8cc220564da006240a58419f64d06a5c6e1fc62889816a6"
+            + "c3dfdd231ed38907504900000000005049000000000000765edf01000000000750490000000000765edf01000000000750"
+            + "49000000000000000053419a81ab0101010001020101000568656c6c6f0105776f726c6400"
+    );
+    let decoded = serializer.bin_to_json("all_types", &data).unwrap();
+    assert_eq!(decoded, object);
+
+    // a missing binary extension is left out of the data and the output
+    let mut truncated = object.clone();
+    truncated.as_object_mut().unwrap().remove("extension");
+    let data = serializer.json_to_bin("all_types", &truncated).unwrap();
+    let decoded = serializer.bin_to_json("all_types", &data).unwrap();
+    assert_eq!(decoded, truncated);
+}
+
+#[test]
+fn abi_serializer_errors() {
+    let abi = ABI::from_string(
+        r#"{
+            "version": "eosio::abi/1.1",
+            "structs": [
+                {"name": "type1", "base": "", "fields": [{"name": "foo", "type": "type2?"}]},
+                {"name": "type2", "base": "", "fields": [{"name": "bar", "type": "type3[]"}]},
+                {"name": "type3", "base": "", "fields": [{"name": "baz", "type": "int8"}]}
+            ]
+        }"#,
     )
-    const decoded = Serializer.decode({data, type: 'all_types', abi})
-    assert.deepStrictEqual(JSON.parse(JSON.stringify(decoded)), object)
-})
+    .unwrap();
+    let serializer = AbiSerializer::new(abi);
+
+    let object = json!({"foo": {"bar": [{"baz": "not int"}]}});
+    let err = serializer.json_to_bin("type1", &object).unwrap_err();
+    assert_eq!(err.path_string(), "$.foo.bar[0].baz");
+
+    let data = hex_to_bytes("0107010203040506");
+    let err = serializer.bin_to_json("type1", &data).unwrap_err();
+    assert_eq!(err.path_string(), "$.foo.bar[6].baz");
+
+    let err = serializer.json_to_bin("type2", &json!({})).unwrap_err();
+    assert_eq!(err.path_string(), "$.bar");
+
+    let err = serializer.json_to_bin("santa", &json!("foo")).unwrap_err();
+    assert_eq!(err.message, "Unknown type: santa");
+}
+
+/*
 
 test('coder metadata', function () {
     @TypeAlias('endian_int64')
@@ -1239,7 +1346,7 @@ fn abi_def() {
     let data_bytes = hex_to_bytes(abi_hex.as_str());
     let mut decoder = Decoder::new(data_bytes.as_slice());
     let mut abi_decoded = ABI::default();
-    decoder.unpack(&mut abi_decoded);
+    decoder.unpack(&mut abi_decoded).unwrap();
 
     assert_eq!(abi.types, abi_decoded.types);
     assert_eq!(abi.structs, abi_decoded.structs);
@@ -1397,3 +1504,921 @@ fn abi_def() {
        assert.equal(decoded.action_results[0].result_type, 'Result')
    })
 */
+
+#[test]
+fn serde_binary_format() {
+    use antelope::serializer::{from_bytes, to_bytes};
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, StructPacker)]
+    struct Inner {
+        flag: bool,
+        names: Vec<Name>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, EnumPacker)]
+    enum Choice {
+        Number(u64),
+        Text(String),
+        Inner(Inner),
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, StructPacker)]
+    struct Outer {
+        id: i32,
+        amount: i128,
+        ratio: f64,
+        label: String,
+        inner: Option<Inner>,
+        nested: Vec<Vec<u16>>,
+        choice: Choice,
+        memo: BinaryExtension<String>,
+    }
+
+    let value = Outer {
+        id: -5,
+        amount: 1 << 100,
+        ratio: 0.5,
+        label: String::from("label"),
+        inner: Some(Inner {
+            flag: true,
+            names: vec![name!("alice"), name!("bob")],
+        }),
+        nested: vec![vec![1, 2], vec![]],
+        choice: Choice::Text(String::from("text")),
+        memo: BinaryExtension::new(Some(String::from("memo"))),
+    };
+    let data = to_bytes(&value).unwrap();
+    assert_eq!(data, Encoder::pack(&value));
+    assert_eq!(from_bytes::<Outer>(&data).unwrap(), value);
+
+    // an absent binary extension is left out
+    let value = Outer {
+        choice: Choice::Number(7),
+        ..Default::default()
+    };
+    let data = to_bytes(&value).unwrap();
+    assert_eq!(data, Encoder::pack(&value));
+    assert_eq!(from_bytes::<Outer>(&data).unwrap(), value);
+
+    // tuples, fixed size arrays and maps match their Packer impls
+    let array = [1u8, 2, 3, 4];
+    let data = to_bytes(&array).unwrap();
+    assert_eq!(data, vec![1, 2, 3, 4]);
+    assert_eq!(data, Encoder::pack(&array));
+    assert_eq!(from_bytes::<[u8; 4]>(&data).unwrap(), array);
+    let nested = [[1u16, 2], [3, 4]];
+    assert_eq!(to_bytes(&nested).unwrap(), Encoder::pack(&nested));
+
+    let tuple = (1u8, String::from("a"), (true, 2u32));
+    let data = to_bytes(&tuple).unwrap();
+    assert_eq!(bytes_to_hex(&data), "0101610102000000");
+    assert_eq!(data, Encoder::pack(&tuple));
+    assert_eq!(
+        from_bytes::<(u8, String, (bool, u32))>(&data).unwrap(),
+        tuple
+    );
+
+    let map = BTreeMap::from([(name!("alice"), 1u8), (name!("bob"), 2u8)]);
+    let data = to_bytes(&map).unwrap();
+    // maps are written like a vector of pairs
+    let expected = [
+        vec![2],
+        Encoder::pack(&name!("alice")),
+        vec![1],
+        Encoder::pack(&name!("bob")),
+        vec![2],
+    ]
+    .concat();
+    assert_eq!(data, expected);
+    assert_eq!(data, Encoder::pack(&map));
+    assert_eq!(from_bytes::<BTreeMap<Name, u8>>(&data).unwrap(), map);
+    let hash_map: HashMap<String, Vec<u8>> = HashMap::from([(String::from("key"), vec![1, 2])]);
+    let data = to_bytes(&hash_map).unwrap();
+    assert_eq!(bytes_to_hex(&data), "01036b6579020102");
+    assert_eq!(
+        from_bytes::<HashMap<String, Vec<u8>>>(&data).unwrap(),
+        hash_map
+    );
+    // hash map entries are sorted by key like their Packer impl writes them
+    let hash_map: HashMap<Name, u8> = [
+        "alice",
+        "bob",
+        "carol",
+        "dave",
+        "eosio",
+        "eosio.token",
+        "zed",
+        "a",
+        "a1",
+        "b",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, s)| (Name::new(s), i as u8))
+    .collect();
+    let data = to_bytes(&hash_map).unwrap();
+    assert_eq!(data, Encoder::pack(&hash_map));
+    assert_eq!(from_bytes::<HashMap<Name, u8>>(&data).unwrap(), hash_map);
+    let hash_map: HashMap<String, u64> = HashMap::from([
+        ("b".into(), 1),
+        ("aa".into(), 2),
+        ("a".into(), 3),
+        ("".into(), 4),
+    ]);
+    let data = to_bytes(&hash_map).unwrap();
+    assert_eq!(data, Encoder::pack(&hash_map));
+    assert_eq!(data[..3], [4, 0, 4]);
+
+    // borrowed strings and bytes point into the input
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Borrowed<'a> {
+        text: &'a str,
+        #[serde(with = "serde_bytes_borrowed")]
+        raw: &'a [u8],
+    }
+    mod serde_bytes_borrowed {
+        pub fn deserialize<'de, D: serde::Deserializer<'de>>(d: D) -> Result<&'de [u8], D::Error> {
+            serde::Deserialize::deserialize(d)
+        }
+    }
+    let data = hex_to_bytes("026869020102");
+    let borrowed: Borrowed = from_bytes(&data).unwrap();
+    assert_eq!(borrowed.text, "hi");
+    assert_eq!(borrowed.raw, &[1, 2]);
+}
+
+#[test]
+fn serde_chain_types() {
+    use antelope::chain::{
+        action::{Action, PermissionLevel},
+        asset::Asset,
+        authority::{Authority, KeyWeight},
+        public_key::PublicKey,
+        time::TimePointSec,
+        transaction::{SignedTransaction, Transaction, TransactionHeader},
+        varint::VarUint32,
+    };
+    use antelope::serializer::{from_bytes, to_bytes};
+
+    let key = PublicKey::new_from_str("PUB_K1_6RrvujLQN1x5Tacbep1KAk8zzKpSThAQXBCKYFfGUYeACcSRFs")
+        .unwrap();
+    let mut authority = Authority::new(1);
+    authority.keys.push(KeyWeight {
+        key: key.clone(),
+        weight: 1,
+    });
+    let data = to_bytes(&authority).unwrap();
+    assert_eq!(data, Encoder::pack(&authority));
+    let decoded: Authority = from_bytes(&data).unwrap();
+    assert_eq!(decoded.keys[0].key, key);
+
+    let action = Action {
+        account: name!("eosio.token"),
+        name: name!("transfer"),
+        authorization: vec![PermissionLevel::new(name!("alice"), name!("active"))],
+        data: Encoder::pack(&Asset::from_string("1.0000 EOS")),
+    };
+    let transaction = SignedTransaction {
+        transaction: Transaction {
+            header: TransactionHeader {
+                expiration: TimePointSec::new(1_700_000_000),
+                ref_block_num: 1,
+                ref_block_prefix: 2,
+                max_net_usage_words: VarUint32::new(300),
+                max_cpu_usage_ms: 0,
+                delay_sec: VarUint32::new(0),
+            },
+            context_free_actions: vec![],
+            actions: vec![action.clone()],
+            extension: vec![],
+        },
+        signatures: vec![Signature::from_string(
+            "SIG_K1_KdNTcLLSyzUFC4AdMxEDn58X8ZN368euanvet4jucUdSPXvLkgsG32tpcqVvnDR9Xv1f7HsTm6kocjeZzFGvUSc2yCbdEA",
+        )
+        .unwrap()],
+        context_free_data: vec![vec![1, 2, 3]],
+    };
+    let data = to_bytes(&transaction).unwrap();
+    assert_eq!(data, Encoder::pack(&transaction));
+    let decoded: SignedTransaction = from_bytes(&data).unwrap();
+    assert!(decoded == transaction);
+    assert_eq!(
+        from_bytes::<Action>(&Encoder::pack(&action)).unwrap(),
+        action
+    );
+
+    // JSON uses the nodeos form instead
+    let json = serde_json::to_value(VarUint32::new(300)).unwrap();
+    assert_eq!(json, json!(300));
+}
+
+#[test]
+fn serde_binary_errors() {
+    use antelope::serializer::{from_bytes, to_bytes};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Pair {
+        first: u32,
+        second: String,
+    }
+
+    let data = to_bytes(&Pair {
+        first: 1,
+        second: String::from("abc"),
+    })
+    .unwrap();
+
+    let err = from_bytes::<Pair>(&data[..6]).unwrap_err();
+    assert_eq!(err.path, vec!["second"]);
+    assert_eq!(err.offset, 5);
+    assert_eq!(
+        err.kind,
+        DecodeErrorKind::UnexpectedEof {
+            needed: 3,
+            remaining: 1
+        }
+    );
+
+    let err = from_bytes::<Pair>(&[data.as_slice(), &[0]].concat()).unwrap_err();
+    assert_eq!(err.offset, data.len());
+
+    let err = from_bytes::<Option<u8>>(&[2, 0]).unwrap_err();
+    assert_eq!(err.kind, DecodeErrorKind::BadVariant(2));
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Choice {
+        A(u8),
+        B(u8),
+    }
+    let err = from_bytes::<Choice>(&[5, 0]).unwrap_err();
+    assert_eq!(err.kind, DecodeErrorKind::BadVariant(5));
+    assert_eq!(from_bytes::<Choice>(&[0, 9]).unwrap(), Choice::A(9));
+    assert_eq!(from_bytes::<Choice>(&[1, 9]).unwrap(), Choice::B(9));
+
+    let err = from_bytes::<serde_json::Value>(&[0]).unwrap_err();
+    assert!(err.to_string().contains("not self-describing"));
+}
+
+#[test]
+fn unpack_borrowed() {
+    use antelope::chain::{asset::Asset, Packer};
+    use antelope::serializer::UnpackBorrowed;
+
+    #[derive(Default, StructPacker)]
+    struct OwnedTrace {
+        receiver: Name,
+        quantity: Asset,
+        console: String,
+        data: Vec<u8>,
+        tags: Vec<String>,
+        memo: Option<String>,
+    }
+
+    #[derive(Debug, UnpackBorrowed)]
+    struct Trace<'a> {
+        receiver: Name,
+        quantity: Asset,
+        console: &'a str,
+        data: &'a [u8],
+        tags: Vec<&'a str>,
+        memo: Option<&'a str>,
+    }
+
+    let owned = OwnedTrace {
+        receiver: name!("alice"),
+        quantity: Asset::from_string("1.0000 EOS"),
+        console: String::from("hello"),
+        data: vec![1, 2, 3],
+        tags: vec![String::from("a"), String::from("bc")],
+        memo: Some(String::from("memo")),
+    };
+    let data = Encoder::pack(&owned);
+
+    let mut decoder = Decoder::new(&data);
+    let trace: Trace = decoder.unpack_borrowed().unwrap();
+    assert_eq!(decoder.remaining(), 0);
+    assert_eq!(trace.receiver, owned.receiver);
+    assert_eq!(trace.quantity, owned.quantity);
+    assert_eq!(trace.console, owned.console);
+    assert_eq!(trace.data, owned.data);
+    assert_eq!(trace.tags, owned.tags);
+    assert_eq!(trace.memo, owned.memo.as_deref());
+
+    // slices point into the input instead of being copied
+    let range = data.as_ptr_range();
+    assert!(range.contains(&trace.console.as_ptr()));
+    assert!(range.contains(&trace.data.as_ptr()));
+    assert!(range.contains(&trace.tags[1].as_ptr()));
+
+    // vectors of borrowed structs, generics and enums
+    #[derive(UnpackBorrowed)]
+    struct Pair<T>(T, u8);
+
+    #[derive(Debug, UnpackBorrowed)]
+    enum Payload<'a> {
+        Text(&'a str),
+        Traces(Vec<Trace<'a>>),
+    }
+
+    let pair: Pair<&[u8]> = Decoder::new(&[2, 5, 6, 7]).unpack_borrowed().unwrap();
+    assert_eq!(pair.0, [5, 6]);
+    assert_eq!(pair.1, 7);
+
+    let data = [vec![1u8, 2], Encoder::pack(&owned), Encoder::pack(&owned)].concat();
+    let (payload, size) = Payload::unpack_borrowed(&data).unwrap();
+    assert_eq!(size, data.len());
+    match payload {
+        Payload::Traces(traces) => {
+            assert_eq!(traces.len(), 2);
+            assert_eq!(traces[1].console, "hello");
+        }
+        Payload::Text(_) => panic!("expected traces"),
+    }
+
+    let (payload, _) = Payload::unpack_borrowed(&[0, 2, b'h', b'i']).unwrap();
+    assert!(matches!(payload, Payload::Text("hi")));
+
+    let err = Payload::unpack_borrowed(&[2]).unwrap_err();
+    assert_eq!(err.kind, DecodeErrorKind::BadVariant(2));
+
+    // unit and named field variants, on the same enum as EnumPacker
+    #[derive(Debug, Clone, PartialEq, EnumPacker, UnpackBorrowed)]
+    enum Action {
+        Noop,
+        Transfer { from: Name, to: Name, amount: u64 },
+        Pair(u8, u16),
+    }
+
+    for action in [
+        Action::Noop,
+        Action::Transfer {
+            from: name!("alice"),
+            to: name!("bob"),
+            amount: 5,
+        },
+        Action::Pair(1, 2),
+    ] {
+        let data = Encoder::pack(&action);
+        assert_eq!(
+            Action::unpack_borrowed(&data).unwrap(),
+            (action.clone(), data.len())
+        );
+    }
+
+    #[derive(Debug, UnpackBorrowed)]
+    enum Note<'a> {
+        Empty,
+        Memo { from: Name, text: &'a str },
+    }
+
+    let data = [
+        vec![1u8],
+        Encoder::pack(&name!("alice")),
+        vec![2, b'h', b'i'],
+    ]
+    .concat();
+    let (note, _) = Note::unpack_borrowed(&data).unwrap();
+    match note {
+        Note::Memo { from, text } => {
+            assert_eq!(from, name!("alice"));
+            assert_eq!(text, "hi");
+        }
+        Note::Empty => panic!("expected a memo"),
+    }
+    assert!(matches!(
+        Note::unpack_borrowed(&[0]).unwrap(),
+        (Note::Empty, 1)
+    ));
+
+    let err = Note::unpack_borrowed(&data[..9]).unwrap_err();
+    assert_eq!(err.path, vec!["Memo", "text"]);
+
+    // errors carry the field path and offset like `Packer::unpack`
+    let mut data = Encoder::pack(&owned);
+    let console = 8 + owned.quantity.size();
+    data[console + 2] = 0xff;
+    let err = Decoder::new(&data).unpack_borrowed::<Trace>().unwrap_err();
+    assert_eq!(err.path, vec!["console"]);
+    assert_eq!(err.offset, console);
+    assert!(matches!(err.kind, DecodeErrorKind::Invalid(_)));
+
+    let data = Encoder::pack(&owned);
+    let err = Decoder::new(&data[..data.len() - 2])
+        .unpack_borrowed::<Trace>()
+        .unwrap_err();
+    assert_eq!(err.path, vec!["memo"]);
+    assert_eq!(
+        err.kind,
+        DecodeErrorKind::UnexpectedEof {
+            needed: 5,
+            remaining: 3
+        }
+    );
+}
+
+#[test]
+fn stream_encoder_decoder() {
+    use antelope::serializer::{StreamDecoder, StreamEncoder, StreamError};
+    use std::io::Read;
+
+    /// Hands out at most one byte per read.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    // a value larger than a single read chunk
+    let large: Vec<String> = (0..5000).map(|i| format!("value {i}")).collect();
+
+    let mut encoder = StreamEncoder::new(Vec::new());
+    for i in 0..100u64 {
+        encoder.pack(&i).unwrap();
+        encoder.pack(&Some(name!("alice"))).unwrap();
+    }
+    encoder.pack(&large).unwrap();
+    let expected = Encoder::pack(&large).len() + 100 * (8 + 1 + 8);
+    assert_eq!(encoder.get_size(), expected);
+    let data = encoder.into_inner();
+    assert_eq!(data.len(), expected);
+
+    let check = |reader: Box<dyn Read + '_>| {
+        let mut decoder = StreamDecoder::new(reader);
+        for i in 0..100u64 {
+            let mut value = 0u64;
+            let mut owner: Option<Name> = None;
+            decoder.unpack(&mut value).unwrap();
+            decoder.unpack(&mut owner).unwrap();
+            assert_eq!(value, i);
+            assert_eq!(owner, Some(name!("alice")));
+        }
+        assert!(!decoder.is_eof().unwrap());
+        let mut value: Vec<String> = vec![];
+        decoder.unpack(&mut value).unwrap();
+        assert_eq!(value, large);
+        assert_eq!(decoder.get_pos(), data.len());
+        assert!(decoder.is_eof().unwrap());
+    };
+    check(Box::new(data.as_slice()));
+    check(Box::new(Trickle(&data)));
+
+    let mut decoder = StreamDecoder::from_buf(bytes::Bytes::from(data.clone()));
+    let mut value = 0u64;
+    decoder.unpack(&mut value).unwrap();
+    assert_eq!(decoder.get_pos(), 8);
+
+    // a truncated stream reports the offset from the start of the stream
+    let truncated = &data[..data.len() - 3];
+    let mut decoder = StreamDecoder::new(Trickle(truncated));
+    let mut value = 0u64;
+    let mut owner: Option<Name> = None;
+    for _ in 0..100 {
+        decoder.unpack(&mut value).unwrap();
+        decoder.unpack(&mut owner).unwrap();
+    }
+    let mut value = vec![String::from("untouched")];
+    match decoder.unpack(&mut value).unwrap_err() {
+        StreamError::Decode(e) => {
+            assert_eq!(e.path, vec!["4999"]);
+            assert!(e.offset > 100 * 17);
+            assert!(matches!(e.kind, DecodeErrorKind::UnexpectedEof { .. }));
+        }
+        StreamError::Io(e) => panic!("unexpected io error {e}"),
+    }
+    assert_eq!(value, vec![String::from("untouched")]);
+
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("broken pipe"))
+        }
+    }
+
+    let err = StreamDecoder::new(Failing).unpack(&mut 0u8).unwrap_err();
+    assert!(matches!(err, StreamError::Io(_)));
+    assert_eq!(err.to_string(), "failed to read: broken pipe");
+
+    // a read ending before a trailing optional value does not make it absent
+    #[derive(Debug, Default, PartialEq, StructPacker)]
+    struct Trailing {
+        value: u8,
+        extension: BinaryExtension<u8>,
+    }
+
+    let mut encoder = StreamEncoder::new(Vec::new());
+    for i in 0..3u8 {
+        encoder.pack(&(i, Some(9u8))).unwrap();
+    }
+    encoder
+        .pack(&Trailing {
+            value: 1,
+            extension: BinaryExtension::new(Some(2)),
+        })
+        .unwrap();
+    let data = encoder.into_inner();
+    let mut decoder = StreamDecoder::new(Trickle(&data));
+    for i in 0..3u8 {
+        let mut value: (u8, Option<u8>) = (0, None);
+        decoder.unpack_until_eof(&mut value).unwrap();
+        assert_eq!(value, (i, Some(9)));
+    }
+    let mut value = Trailing::default();
+    decoder.unpack_until_eof(&mut value).unwrap();
+    assert_eq!(value.extension.value(), Some(&2));
+    assert!(decoder.is_eof().unwrap());
+
+    // a value is returned without waiting for the next one to start
+    let data = Encoder::pack(&(1u8, Some(2u8)));
+    let mut decoder = StreamDecoder::new(Read::chain(&data[..], Failing));
+    let mut value: (u8, Option<u8>) = (0, None);
+    decoder.unpack(&mut value).unwrap();
+    assert_eq!(value, (1, Some(2)));
+    let mut decoder = StreamDecoder::new(Read::chain(&data[..], Failing));
+    let err = decoder.unpack_until_eof(&mut value).unwrap_err();
+    assert!(matches!(err, StreamError::Io(_)));
+
+    // empty trailing data is an absent optional value
+    let mut value: Option<u8> = Some(1);
+    assert_eq!(Decoder::new(&[]).unpack(&mut value).unwrap(), 0);
+    assert_eq!(value, None);
+}
+
+#[test]
+fn single_pass_packing() {
+    use antelope::chain::Packer;
+    use std::cell::Cell;
+
+    // size() is optional and packing never calls it
+    #[derive(Default)]
+    struct Counted {
+        value: u32,
+        sized: Cell<usize>,
+    }
+
+    impl Packer for Counted {
+        fn size(&self) -> usize {
+            self.sized.set(self.sized.get() + 1);
+            4
+        }
+
+        fn pack(&self, enc: &mut Encoder) -> usize {
+            self.value.pack(enc)
+        }
+
+        fn unpack(&mut self, data: &[u8]) -> Result<usize, antelope::serializer::DecodeError> {
+            self.value.unpack(data)
+        }
+    }
+
+    #[derive(Default, StructPacker)]
+    struct Outer {
+        items: Vec<Counted>,
+        label: String,
+    }
+
+    let outer = Outer {
+        items: (0..1000)
+            .map(|i| Counted {
+                value: i,
+                ..Default::default()
+            })
+            .collect(),
+        label: String::from("outer"),
+    };
+    let data = Encoder::pack(&outer);
+    assert_eq!(data.len(), 2 + 4000 + 6);
+    assert!(outer.items.iter().all(|i| i.sized.get() == 0));
+
+    struct Unsized(u16);
+
+    impl Packer for Unsized {
+        fn pack(&self, enc: &mut Encoder) -> usize {
+            self.0.pack(enc)
+        }
+
+        fn unpack(&mut self, data: &[u8]) -> Result<usize, antelope::serializer::DecodeError> {
+            self.0.unpack(data)
+        }
+    }
+    assert_eq!(Unsized(7).size(), 2);
+
+    // the length prefix counts elements, not bytes
+    let values = vec![0u64; 20];
+    assert_eq!(values.size(), 1 + 160);
+    assert_eq!(values.size(), Encoder::pack(&values).len());
+    let empty: Vec<u64> = vec![];
+    assert_eq!(empty.size(), 1);
+
+    // an encoder can be reused without reallocating
+    let mut encoder = Encoder::default();
+    outer.pack(&mut encoder);
+    let first = encoder.get_bytes().to_vec();
+    encoder.clear();
+    outer.pack(&mut encoder);
+    assert_eq!(encoder.into_bytes(), first);
+}
+
+#[test]
+fn packer_attributes() {
+    use antelope::chain::{asset::Asset, Packer};
+    use antelope::serializer::UnpackBorrowed;
+
+    #[derive(Debug, Default, PartialEq, StructPacker)]
+    struct Transfer {
+        from: Name,
+        #[packer(skip)]
+        cached: u64,
+        quantity: Asset,
+        memo: BinaryExtension<String>,
+        #[packer(extension)]
+        tag: Option<u32>,
+    }
+
+    let mut value = Transfer {
+        from: name!("alice"),
+        cached: 7,
+        quantity: Asset::from_string("1.0000 EOS"),
+        memo: BinaryExtension::new(None),
+        tag: None,
+    };
+    let base = Encoder::pack(&value);
+    assert_eq!(base.len(), 8 + 16);
+    assert_eq!(value.size(), base.len());
+
+    // skipped fields keep their value, missing extensions are left out
+    let mut decoded = Transfer {
+        tag: Some(1),
+        ..Default::default()
+    };
+    decoded.unpack(&base).unwrap();
+    assert_eq!(decoded.cached, 0);
+    assert_eq!(decoded.tag, None);
+    assert_eq!(decoded.from, value.from);
+
+    value.memo = BinaryExtension::new(Some(String::from("hi")));
+    value.tag = Some(9);
+    let data = Encoder::pack(&value);
+    assert_eq!(data, [base, vec![2, b'h', b'i', 9, 0, 0, 0]].concat());
+    let data_with_extensions = data.clone();
+    let mut decoded = Transfer::default();
+    decoded.unpack(&data).unwrap();
+    value.cached = 0;
+    assert_eq!(decoded, value);
+
+    #[derive(Debug, Default, PartialEq, StructPacker)]
+    struct Empty;
+    assert_eq!(Encoder::pack(&Empty).len(), 0);
+
+    #[derive(Debug, Default, PartialEq, StructPacker)]
+    struct Pair<T>(T, u8);
+    let pair = Pair(String::from("a"), 2);
+    let data = Encoder::pack(&pair);
+    assert_eq!(data, vec![1, b'a', 2]);
+    let mut decoded = Pair::<String>::default();
+    decoded.unpack(&data).unwrap();
+    assert_eq!(decoded, pair);
+
+    #[derive(Debug, PartialEq, EnumPacker)]
+    enum Event<T> {
+        Start,
+        #[packer(index = 5)]
+        Value(T),
+        Move {
+            from: Name,
+            to: Name,
+        },
+        #[packer(default)]
+        Pair(u8, u16),
+    }
+
+    assert_eq!(Event::<u32>::default(), Event::Pair(0, 0));
+    let cases = [
+        (Event::Start, vec![0]),
+        (Event::Value(3u32), vec![5, 3, 0, 0, 0]),
+        (
+            Event::Move {
+                from: name!("alice"),
+                to: name!("bob"),
+            },
+            [
+                vec![6],
+                Encoder::pack(&name!("alice")),
+                Encoder::pack(&name!("bob")),
+            ]
+            .concat(),
+        ),
+        (Event::Pair(1, 2), vec![7, 1, 2, 0]),
+    ];
+    for (event, expected) in cases {
+        let data = Encoder::pack(&event);
+        assert_eq!(data, expected);
+        assert_eq!(event.size(), data.len());
+        let mut decoded = Event::default();
+        assert_eq!(decoded.unpack(&data).unwrap(), data.len());
+        assert_eq!(decoded, event);
+    }
+
+    let mut decoded = Event::<u32>::default();
+    let err = decoded.unpack(&[1]).unwrap_err();
+    assert_eq!(err.kind, DecodeErrorKind::BadVariant(1));
+    let err = decoded.unpack(&[6, 0, 0]).unwrap_err();
+    assert_eq!(err.path, vec!["Move", "from"]);
+
+    // borrowed decoding reads the same attributes
+    #[derive(Debug, PartialEq, UnpackBorrowed)]
+    struct BorrowedTransfer {
+        from: Name,
+        #[packer(skip)]
+        cached: u64,
+        quantity: Asset,
+        memo: BinaryExtension<String>,
+        #[packer(extension)]
+        tag: Option<u32>,
+    }
+
+    let (borrowed, size) = BorrowedTransfer::unpack_borrowed(&data_with_extensions).unwrap();
+    assert_eq!(size, data_with_extensions.len());
+    assert_eq!(
+        borrowed,
+        BorrowedTransfer {
+            from: name!("alice"),
+            cached: 0,
+            quantity: Asset::from_string("1.0000 EOS"),
+            memo: BinaryExtension::new(Some(String::from("hi"))),
+            tag: Some(9),
+        }
+    );
+    let (borrowed, _) = BorrowedTransfer::unpack_borrowed(&data_with_extensions[..24]).unwrap();
+    assert_eq!(borrowed.memo.value(), None);
+    assert_eq!(borrowed.tag, None);
+
+    #[derive(Debug, PartialEq, UnpackBorrowed)]
+    enum BorrowedEvent<'a> {
+        Text(&'a str),
+        #[packer(index = 5)]
+        Value(u32),
+        Other(u8),
+    }
+
+    let (event, _) = BorrowedEvent::unpack_borrowed(&[5, 3, 0, 0, 0]).unwrap();
+    assert_eq!(event, BorrowedEvent::Value(3));
+    let (event, _) = BorrowedEvent::unpack_borrowed(&[6, 1]).unwrap();
+    assert_eq!(event, BorrowedEvent::Other(1));
+    let err = BorrowedEvent::unpack_borrowed(&[1, 0]).unwrap_err();
+    assert_eq!(err.kind, DecodeErrorKind::BadVariant(1));
+}
+
+#[test]
+fn collection_packers() {
+    use antelope::chain::{asset::Asset, varint::VarInt32, Packer};
+    use std::collections::{BTreeMap, HashMap};
+
+    fn round_trip<T: Packer + Default + PartialEq + std::fmt::Debug>(value: T, hex: &str) {
+        let data = Encoder::pack(&value);
+        assert_eq!(bytes_to_hex(&data), hex);
+        assert_eq!(value.size(), data.len());
+        let mut decoded = T::default();
+        assert_eq!(decoded.unpack(&data).unwrap(), data.len());
+        assert_eq!(decoded, value);
+    }
+
+    // pair<name, asset>
+    round_trip(
+        (name!("alice"), Asset::from_string("1.0000 EOS")),
+        "0000000000855c34102700000000000004454f5300000000",
+    );
+    round_trip((1u8, -2i16, VarInt32::new(-3)), "01feff05");
+
+    // std::array<T, N> has no length prefix
+    round_trip([1u16, 2, 3], "010002000300");
+    round_trip([[0u8; 2]; 2], "00000000");
+    let mut array = [0u16; 3];
+    let err = array.unpack(&[1, 0, 2, 0, 3]).unwrap_err();
+    assert!(matches!(err.kind, DecodeErrorKind::UnexpectedEof { .. }));
+    assert_eq!(err.path, vec!["2"]);
+
+    // map<string, uint64> is a vector of pairs
+    round_trip(
+        BTreeMap::from([(String::from("b"), 2u64), (String::from("a"), 1u64)]),
+        "020161010000000000000001620200000000000000",
+    );
+    round_trip(
+        HashMap::from([(name!("bob"), vec![1u8, 2])]),
+        "010000000000000e3d020102",
+    );
+    let mut map = BTreeMap::<u8, u8>::new();
+    let err = map.unpack(&[2, 1, 1, 2]).unwrap_err();
+    assert_eq!(err.path, vec!["1", "1"]);
+
+    round_trip(-1i128, "ffffffffffffffffffffffffffffffff");
+    round_trip(u128::MAX - 1, "feffffffffffffffffffffffffffffff");
+}
+
+#[test]
+fn strict_decoding() {
+    use antelope::chain::{action::Action, varint::VarUint32};
+    use antelope::serializer::decode_exact;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, Default, PartialEq, StructPacker)]
+    struct Payload {
+        flag: bool,
+        items: Vec<u8>,
+        count: VarUint32,
+    }
+
+    let value = Payload {
+        flag: true,
+        items: vec![7],
+        count: VarUint32::new(300),
+    };
+    let data = Encoder::pack(&value);
+    assert_eq!(data, vec![1, 1, 7, 0xac, 0x02]);
+    assert_eq!(decode_exact::<Payload>(&data).unwrap(), value);
+
+    // trailing bytes
+    let err = decode_exact::<Payload>(&[data.as_slice(), &[0]].concat()).unwrap_err();
+    assert_eq!(err.offset, data.len());
+    assert_eq!(
+        err.kind,
+        DecodeErrorKind::Invalid(String::from("1 trailing bytes"))
+    );
+
+    // a non-minimal length prefix and varuint32
+    let long_prefix = [1, 0x81, 0x00, 7, 0xac, 0x02];
+    let err = decode_exact::<Payload>(&long_prefix).unwrap_err();
+    assert_eq!(err.offset, 1);
+    assert_eq!(
+        err.kind,
+        DecodeErrorKind::Invalid(String::from("non-canonical encoding"))
+    );
+    let long_count = [1, 1, 7, 0xac, 0x82, 0x00];
+    assert_eq!(decode_exact::<Payload>(&long_count).unwrap_err().offset, 4);
+
+    // the lenient decoder accepts both
+    let mut decoded = Payload::default();
+    Decoder::new(&long_prefix).unpack(&mut decoded).unwrap();
+    assert_eq!(decoded, value);
+
+    // bools must be 0 or 1
+    let err = decode_exact::<Payload>(&[2, 1, 7, 0xac, 0x02]).unwrap_err();
+    assert!(matches!(err.kind, DecodeErrorKind::Invalid(_)));
+
+    // offsets are relative to the strict decoder's buffer
+    let action = Action::default();
+    let packed = Encoder::pack(&action);
+    let mut dec = Decoder::new_strict(&packed);
+    assert!(dec.is_strict());
+    let mut decoded = Action::default();
+    dec.unpack(&mut decoded).unwrap();
+    dec.finish().unwrap();
+    let mut dec = Decoder::new_strict(&long_prefix);
+    dec.unpack(&mut false).unwrap();
+    let err = dec.unpack(&mut Vec::<u8>::new()).unwrap_err();
+    assert_eq!(err.offset, 1);
+
+    // borrowed values cannot be checked, so strict decoders refuse them
+    let long_str = [0x81, 0x00, b'a'];
+    assert_eq!(
+        Decoder::new(&long_str).unpack_borrowed::<&str>().unwrap(),
+        "a"
+    );
+    let err = Decoder::new_strict(&long_str)
+        .unpack_borrowed::<&str>()
+        .unwrap_err();
+    assert!(matches!(err.kind, DecodeErrorKind::Invalid(_)));
+    assert!(Decoder::new_strict(&[1, 7])
+        .unpack_borrowed::<Vec<u8>>()
+        .is_err());
+
+    // maps pack in key order, whatever the iteration order of a HashMap
+    let map: HashMap<u64, String> = (0..32u64)
+        .map(|i| (i * 7919 % 101, i.to_string()))
+        .collect();
+    let data = Encoder::pack(&map);
+    let sorted: BTreeMap<u64, String> = map.clone().into_iter().collect();
+    assert_eq!(data, Encoder::pack(&sorted));
+    assert_eq!(decode_exact::<HashMap<u64, String>>(&data).unwrap(), map);
+    assert_eq!(
+        decode_exact::<BTreeMap<u64, String>>(&data).unwrap(),
+        sorted
+    );
+
+    // and entries out of key order are not canonical
+    let swapped = Encoder::pack(&vec![(2u64, 0u8), (1u64, 0u8)]);
+    assert!(decode_exact::<HashMap<u64, u8>>(&swapped).is_err());
+    let mut decoded = HashMap::<u64, u8>::new();
+    Decoder::new(&swapped).unpack(&mut decoded).unwrap();
+    assert_eq!(decoded.len(), 2);
+}
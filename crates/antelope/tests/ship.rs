@@ -36,6 +36,6 @@ fn traces() {
 
 fn decode<T: Packer + Default>(raw: &[u8]) -> T {
     let mut result = T::default();
-    result.unpack(raw);
+    result.unpack(raw).unwrap();
     result
 }
@@ -16,7 +16,6 @@ use antelope::{
         name::Name,
         private_key::PrivateKey,
         transaction::{SignedTransaction, Transaction},
-        Decoder, Encoder, Packer,
     },
     name,
 };
@@ -65,7 +64,7 @@ impl Provider for MockProvider {
 
 #[allow(dead_code)]
 pub fn make_mock_transaction(info: &GetInfoResponse, asset_to_transfer: Asset) -> Transaction {
-    let trx_header = info.get_transaction_header(90);
+    let trx_header = info.get_transaction_header(90).unwrap();
 
     #[derive(Clone, Eq, PartialEq, Default, StructPacker)]
     struct Transfer {
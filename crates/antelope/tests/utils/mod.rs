@@ -1,3 +1,6 @@
+// Each test binary pulls in this module but only uses part of it.
+#![allow(dead_code)]
+
 //pub mod client;
 //pub mod base58;
 //pub mod crypto;
@@ -3,9 +3,7 @@ use antelope::chain::name::Name;
 use antelope::chain::public_key::PublicKey;
 use antelope::chain::signature::Signature;
 use antelope::chain::varint::VarUint32;
-use antelope::chain::{Decoder, Encoder};
 use antelope::serializer::packer::Float128;
-use antelope::serializer::Packer;
 use antelope::{EnumPacker, StructPacker};
 use serde::{Deserialize, Serialize};
 use std::option::Option;
@@ -55,23 +53,9 @@ pub enum ActionReceipt {
     V0(ActionReceiptV0),
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, StructPacker)]
 pub struct GetStatusRequestV0;
 
-impl Packer for GetStatusRequestV0 {
-    fn size(&self) -> usize {
-        0
-    }
-
-    fn pack(&self, _enc: &mut Encoder) -> usize {
-        0
-    }
-
-    fn unpack(&mut self, _data: &[u8]) -> usize {
-        0
-    }
-}
-
 #[derive(Debug, Clone, Default, Serialize, Deserialize, StructPacker)]
 pub struct BlockPosition {
     pub block_num: u32,
@@ -0,0 +1,449 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use serde_json::Value;
+use syn::{Ident, LitStr};
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "box", "final", "try",
+];
+
+struct StructDef {
+    name: String,
+    base: String,
+    fields: Vec<(String, String)>,
+}
+
+struct AbiDef {
+    types: Vec<(String, String)>,
+    structs: Vec<StructDef>,
+    variants: Vec<(String, Vec<String>)>,
+    actions: Vec<(String, String)>,
+    tables: Vec<(String, String)>,
+}
+
+pub fn expand(path: LitStr) -> syn::Result<TokenStream> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = PathBuf::from(manifest_dir).join(path.value());
+    let json = std::fs::read_to_string(&full_path).map_err(|e| {
+        syn::Error::new(
+            path.span(),
+            format!("failed to read {}: {e}", full_path.display()),
+        )
+    })?;
+    let abi = parse_abi(&json).map_err(|e| syn::Error::new(path.span(), e))?;
+    let generator = Generator::new(&abi);
+    let items = generator
+        .generate()
+        .map_err(|e| syn::Error::new(path.span(), e))?;
+
+    // Makes cargo rebuild the caller when the ABI file changes
+    let full_path = full_path.to_string_lossy().to_string();
+    Ok(quote! {
+        const _: &str = include_str!(#full_path);
+        #items
+    })
+}
+
+fn parse_abi(json: &str) -> Result<AbiDef, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("invalid ABI: {e}"))?;
+    let list = |key: &str| -> Vec<Value> {
+        value
+            .get(key)
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+    };
+    let string = |v: &Value, key: &str| -> Result<String, String> {
+        v.get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| format!("invalid ABI: missing {key} in {v}"))
+    };
+
+    let mut abi = AbiDef {
+        types: Vec::new(),
+        structs: Vec::new(),
+        variants: Vec::new(),
+        actions: Vec::new(),
+        tables: Vec::new(),
+    };
+    for t in list("types") {
+        abi.types
+            .push((string(&t, "new_type_name")?, string(&t, "type")?));
+    }
+    for s in list("structs") {
+        let mut fields = Vec::new();
+        for f in s
+            .get("fields")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            fields.push((string(f, "name")?, string(f, "type")?));
+        }
+        abi.structs.push(StructDef {
+            name: string(&s, "name")?,
+            base: string(&s, "base").unwrap_or_default(),
+            fields,
+        });
+    }
+    for v in list("variants") {
+        let types = v
+            .get("types")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect();
+        abi.variants.push((string(&v, "name")?, types));
+    }
+    for a in list("actions") {
+        abi.actions.push((string(&a, "name")?, string(&a, "type")?));
+    }
+    for t in list("tables") {
+        abi.tables.push((string(&t, "name")?, string(&t, "type")?));
+    }
+    Ok(abi)
+}
+
+struct Generator<'a> {
+    abi: &'a AbiDef,
+    typedefs: HashMap<&'a str, &'a str>,
+    structs: HashMap<&'a str, &'a StructDef>,
+    variants: HashMap<&'a str, &'a Vec<String>>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(abi: &'a AbiDef) -> Self {
+        Self {
+            abi,
+            typedefs: abi
+                .types
+                .iter()
+                .map(|(n, t)| (n.as_str(), t.as_str()))
+                .collect(),
+            structs: abi.structs.iter().map(|s| (s.name.as_str(), s)).collect(),
+            variants: abi.variants.iter().map(|(n, t)| (n.as_str(), t)).collect(),
+        }
+    }
+
+    fn generate(&self) -> Result<TokenStream, String> {
+        let mut items = TokenStream::new();
+
+        for (name, ty) in &self.abi.types {
+            let ident = type_ident(name);
+            let target = self.type_tokens(ty, None)?;
+            items.extend(quote! {
+                pub type #ident = #target;
+            });
+        }
+
+        for s in &self.abi.structs {
+            let ident = type_ident(&s.name);
+            let mut fields = Vec::new();
+            for (name, ty) in self.all_fields(s, 0)? {
+                let field = field_ident(name);
+                let ty = self.type_tokens(ty, Some(&s.name))?;
+                fields.push(quote! { pub #field: #ty });
+            }
+            items.extend(quote! {
+                #[derive(Debug, Clone, Default, PartialEq, ::antelope::StructPacker)]
+                pub struct #ident {
+                    #(#fields),*
+                }
+            });
+        }
+
+        for (name, types) in &self.abi.variants {
+            let ident = type_ident(name);
+            let mut variants = Vec::new();
+            for ty in types.iter() {
+                let variant = type_ident(&ty.replace("[]", "_array").replace('?', "_optional"));
+                let ty = self.type_tokens(ty, Some(name))?;
+                variants.push(quote! { #variant(#ty) });
+            }
+            items.extend(quote! {
+                #[derive(Debug, Clone, PartialEq, ::antelope::EnumPacker)]
+                pub enum #ident {
+                    #(#variants),*
+                }
+            });
+        }
+
+        let mut actions = Vec::new();
+        for (name, ty) in &self.abi.actions {
+            let constant = const_ident(name);
+            let value = string_to_name(name)?;
+            let function = field_ident(&name.replace('.', "_"));
+            let ty = self.type_tokens(ty, None)?;
+            actions.push(quote! {
+                pub const #constant: ::antelope::chain::name::Name =
+                    ::antelope::chain::name::Name { n: #value };
+
+                pub fn #function(
+                    account: ::antelope::chain::name::Name,
+                    authorization: Vec<::antelope::chain::action::PermissionLevel>,
+                    data: #ty,
+                ) -> ::antelope::chain::action::Action {
+                    ::antelope::chain::action::Action::new_ex(account, #constant, authorization, data)
+                }
+            });
+        }
+
+        let mut tables = Vec::new();
+        for (name, ty) in &self.abi.tables {
+            let constant = const_ident(name);
+            let value = string_to_name(name)?;
+            let row = type_ident(name);
+            let ty = self.scoped_type_tokens(ty, None, &quote! { super:: })?;
+            tables.push(quote! {
+                pub const #constant: ::antelope::chain::name::Name =
+                    ::antelope::chain::name::Name { n: #value };
+
+                pub type #row = #ty;
+            });
+        }
+
+        items.extend(quote! {
+            /// Action names and constructors for each action of the ABI.
+            pub mod actions {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #(#actions)*
+            }
+
+            /// Table names and row types for each table of the ABI.
+            pub mod tables {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #(#tables)*
+            }
+        });
+        Ok(items)
+    }
+
+    /// Base struct fields come first, as in the binary format.
+    fn all_fields(
+        &self,
+        s: &'a StructDef,
+        depth: usize,
+    ) -> Result<Vec<(&'a str, &'a str)>, String> {
+        if depth > 32 {
+            return Err(format!("base struct cycle for {}", s.name));
+        }
+        let mut fields = Vec::new();
+        if !s.base.is_empty() {
+            let base_name = self.resolve(&s.base);
+            let base = self
+                .structs
+                .get(base_name)
+                .ok_or_else(|| format!("unknown base struct {}", s.base))?;
+            fields.extend(self.all_fields(base, depth + 1)?);
+        }
+        fields.extend(s.fields.iter().map(|(n, t)| (n.as_str(), t.as_str())));
+        Ok(fields)
+    }
+
+    fn resolve<'b>(&'b self, ty: &'b str) -> &'b str {
+        let mut ty = ty;
+        for _ in 0..32 {
+            match self.typedefs.get(ty) {
+                Some(next) => ty = next,
+                None => break,
+            }
+        }
+        ty
+    }
+
+    /// Maps an ABI type to a Rust type. `owner` is the struct or variant the
+    /// type is used in, so recursive references can be boxed.
+    fn type_tokens(&self, ty: &str, owner: Option<&str>) -> Result<TokenStream, String> {
+        self.scoped_type_tokens(ty, owner, &TokenStream::new())
+    }
+
+    /// Like `type_tokens`, with the generated types referred to through
+    /// `scope`, such as `super::` from the `tables` module, where a table
+    /// named after its row struct would otherwise shadow it.
+    fn scoped_type_tokens(
+        &self,
+        ty: &str,
+        owner: Option<&str>,
+        scope: &TokenStream,
+    ) -> Result<TokenStream, String> {
+        if let Some(inner) = ty.strip_suffix('$') {
+            let inner = self.boxed_type_tokens(inner, owner, scope)?;
+            return Ok(quote! { ::antelope::chain::binary_extension::BinaryExtension<#inner> });
+        }
+        if let Some(inner) = ty.strip_suffix('?') {
+            let inner = self.boxed_type_tokens(inner, owner, scope)?;
+            return Ok(quote! { Option<#inner> });
+        }
+        if let Some(inner) = ty.strip_suffix("[]") {
+            let inner = self.scoped_type_tokens(inner, None, scope)?;
+            return Ok(quote! { Vec<#inner> });
+        }
+        if let Some(builtin) = builtin_type(ty) {
+            return Ok(builtin);
+        }
+        if self.typedefs.contains_key(ty)
+            || self.structs.contains_key(ty)
+            || self.variants.contains_key(ty)
+        {
+            let ident = type_ident(ty);
+            return Ok(quote! { #scope #ident });
+        }
+        Err(format!("unknown ABI type {ty}"))
+    }
+
+    fn boxed_type_tokens(
+        &self,
+        ty: &str,
+        owner: Option<&str>,
+        scope: &TokenStream,
+    ) -> Result<TokenStream, String> {
+        let tokens = self.scoped_type_tokens(ty, owner, scope)?;
+        match owner {
+            Some(owner) if self.reaches(ty, owner, &mut HashSet::new()) => {
+                Ok(quote! { Box<#tokens> })
+            }
+            _ => Ok(tokens),
+        }
+    }
+
+    /// Whether a value of `ty` directly contains a value of `target`, which
+    /// would make the Rust type infinitely sized without a `Box`.
+    fn reaches(&self, ty: &str, target: &str, seen: &mut HashSet<String>) -> bool {
+        let ty = self.resolve(ty);
+        if ty.ends_with("[]") {
+            return false;
+        }
+        let ty = ty.trim_end_matches(['?', '$']);
+        if ty == target {
+            return true;
+        }
+        if !seen.insert(ty.to_string()) {
+            return false;
+        }
+        if let Some(s) = self.structs.get(ty) {
+            if !s.base.is_empty() && self.reaches(&s.base, target, seen) {
+                return true;
+            }
+            return s.fields.iter().any(|(_, t)| self.reaches(t, target, seen));
+        }
+        if let Some(types) = self.variants.get(ty) {
+            return types.iter().any(|t| self.reaches(t, target, seen));
+        }
+        false
+    }
+}
+
+fn builtin_type(ty: &str) -> Option<TokenStream> {
+    let tokens = match ty {
+        "bool" => quote! { bool },
+        "int8" => quote! { i8 },
+        "uint8" => quote! { u8 },
+        "int16" => quote! { i16 },
+        "uint16" => quote! { u16 },
+        "int32" => quote! { i32 },
+        "uint32" => quote! { u32 },
+        "int64" => quote! { i64 },
+        "uint64" => quote! { u64 },
+        "int128" => quote! { i128 },
+        "uint128" => quote! { u128 },
+        "varint32" => quote! { ::antelope::chain::varint::VarInt32 },
+        "varuint32" => quote! { ::antelope::chain::varint::VarUint32 },
+        "float32" => quote! { f32 },
+        "float64" => quote! { f64 },
+        "float128" => quote! { ::antelope::serializer::packer::Float128 },
+        "time_point" => quote! { ::antelope::chain::time::TimePoint },
+        "time_point_sec" => quote! { ::antelope::chain::time::TimePointSec },
+        "block_timestamp_type" => quote! { ::antelope::chain::time::BlockTimestamp },
+        "name" => quote! { ::antelope::chain::name::Name },
+        "bytes" => quote! { Vec<u8> },
+        "string" => quote! { String },
+        "checksum160" => quote! { ::antelope::chain::checksum::Checksum160 },
+        "checksum256" => quote! { ::antelope::chain::checksum::Checksum256 },
+        "checksum512" => quote! { ::antelope::chain::checksum::Checksum512 },
+        "public_key" => quote! { ::antelope::chain::public_key::PublicKey },
+        "signature" => quote! { ::antelope::chain::signature::Signature },
+        "symbol" => quote! { ::antelope::chain::asset::Symbol },
+        "symbol_code" => quote! { ::antelope::chain::asset::SymbolCode },
+        "asset" => quote! { ::antelope::chain::asset::Asset },
+        "extended_asset" => quote! { ::antelope::chain::asset::ExtendedAsset },
+        _ => return None,
+    };
+    Some(tokens)
+}
+
+/// `bool_types` becomes `BoolTypes`.
+fn type_ident(name: &str) -> Ident {
+    let mut result = String::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            result.push(first.to_ascii_uppercase());
+            result.extend(chars);
+        }
+    }
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, 'T');
+    }
+    format_ident!("{}", result)
+}
+
+fn field_ident(name: &str) -> Ident {
+    let mut result: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    match result.as_str() {
+        "self" | "Self" | "super" | "crate" => format_ident!("{}_", result),
+        r if KEYWORDS.contains(&r) => Ident::new_raw(r, Span::call_site()),
+        _ => format_ident!("{}", result),
+    }
+}
+
+fn const_ident(name: &str) -> Ident {
+    let ident = field_ident(name).to_string();
+    format_ident!("{}", ident.trim_start_matches("r#").to_ascii_uppercase())
+}
+
+fn char_to_index(c: u8) -> Option<u64> {
+    match c {
+        b'a'..=b'z' => Some((c - b'a') as u64 + 6),
+        b'1'..=b'5' => Some((c - b'1') as u64 + 1),
+        b'.' => Some(0),
+        _ => None,
+    }
+}
+
+fn string_to_name(s: &str) -> Result<u64, String> {
+    let bytes = s.as_bytes();
+    if bytes.len() > 13 {
+        return Err(format!("invalid name {s}"));
+    }
+    let mut value = 0u64;
+    for (i, c) in bytes.iter().enumerate() {
+        let index = char_to_index(*c).ok_or_else(|| format!("invalid name {s}"))?;
+        if i < 12 {
+            value |= (index & 0x1f) << (64 - 5 * (i + 1));
+        } else if index > 0x0f {
+            return Err(format!("invalid name {s}"));
+        } else {
+            value |= index;
+        }
+    }
+    Ok(value)
+}
@@ -1,170 +1,747 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Fields};
+use syn::{parse_macro_input, Attribute, DeriveInput, Fields, LitStr};
 
-#[proc_macro_derive(StructPacker)]
+mod abi;
+
+/// Implements `serializer::Packer` for a struct, packing the fields in
+/// declaration order.
+///
+/// Fields marked `#[packer(skip)]` are left out and keep their value when
+/// unpacking. Binary extensions, either `BinaryExtension<T>` fields or
+/// `Option<T>` fields marked `#[packer(extension)]`, must come last and are
+/// only read when data remains.
+#[proc_macro_derive(StructPacker, attributes(packer))]
 pub fn struct_packer_macro(input: TokenStream) -> TokenStream {
-    // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
+    struct_packer_impl(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
 
-    // Build the trait implementation
-    let name = input.ident;
-    let fields = match input.data {
-        syn::Data::Struct(s) => match s.fields {
-            Fields::Named(fields) => fields.named,
-            Fields::Unnamed(fields) => fields.unnamed,
-            Fields::Unit => panic!("Unit structs are not supported"),
-        },
-        _ => panic!("StructPacker can only be derived for structs"),
+fn struct_packer_impl(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        syn::Data::Struct(s) => &s.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "StructPacker can only be derived for structs",
+            ))
+        }
     };
 
-    let size_fields = fields.iter().map(|f| {
-        let field_name = &f.ident;
-        quote! {
-            _size += self.#field_name.size();
+    let mut size_fields = vec![];
+    let mut pack_fields = vec![];
+    let mut unpack_fields = vec![];
+    let mut extensions = false;
+    for (i, f) in fields.iter().enumerate() {
+        let attrs = packer_attrs(&f.attrs)?;
+        if attrs.skip {
+            continue;
         }
-    });
+        // tuple struct fields are accessed by index
+        let field_name = match &f.ident {
+            Some(ident) => quote! { #ident },
+            None => {
+                let index = syn::Index::from(i);
+                quote! { #index }
+            }
+        };
+        let label = match &f.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        };
 
-    let pack_fields = fields.iter().map(|f| {
-        let field_name = &f.ident;
-        quote! {
-            self.#field_name.pack(enc);
+        if attrs.extension {
+            extensions = true;
+            size_fields.push(quote! {
+                if let Some(v) = &self.#field_name {
+                    _size += ::antelope::serializer::Packer::size(v);
+                }
+            });
+            pack_fields.push(quote! {
+                if let Some(v) = &self.#field_name {
+                    ::antelope::serializer::Packer::pack(v, enc);
+                }
+            });
+            unpack_fields.push(quote! {
+                self.#field_name = if dec.remaining() > 0 {
+                    let mut v = Default::default();
+                    dec.unpack(&mut v).map_err(|e| e.in_field(#label))?;
+                    Some(v)
+                } else {
+                    None
+                };
+            });
+            continue;
         }
-    });
 
-    let unpack_fields = fields.iter().map(|f| {
-        let field_name = &f.ident;
-        quote! {
-            dec.unpack(&mut self.#field_name);
+        if is_binary_extension(&f.ty) {
+            extensions = true;
+        } else if extensions {
+            return Err(syn::Error::new_spanned(
+                f,
+                "binary extensions must be the trailing fields",
+            ));
         }
-    });
+        size_fields.push(quote! {
+            _size += ::antelope::serializer::Packer::size(&self.#field_name);
+        });
+        pack_fields.push(quote! {
+            ::antelope::serializer::Packer::pack(&self.#field_name, enc);
+        });
+        unpack_fields.push(quote! {
+            dec.unpack(&mut self.#field_name)
+                .map_err(|e| e.in_field(#label))?;
+        });
+    }
 
-    let expanded = quote! {
-        // Generate the code to be added
-        impl Packer for #name {
+    let generics = add_packer_bounds(input.generics.clone());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::antelope::serializer::Packer for #name #ty_generics #where_clause {
             fn size(&self) -> usize {
                 let mut _size: usize = 0;
                 #(#size_fields)*
                 _size
             }
 
-            fn pack(&self, enc: &mut Encoder) -> usize {
+            fn pack(&self, enc: &mut ::antelope::serializer::Encoder) -> usize {
                 let pos = enc.get_size();
                 #(#pack_fields)*
                 enc.get_size() - pos
             }
 
-            fn unpack(&mut self, data: &[u8]) -> usize {
-                let mut dec = Decoder::new(data);
+            fn unpack(&mut self, data: &[u8]) -> Result<usize, ::antelope::serializer::DecodeError> {
+                let mut dec = ::antelope::serializer::Decoder::new(data);
                 #(#unpack_fields)*
-                dec.get_pos()
+                Ok(dec.get_pos())
             }
         }
-    };
-
-    // Return the generated implementation
-    TokenStream::from(expanded)
+    })
 }
 
-#[proc_macro_derive(EnumPacker)]
+/// Implements `serializer::Packer` and `Default` for an enum, packing the
+/// variant index as a `u8` followed by the variant fields.
+///
+/// Indices follow declaration order unless set with `#[packer(index = N)]`,
+/// after which counting continues from `N`. `Default` is the first variant,
+/// or the one marked `#[packer(default)]`, with all fields defaulted.
+#[proc_macro_derive(EnumPacker, attributes(packer))]
 pub fn enum_packer_macro(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    enum_packer_impl(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn enum_packer_impl(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &input.ident;
+    let data_enum = match &input.data {
+        syn::Data::Enum(data_enum) => data_enum,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "EnumPacker can only be derived for enums",
+            ))
+        }
+    };
+    if data_enum.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "EnumPacker needs at least one variant",
+        ));
+    }
 
-    let gen = match input.data {
-        syn::Data::Enum(data_enum) => {
-            let size_variants = data_enum.variants.iter().map(|variant| {
-                let variant_ident = &variant.ident;
-                match &variant.fields {
-                    Fields::Unnamed(fields) => {
-                        if fields.unnamed.len() != 1 {
-                            panic!("Each variant must have exactly one field implementing the Packer trait.");
-                        }
-                        quote! {
-                            #name::#variant_ident(x) => { _size = 1 + x.size(); }
-                        }
-                    },
-                    _ => panic!("Only unnamed fields are supported"),
+    let mut size_variants = vec![];
+    let mut pack_variants = vec![];
+    let mut unpack_variants = vec![];
+    let mut default_variant = None;
+    let indices = variant_indices(data_enum)?;
+    for (variant, index) in data_enum.variants.iter().zip(indices) {
+        let attrs = packer_attrs(&variant.attrs)?;
+        let variant_ident = &variant.ident;
+        let variant_name = variant_ident.to_string();
+        let bindings: Vec<_> = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => ident.clone(),
+                None => quote::format_ident!("x{}", i),
+            })
+            .collect();
+        let types = variant.fields.iter().map(|f| &f.ty);
+        let pattern = match &variant.fields {
+            Fields::Named(_) => quote! { #name::#variant_ident { #(#bindings),* } },
+            Fields::Unnamed(_) => quote! { #name::#variant_ident(#(#bindings),*) },
+            Fields::Unit => quote! { #name::#variant_ident },
+        };
+        // a single tuple field is reported as the variant itself
+        let single = matches!(&variant.fields, Fields::Unnamed(f) if f.unnamed.len() == 1);
+        let labels = variant.fields.iter().enumerate().map(|(i, f)| {
+            if single {
+                return quote! { .map_err(|e| e.in_field(#variant_name)) };
+            }
+            let field = match &f.ident {
+                Some(ident) => ident.to_string(),
+                None => i.to_string(),
+            };
+            quote! { .map_err(|e| e.in_field(#field).in_field(#variant_name)) }
+        });
+
+        size_variants.push(quote! {
+            #pattern => {
+                #( _size += ::antelope::serializer::Packer::size(#bindings); )*
+            }
+        });
+        pack_variants.push(quote! {
+            #pattern => {
+                ::antelope::serializer::Packer::pack(&#index, enc);
+                #( ::antelope::serializer::Packer::pack(#bindings, enc); )*
+            }
+        });
+        unpack_variants.push(quote! {
+            #index => {
+                #(
+                    let mut #bindings: #types = Default::default();
+                    dec.unpack(&mut #bindings)#labels?;
+                )*
+                *self = #pattern;
+            }
+        });
+
+        if attrs.default {
+            if default_variant.is_some() {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "only one variant can be the default",
+                ));
+            }
+            default_variant = Some(variant);
+        }
+    }
+
+    let default_variant = default_variant.unwrap_or(&data_enum.variants[0]);
+    let default_ident = &default_variant.ident;
+    let defaults = default_variant.fields.iter().map(|f| match &f.ident {
+        Some(ident) => quote! { #ident: Default::default() },
+        None => quote! { Default::default() },
+    });
+    let default_value = match &default_variant.fields {
+        Fields::Named(_) => quote! { #name::#default_ident { #(#defaults),* } },
+        Fields::Unnamed(_) => quote! { #name::#default_ident(#(#defaults),*) },
+        Fields::Unit => quote! { #name::#default_ident },
+    };
+
+    let generics = add_packer_bounds(input.generics.clone());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics Default for #name #ty_generics #where_clause {
+            #[doc = r""]
+            #[inline]
+            fn default() -> Self {
+                #default_value
+            }
+        }
+
+        impl #impl_generics ::antelope::serializer::Packer for #name #ty_generics #where_clause {
+            fn size(&self) -> usize {
+                let mut _size: usize = 1;
+                match self {
+                    #(#size_variants)*
                 }
-            });
+                _size
+            }
 
-            let pack_variants = data_enum.variants.iter().enumerate().map(|(i, variant)| {
-                let variant_ident = &variant.ident;
-                quote! {
-                    #name::#variant_ident(x) => {
-                        let mut i: u8 = #i as u8;
-                        i.pack(enc);
-                        x.pack(enc);
+            fn pack(&self, enc: &mut ::antelope::serializer::Encoder) -> usize {
+                let pos = enc.get_size();
+                match self {
+                    #(#pack_variants)*
+                }
+                enc.get_size() - pos
+            }
+
+            fn unpack(&mut self, data: &[u8]) -> Result<usize, ::antelope::serializer::DecodeError> {
+                let mut dec = ::antelope::serializer::Decoder::new(data);
+                let mut variant_type_index: u8 = 0;
+                dec.unpack(&mut variant_type_index)?;
+                match variant_type_index {
+                    #(#unpack_variants)*
+                    _ => {
+                        return Err(::antelope::serializer::DecodeError::bad_variant(
+                            stringify!(#name),
+                            variant_type_index as u64,
+                        ));
                     }
                 }
-            });
+                Ok(dec.get_pos())
+            }
+        }
+    })
+}
 
-            let unpack_variants = data_enum.variants.iter().enumerate().map(|(i, variant)| {
-                let variant_ident = &variant.ident;
-                let variant_type = &variant.fields;
-                let variant_default = match variant_type {
-                    Fields::Unnamed(fields) => {
-                        let ty = &fields.unnamed.first().unwrap().ty;
-                        quote! {
-                            let mut v: #ty = Default::default();
-                            dec.unpack(&mut v);
-                            *self = #name::#variant_ident(v);
+/// Options set with `#[packer(...)]` on a field or variant.
+#[derive(Default)]
+struct PackerAttrs {
+    skip: bool,
+    extension: bool,
+    default: bool,
+    index: Option<usize>,
+}
+
+fn packer_attrs(attrs: &[Attribute]) -> syn::Result<PackerAttrs> {
+    let mut out = PackerAttrs::default();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("packer")) {
+        let meta = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            meta => return Err(syn::Error::new_spanned(meta, "expected #[packer(...)]")),
+        };
+        for nested in meta.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") => {
+                    out.skip = true
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("extension") => {
+                    out.extension = true
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("default") => {
+                    out.default = true
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("index") => {
+                    match nv.lit {
+                        syn::Lit::Int(i) => out.index = Some(i.base10_parse()?),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected an integer")),
+                    }
+                }
+                other => return Err(syn::Error::new_spanned(other, "unknown packer attribute")),
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// The `u8` index of each variant of an `EnumPacker` enum, in declaration
+/// order: one more than the previous variant unless set with
+/// `#[packer(index = N)]`.
+fn variant_indices(data_enum: &syn::DataEnum) -> syn::Result<Vec<u8>> {
+    let mut indices: Vec<u8> = vec![];
+    let mut next_index: usize = 0;
+    for variant in &data_enum.variants {
+        let index = packer_attrs(&variant.attrs)?.index.unwrap_or(next_index);
+        let index = u8::try_from(index)
+            .map_err(|_| syn::Error::new_spanned(variant, "variant index must fit in a u8"))?;
+        if indices.contains(&index) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("duplicate variant index {index}"),
+            ));
+        }
+        indices.push(index);
+        next_index = index as usize + 1;
+    }
+    Ok(indices)
+}
+
+/// The `T` of an `Option<T>` field.
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(p) => p.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_binary_extension(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(p) => p
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "BinaryExtension"),
+        _ => false,
+    }
+}
+
+/// Requires every type parameter to be packable.
+fn add_packer_bounds(mut generics: syn::Generics) -> syn::Generics {
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(::antelope::serializer::Packer));
+        param.bounds.push(syn::parse_quote!(Default));
+    }
+    generics
+}
+
+/// Implements `serializer::UnpackBorrowed` for a struct, or for an
+/// `EnumPacker` style enum.
+///
+/// The first lifetime parameter, if any, is the lifetime of the input buffer,
+/// so `&'a str` and `&'a [u8]` fields borrow from it. The `#[packer(...)]`
+/// attributes are read as by `StructPacker` and `EnumPacker`, with skipped
+/// fields set to their default.
+#[proc_macro_derive(UnpackBorrowed, attributes(packer))]
+pub fn unpack_borrowed_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    unpack_borrowed_impl(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn unpack_borrowed_impl(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+
+    let mut generics = input.generics.clone();
+    let lifetime = match input.generics.lifetimes().next() {
+        Some(def) => def.lifetime.clone(),
+        None => {
+            let lifetime = syn::Lifetime::new("'__de", proc_macro2::Span::call_site());
+            generics.params.insert(
+                0,
+                syn::GenericParam::Lifetime(syn::LifetimeDef::new(lifetime.clone())),
+            );
+            lifetime
+        }
+    };
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(::antelope::serializer::UnpackBorrowed<#lifetime>));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        syn::Data::Struct(s) => {
+            let mut fields = vec![];
+            for (i, f) in s.fields.iter().enumerate() {
+                let attrs = packer_attrs(&f.attrs)?;
+                let label = match &f.ident {
+                    Some(ident) => ident.to_string(),
+                    None => i.to_string(),
+                };
+                let value = if attrs.skip {
+                    quote! { Default::default() }
+                } else if attrs.extension {
+                    quote! {
+                        if dec.remaining() > 0 {
+                            Some(dec.unpack_borrowed().map_err(|e| e.in_field(#label))?)
+                        } else {
+                            None
                         }
                     }
-                    _ => panic!("Only unnamed fields are supported"),
+                } else {
+                    quote! {
+                        dec.unpack_borrowed().map_err(|e| e.in_field(#label))?
+                    }
+                };
+                fields.push(match &f.ident {
+                    Some(ident) => quote! { #ident: #value },
+                    None => value,
+                });
+            }
+            let value = match &s.fields {
+                Fields::Named(_) => quote! { Self { #(#fields),* } },
+                Fields::Unnamed(_) => quote! { Self(#(#fields),*) },
+                Fields::Unit => quote! { Self },
+            };
+            quote! {
+                let value = #value;
+            }
+        }
+        syn::Data::Enum(e) => {
+            let indices = variant_indices(e)?;
+            let variants = e.variants.iter().zip(indices).map(|(variant, index)| {
+                let variant_ident = &variant.ident;
+                let variant_name = variant_ident.to_string();
+                let bindings: Vec<_> = variant
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| match &f.ident {
+                        Some(ident) => ident.clone(),
+                        None => quote::format_ident!("x{}", i),
+                    })
+                    .collect();
+                let pattern = match &variant.fields {
+                    Fields::Named(_) => quote! { #name::#variant_ident { #(#bindings),* } },
+                    Fields::Unnamed(_) => quote! { #name::#variant_ident(#(#bindings),*) },
+                    Fields::Unit => quote! { #name::#variant_ident },
                 };
+                // a single tuple field is reported as the variant itself
+                let single = matches!(&variant.fields, Fields::Unnamed(f) if f.unnamed.len() == 1);
+                let labels = variant.fields.iter().enumerate().map(|(i, f)| {
+                    if single {
+                        return quote! { .map_err(|e| e.in_field(#variant_name)) };
+                    }
+                    let field = match &f.ident {
+                        Some(ident) => ident.to_string(),
+                        None => i.to_string(),
+                    };
+                    quote! { .map_err(|e| e.in_field(#field).in_field(#variant_name)) }
+                });
                 quote! {
-                    #i => {
-                        #variant_default
+                    #index => {
+                        #( let #bindings = dec.unpack_borrowed()#labels?; )*
+                        #pattern
                     }
                 }
             });
+            let variants = variants.collect::<Vec<_>>();
+            quote! {
+                let mut variant_type_index: u8 = 0;
+                dec.unpack(&mut variant_type_index)?;
+                let value = match variant_type_index {
+                    #(#variants)*
+                    index => {
+                        return Err(::antelope::serializer::DecodeError::bad_variant(
+                            stringify!(#name),
+                            index as u64,
+                        ));
+                    }
+                };
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "UnpackBorrowed can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::antelope::serializer::UnpackBorrowed<#lifetime> for #name #ty_generics
+        #where_clause
+        {
+            fn unpack_borrowed(
+                data: &#lifetime [u8],
+            ) -> Result<(Self, usize), ::antelope::serializer::DecodeError> {
+                let mut dec = ::antelope::serializer::Decoder::new(data);
+                #body
+                Ok((value, dec.get_pos()))
+            }
+        }
+    })
+}
 
-            let default_variant = &data_enum.variants[0];
-            let default_variant_ident = &default_variant.ident;
+/// Implements `chain::abi::AbiType`, describing the type as an ABI struct
+/// (for structs) or variant (for `EnumPacker` style enums).
+///
+/// The ABI name is the snake_case type name unless set with
+/// `#[abi(name = "...")]`, which also renames fields. Fields marked
+/// `#[packer(skip)]` are left out, `#[packer(extension)]` fields are binary
+/// extensions, and variants are listed by their `#[packer(index = N)]`,
+/// which must then run from 0 without gaps.
+#[proc_macro_derive(AbiType, attributes(abi, packer))]
+pub fn abi_type_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    abi_type_impl(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn abi_type_impl(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let abi_name = abi_name_attr(&input.attrs)?.unwrap_or_else(|| to_snake_case(&name.to_string()));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
+    let definitions = match &input.data {
+        syn::Data::Struct(s) => {
+            let fields = match &s.fields {
+                Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+                Fields::Unit => vec![],
+                Fields::Unnamed(fields) => {
+                    return Err(syn::Error::new_spanned(
+                        fields,
+                        "AbiType cannot be derived for tuple structs",
+                    ))
+                }
+            };
+            let mut abi_fields = vec![];
+            let mut field_types = vec![];
+            for f in fields {
+                let attrs = packer_attrs(&f.attrs)?;
+                if attrs.skip {
+                    continue;
+                }
+                let ident = f.ident.as_ref().unwrap().to_string();
+                let field_name = abi_name_attr(&f.attrs)?
+                    .unwrap_or_else(|| ident.trim_start_matches("r#").to_string());
+                let ty = &f.ty;
+                let abi_type = if attrs.extension {
+                    let inner = option_inner(ty).ok_or_else(|| {
+                        syn::Error::new_spanned(ty, "extension fields must be an Option")
+                    })?;
+                    quote! {
+                        format!("{}$", <#inner as ::antelope::chain::abi::AbiType>::abi_type())
+                    }
+                } else {
+                    quote! { <#ty as ::antelope::chain::abi::AbiType>::abi_type() }
+                };
+                abi_fields.push(quote! {
+                    ::antelope::chain::abi::AbiField {
+                        name: String::from(#field_name),
+                        r#type: #abi_type,
+                    }
+                });
+                field_types.push(ty);
+            }
             quote! {
-                impl Default for #name {
-                    #[doc = r""]
-                    #[inline]
-                    fn default() -> Self {
-                        #name::#default_variant_ident(Default::default())
+                if abi.structs.iter().any(|s| s.name == #abi_name) {
+                    return;
+                }
+                abi.structs.push(::antelope::chain::abi::AbiStruct {
+                    name: String::from(#abi_name),
+                    base: String::new(),
+                    fields: vec![#(#abi_fields),*],
+                });
+                #( <#field_types as ::antelope::chain::abi::AbiType>::add_abi_definitions(abi); )*
+            }
+        }
+        syn::Data::Enum(data_enum) => {
+            let indices = variant_indices(data_enum)?;
+            let mut variants = vec![];
+            for (variant, index) in data_enum.variants.iter().zip(indices) {
+                match &variant.fields {
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                        variants.push((index, variant, &fields.unnamed.first().unwrap().ty));
+                    }
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            variant,
+                            "each variant must have exactly one unnamed field",
+                        ))
                     }
                 }
+            }
+            // an ABI variant is indexed by its position in `types`
+            variants.sort_by_key(|(index, _, _)| *index);
+            for (position, (index, variant, _)) in variants.iter().enumerate() {
+                if *index as usize != position {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        format!("variant index {index} leaves a gap in the ABI variant"),
+                    ));
+                }
+            }
+            let variant_types: Vec<_> = variants.iter().map(|(_, _, ty)| ty).collect();
+            quote! {
+                if abi.variants.iter().any(|v| v.name == #abi_name) {
+                    return;
+                }
+                abi.variants.push(::antelope::chain::abi::AbiVariant {
+                    name: String::from(#abi_name),
+                    types: vec![#( <#variant_types as ::antelope::chain::abi::AbiType>::abi_type() ),*],
+                });
+                #( <#variant_types as ::antelope::chain::abi::AbiType>::add_abi_definitions(abi); )*
+            }
+        }
+        syn::Data::Union(u) => {
+            return Err(syn::Error::new_spanned(
+                u.union_token,
+                "AbiType cannot be derived for unions",
+            ))
+        }
+    };
 
-                impl ::antelope::serializer::Packer for #name {
-                    fn size(&self) -> usize {
-                        let mut _size: usize = 0;
-                        match self {
-                            #( #size_variants ),*
-                        }
-                        _size
-                    }
+    Ok(quote! {
+        impl #impl_generics ::antelope::chain::abi::AbiType for #name #ty_generics #where_clause {
+            fn abi_type() -> String {
+                String::from(#abi_name)
+            }
 
-                    fn pack(&self, enc: &mut ::antelope::chain::Encoder) -> usize {
-                        let pos = enc.get_size();
-                        match self {
-                            #( #pack_variants ),*
-                        }
-                        enc.get_size() - pos
-                    }
+            fn add_abi_definitions(abi: &mut ::antelope::chain::abi::ABI) {
+                #definitions
+            }
+        }
+    })
+}
 
-                    fn unpack<'a>(&mut self, data: &'a [u8]) -> usize {
-                        let mut dec = ::antelope::chain::Decoder::new(data);
-                        let mut variant_type_index: u8 = 0;
-                        dec.unpack(&mut variant_type_index);
-                        let variant_type_index = variant_type_index as usize;
-                        match variant_type_index {
-                            #( #unpack_variants ),*
-                            _ => { panic!("bad variant index!"); }
-                        }
-                        dec.get_pos()
+/// Reads `name` from an `#[abi(name = "...")]` attribute.
+fn abi_name_attr(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|a| a.path.is_ident("abi")) {
+        let meta = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected #[abi(name = \"...\")]",
+                ))
+            }
+        };
+        for nested in meta.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                    match nv.lit {
+                        syn::Lit::Str(s) => name = Some(s.value()),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
                     }
                 }
+                other => return Err(syn::Error::new_spanned(other, "unknown abi attribute")),
             }
         }
-        _ => panic!("EnumPacker can only be derived for enums"),
-    };
+    }
+    Ok(name)
+}
+
+fn to_snake_case(s: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
 
-    TokenStream::from(gen)
+/// Generates Rust types from a contract ABI file, with the path relative to
+/// the crate's `Cargo.toml`.
+///
+/// Every ABI struct becomes a `StructPacker` struct (base struct fields are
+/// inlined), every variant an `EnumPacker` enum and every typedef a type
+/// alias. Names are converted to `UpperCamelCase`, so `bool_types` becomes
+/// `BoolTypes`. An `actions` module holds a name constant and a constructor
+/// returning a `chain::action::Action` for each action, and a `tables` module
+/// holds a name constant and row type for each table.
+///
+/// The generated items are placed at the call site, so invoke the macro
+/// inside its own module:
+///
+/// ```ignore
+/// mod token {
+///     antelope::antelope_abi!("abi/eosio.token.abi");
+/// }
+///
+/// let action = token::actions::transfer(
+///     name!("eosio.token"),
+///     vec![PermissionLevel::new(name!("alice"), name!("active"))],
+///     token::Transfer { from, to, quantity, memo },
+/// );
+/// ```
+#[proc_macro]
+pub fn antelope_abi(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    abi::expand(path)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }